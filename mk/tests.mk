@@ -648,6 +648,12 @@ ifdef CFG_ENABLE_DEBUGINFO_TESTS
 CTEST_RUSTC_FLAGS += -g
 endif
 
+# Run the test suite through the MIR backend (`-Z orbit`) rather than the
+# AST-based translation.
+ifdef CFG_ENABLE_ORBIT_TESTS
+CTEST_RUSTC_FLAGS += -Z orbit
+endif
+
 CTEST_COMMON_ARGS$(1)-T-$(2)-H-$(3) := \
 		--compile-lib-path $$(HLIB$(1)_H_$(3)) \
         --run-lib-path $$(TLIB$(1)_T_$(2)_H_$(3)) \
@@ -0,0 +1,188 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The dependency graph used by incremental compilation.
+//!
+//! Each result the compiler computes for a piece of the crate (the type
+//! check of a fn body, its MIR, the translation of an item into a codegen
+//! unit, ...) is produced inside a *task* identified by a `DepNode`. While a
+//! task is running, every `read` of another node records an edge from that
+//! node to the task. Reading a `DepNode::Hir` node means "this result
+//! depends on the source of that item".
+//!
+//! Once compilation is done, the transitive `Hir` inputs of a result can be
+//! queried with `DepGraph::hir_inputs`; `-Z incremental` persists these
+//! (keyed by def-path, not `DefId`, since def-ids are not stable across
+//! compilations) so that the next compilation can tell which results are
+//! still valid.
+//!
+//! When incremental compilation is disabled the graph is a no-op, so the
+//! `read`s and tasks scattered through the compiler cost next to nothing.
+
+use middle::def_id::DefId;
+use util::nodemap::{FnvHashMap, FnvHashSet};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DepNode {
+    /// Represents the crate as a whole; read by results which depend on
+    /// things that are not attributable to a single item (crate attributes,
+    /// upstream crates, command line options, ...).
+    Krate,
+
+    /// The source of a local item.
+    Hir(DefId),
+
+    /// Type checking the body of a local fn, method or constant.
+    TypeckItemBody(DefId),
+
+    /// Building the MIR of a local fn or method.
+    Mir(DefId),
+
+    /// Translating a local item (including its monomorphizations and
+    /// any glue it needs) into the current codegen unit.
+    TransCrateItem(DefId),
+
+    /// The contents of the object file produced for codegen unit `n`.
+    CodegenUnit(usize),
+}
+
+#[derive(Clone)]
+pub struct DepGraph {
+    data: Rc<DepGraphData>,
+}
+
+struct DepGraphData {
+    enabled: bool,
+
+    /// Edges from source to target: `target` read `source`.
+    edges: RefCell<FnvHashMap<DepNode, FnvHashSet<DepNode>>>,
+
+    /// The tasks which are currently running, innermost last.
+    task_stack: RefCell<Vec<DepNode>>,
+}
+
+impl DepGraph {
+    pub fn new(enabled: bool) -> DepGraph {
+        DepGraph {
+            data: Rc::new(DepGraphData {
+                enabled: enabled,
+                edges: RefCell::new(FnvHashMap()),
+                task_stack: RefCell::new(vec![]),
+            })
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.data.enabled
+    }
+
+    /// Starts the task `node`; it ends when the returned guard is dropped.
+    pub fn in_task<'graph>(&'graph self, node: DepNode) -> Option<DepTask<'graph>> {
+        if !self.data.enabled {
+            return None;
+        }
+        self.data.task_stack.borrow_mut().push(node);
+        Some(DepTask { graph: self, node: node })
+    }
+
+    pub fn with_task<OP, R>(&self, node: DepNode, op: OP) -> R
+        where OP: FnOnce() -> R
+    {
+        let _task = self.in_task(node);
+        op()
+    }
+
+    /// Records that the current task (if any) read `node`.
+    pub fn read(&self, node: DepNode) {
+        if !self.data.enabled {
+            return;
+        }
+        let target = match self.data.task_stack.borrow().last() {
+            Some(&target) => target,
+            None => return,
+        };
+        if target != node {
+            self.data.edges.borrow_mut().entry(target).or_insert_with(FnvHashSet).insert(node);
+        }
+    }
+
+    /// Every `Hir` node `node` transitively depends upon, in no particular
+    /// order.
+    pub fn hir_inputs(&self, node: DepNode) -> Vec<DefId> {
+        let edges = self.data.edges.borrow();
+        let mut visited = FnvHashSet();
+        let mut stack = vec![node];
+        let mut result = vec![];
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node) {
+                continue;
+            }
+            if let DepNode::Hir(def_id) = node {
+                result.push(def_id);
+            }
+            if let Some(sources) = edges.get(&node) {
+                stack.extend(sources.iter().cloned());
+            }
+        }
+        result
+    }
+}
+
+pub struct DepTask<'graph> {
+    graph: &'graph DepGraph,
+    node: DepNode,
+}
+
+impl<'graph> Drop for DepTask<'graph> {
+    fn drop(&mut self) {
+        let popped = self.graph.data.task_stack.borrow_mut().pop();
+        assert_eq!(popped, Some(self.node));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DepGraph, DepNode};
+    use middle::def_id::{DefId, DefIndex, LOCAL_CRATE};
+
+    fn def(index: usize) -> DefId {
+        DefId { krate: LOCAL_CRATE, index: DefIndex::new(index) }
+    }
+
+    #[test]
+    fn test_hir_inputs_are_transitive() {
+        let graph = DepGraph::new(true);
+        graph.with_task(DepNode::CodegenUnit(0), || {
+            graph.read(DepNode::TransCrateItem(def(1)));
+        });
+        graph.with_task(DepNode::TransCrateItem(def(1)), || {
+            graph.read(DepNode::Hir(def(1)));
+            graph.read(DepNode::Hir(def(2)));
+        });
+        graph.with_task(DepNode::CodegenUnit(1), || {
+            graph.read(DepNode::Hir(def(3)));
+        });
+        let mut inputs = graph.hir_inputs(DepNode::CodegenUnit(0));
+        inputs.sort();
+        assert_eq!(inputs, vec![def(1), def(2)]);
+    }
+
+    #[test]
+    fn test_disabled_graph_records_nothing() {
+        let graph = DepGraph::new(false);
+        graph.with_task(DepNode::CodegenUnit(0), || {
+            graph.read(DepNode::Hir(def(1)));
+        });
+        assert!(graph.hir_inputs(DepNode::CodegenUnit(0)).is_empty());
+    }
+}
@@ -2269,6 +2269,27 @@ each method; it is not possible to annotate the entire impl with an `#[inline]`
 attribute.
 "##,
 
+E0519: r##"
+This error indicates that an `#[overflow_checks(..)]` attribute was placed on
+something other than a function, method, impl or module.
+
+Examples of erroneous code:
+
+```
+#[overflow_checks(off)]
+struct Foo;
+
+#[overflow_checks(on)]
+static BAR: u32 = 1 + 1;
+```
+
+`#[overflow_checks(on)]` and `#[overflow_checks(off)]` decide whether integer
+arithmetic panics on overflow in the functions they are applied to, or in every
+function inside the impl or module they are applied to. Apply the attribute to
+the function computing the value instead. Closures can't have the attribute
+either; they take the setting of the function they are defined in.
+"##,
+
 }
 
 
@@ -11,7 +11,7 @@
 use session::Session;
 
 use syntax::ast;
-use syntax::attr::AttrMetaMethods;
+use syntax::attr::{self, AttrMetaMethods, ThinAttributesExt};
 use syntax::visit;
 use syntax::visit::Visitor;
 
@@ -20,6 +20,8 @@ enum Target {
     Fn,
     Struct,
     Enum,
+    Impl,
+    Mod,
     Other,
 }
 
@@ -29,6 +31,8 @@ impl Target {
             ast::ItemFn(..) => Target::Fn,
             ast::ItemStruct(..) => Target::Struct,
             ast::ItemEnum(..) => Target::Enum,
+            ast::ItemImpl(..) => Target::Impl,
+            ast::ItemMod(..) => Target::Mod,
             _ => Target::Other,
         }
     }
@@ -85,25 +89,70 @@ impl<'a> CheckAttrVisitor<'a> {
         }
     }
 
+    fn check_overflow_checks(&self, attr: &ast::Attribute, target: Target) {
+        match target {
+            Target::Fn | Target::Impl | Target::Mod => {}
+            _ => span_err!(self.sess, attr.span, E0519,
+                           "attribute should be applied to function, impl or module"),
+        }
+    }
+
     fn check_attribute(&self, attr: &ast::Attribute, target: Target) {
         let name: &str = &attr.name();
         match name {
             "inline" => self.check_inline(attr, target),
             "repr" => self.check_repr(attr, target),
+            "overflow_checks" => self.check_overflow_checks(attr, target),
             _ => (),
         }
     }
+
+    fn check_attributes(&self, attrs: &[ast::Attribute], target: Target) {
+        for attr in attrs {
+            self.check_attribute(attr, target);
+        }
+        attr::find_overflow_checks(Some(self.sess.diagnostic()), attrs);
+    }
 }
 
 impl<'a, 'v> Visitor<'v> for CheckAttrVisitor<'a> {
     fn visit_item(&mut self, item: &ast::Item) {
-        let target = Target::from_item(item);
-        for attr in &item.attrs {
-            self.check_attribute(attr, target);
-        }
+        self.check_attributes(&item.attrs, Target::from_item(item));
+        visit::walk_item(self, item);
+    }
+
+    fn visit_trait_item(&mut self, trait_item: &ast::TraitItem) {
+        let target = match trait_item.node {
+            ast::MethodTraitItem(..) => Target::Fn,
+            _ => Target::Other,
+        };
+        self.check_attributes(&trait_item.attrs, target);
+        visit::walk_trait_item(self, trait_item);
+    }
+
+    fn visit_impl_item(&mut self, impl_item: &ast::ImplItem) {
+        let target = match impl_item.node {
+            ast::ImplItemKind::Method(..) => Target::Fn,
+            _ => Target::Other,
+        };
+        self.check_attributes(&impl_item.attrs, target);
+        visit::walk_impl_item(self, impl_item);
+    }
+
+    // Only items are looked at when deciding on overflow checks, so an
+    // `#[overflow_checks]` on a closure or other expression is rejected.
+    fn visit_expr(&mut self, expr: &ast::Expr) {
+        self.check_attributes(expr.attrs.as_attr_slice(), Target::Other);
+        visit::walk_expr(self, expr);
+    }
+
+    fn visit_local(&mut self, local: &ast::Local) {
+        self.check_attributes(local.attrs.as_attr_slice(), Target::Other);
+        visit::walk_local(self, local);
     }
 }
 
 pub fn check_crate(sess: &Session, krate: &ast::Crate) {
+    attr::find_overflow_checks(Some(sess.diagnostic()), &krate.attrs);
     visit::walk_crate(&mut CheckAttrVisitor { sess: sess }, krate);
 }
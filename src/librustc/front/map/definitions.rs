@@ -258,3 +258,14 @@ pub fn make_def_path<FN>(start_index: DefIndex, mut get_key: FN) -> DefPath
     result.reverse();
     result
 }
+
+/// A string identifying the definition at `path`. Unlike a `DefId` or a
+/// `NodeId`, it does not change when unrelated parts of the crate are edited.
+pub fn def_path_str(path: &DefPath) -> String {
+    path.iter()
+        .map(|component| {
+            format!("{}[{}]", component.data.as_interned_str(), component.disambiguator)
+        })
+        .collect::<Vec<_>>()
+        .join("::")
+}
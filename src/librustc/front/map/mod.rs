@@ -13,6 +13,7 @@ pub use self::PathElem::*;
 use self::MapEntry::*;
 use self::collector::NodeCollector;
 pub use self::definitions::{Definitions, DefKey, DefPath, DefPathData, DisambiguatedDefPathData};
+pub use self::definitions::def_path_str;
 
 use middle::cstore::InlinedItem;
 use middle::cstore::InlinedItem as II;
@@ -90,6 +90,8 @@ pub mod back {
     pub use rustc_back::svh;
 }
 
+pub mod dep_graph;
+
 pub mod front {
     pub mod check_attr;
     pub mod map;
@@ -152,6 +154,7 @@ pub mod util {
     pub mod nodemap;
     pub mod num;
     pub mod fs;
+    pub mod profiling;
 }
 
 pub mod lib {
@@ -28,10 +28,13 @@ use self::TargetLint::*;
 use middle::privacy::AccessLevels;
 use middle::ty::{self, Ty};
 use session::{early_error, Session};
+use session::config::ErrorOutputType;
 use lint::{Level, LevelSource, Lint, LintId, LintArray, LintPass};
 use lint::{EarlyLintPass, EarlyLintPassObject, LateLintPass, LateLintPassObject};
+use lint::MirLintPassObject;
 use lint::{Default, CommandLine, Node, Allow, Warn, Deny, Forbid};
 use lint::builtin;
+use mir::repr::Mir;
 use util::nodemap::FnvHashMap;
 
 use std::cell::RefCell;
@@ -47,7 +50,7 @@ use rustc_front::hir;
 use rustc_front::util;
 use rustc_front::intravisit as hir_visit;
 use syntax::visit as ast_visit;
-use syntax::diagnostic;
+use syntax::diagnostic::{self, CodeSuggestion};
 
 /// Information about the registered lints.
 ///
@@ -63,6 +66,7 @@ pub struct LintStore {
     /// of run_lints.
     early_passes: Option<Vec<EarlyLintPassObject>>,
     late_passes: Option<Vec<LateLintPassObject>>,
+    mir_passes: Option<Vec<MirLintPassObject>>,
 
     /// Lints indexed by name.
     by_name: FnvHashMap<String, TargetLint>,
@@ -120,6 +124,7 @@ impl LintStore {
             lints: vec!(),
             early_passes: Some(vec!()),
             late_passes: Some(vec!()),
+            mir_passes: Some(vec!()),
             by_name: FnvHashMap(),
             levels: FnvHashMap(),
             lint_groups: FnvHashMap(),
@@ -153,7 +158,15 @@ impl LintStore {
         self.late_passes.as_mut().unwrap().push(pass);
     }
 
-    // Helper method for register_early/late_pass
+    pub fn register_mir_pass(&mut self,
+                             sess: Option<&Session>,
+                             from_plugin: bool,
+                             pass: MirLintPassObject) {
+        self.push_pass(sess, from_plugin, &pass);
+        self.mir_passes.as_mut().unwrap().push(pass);
+    }
+
+    // Helper method for register_early/late/mir_pass
     fn push_pass<P: LintPass + ?Sized + 'static>(&mut self,
                                         sess: Option<&Session>,
                                         from_plugin: bool,
@@ -167,7 +180,9 @@ impl LintStore {
                 match (sess, from_plugin) {
                     // We load builtin lints first, so a duplicate is a compiler bug.
                     // Use early_error when handling -W help with no crate.
-                    (None, _) => early_error(diagnostic::Auto, &msg[..]),
+                    (None, _) => {
+                        early_error(ErrorOutputType::HumanReadable(diagnostic::Auto), &msg[..])
+                    }
                     (Some(sess), false) => sess.bug(&msg[..]),
 
                     // A duplicate name from a plugin is a user error.
@@ -191,7 +206,9 @@ impl LintStore {
             match (sess, from_plugin) {
                 // We load builtin lints first, so a duplicate is a compiler bug.
                 // Use early_error when handling -W help with no crate.
-                (None, _) => early_error(diagnostic::Auto, &msg[..]),
+                (None, _) => {
+                    early_error(ErrorOutputType::HumanReadable(diagnostic::Auto), &msg[..])
+                }
                 (Some(sess), false) => sess.bug(&msg[..]),
 
                 // A duplicate name from a plugin is a user error.
@@ -312,6 +329,24 @@ pub struct EarlyContext<'a> {
     level_stack: Vec<(LintId, LevelSource)>,
 }
 
+/// Context for lint checking of the MIR of a fn.
+pub struct MirContext<'a, 'tcx: 'a> {
+    /// Type context we're checking in.
+    pub tcx: &'a ty::ctxt<'tcx>,
+
+    /// The fn whose MIR is being checked.
+    pub id: ast::NodeId,
+}
+
+impl<'a, 'tcx> MirContext<'a, 'tcx> {
+    /// Emit a lint at `span`. It is only added to the session here, and the
+    /// late lint pass emits it at the level in effect at the fn, so
+    /// attributes inside the fn body do not affect it.
+    pub fn span_lint(&self, lint: &'static Lint, span: Span, msg: &str) {
+        self.tcx.sess.add_lint(lint, self.id, span, msg.to_string());
+    }
+}
+
 /// Convenience macro for calling a `LintPass` method on every pass in the context.
 macro_rules! run_lints { ($cx:expr, $f:ident, $ps:ident, $($args:expr),*) => ({
     // Move the vector of passes out of `$cx` so that we can
@@ -458,6 +493,16 @@ pub trait LintContext: Sized {
         }
     }
 
+    /// Emit a lint and a suggested edit at the appropriate level, for a
+    /// particular span.
+    fn span_lint_suggestion(&self, lint: &'static Lint, span: Span, msg: &str,
+                            suggestion: CodeSuggestion) {
+        self.span_lint(lint, span, msg);
+        if self.current_level(lint) != Level::Allow {
+            self.sess().span_lint_fix(suggestion)
+        }
+    }
+
     /// Emit a lint at the appropriate level, with no associated span.
     fn lint(&self, lint: &'static Lint, msg: &str) {
         self.lookup_and_emit(lint, None, msg);
@@ -969,8 +1014,13 @@ impl<'a, 'tcx> IdVisitingOperation for LateContext<'a, 'tcx> {
             None => {}
             Some(lints) => {
                 debug!("LateContext::visit_id: id={:?} lints={:?}", id, lints);
-                for (lint_id, span, msg) in lints {
-                    self.span_lint(lint_id.lint, span, &msg[..])
+                for (lint_id, span, msg, suggestion) in lints {
+                    match suggestion {
+                        Some(suggestion) => {
+                            self.span_lint_suggestion(lint_id.lint, span, &msg[..], suggestion)
+                        }
+                        None => self.span_lint(lint_id.lint, span, &msg[..]),
+                    }
                 }
             }
         }
@@ -981,8 +1031,13 @@ impl<'a> IdVisitingOperation for EarlyContext<'a> {
         match self.sess.lints.borrow_mut().remove(&id) {
             None => {}
             Some(lints) => {
-                for (lint_id, span, msg) in lints {
-                    self.span_lint(lint_id.lint, span, &msg[..])
+                for (lint_id, span, msg, suggestion) in lints {
+                    match suggestion {
+                        Some(suggestion) => {
+                            self.span_lint_suggestion(lint_id.lint, span, &msg[..], suggestion)
+                        }
+                        None => self.span_lint(lint_id.lint, span, &msg[..]),
+                    }
                 }
             }
         }
@@ -1045,7 +1100,7 @@ pub fn check_crate(tcx: &ty::ctxt, access_levels: &AccessLevels) {
     // If we missed any lints added to the session, then there's a bug somewhere
     // in the iteration code.
     for (id, v) in tcx.sess.lints.borrow().iter() {
-        for &(lint, span, ref msg) in v {
+        for &(lint, span, ref msg, _) in v {
             tcx.sess.span_bug(span,
                               &format!("unprocessed lint {} at {}: {}",
                                        lint.as_str(), tcx.map.node_to_string(*id), *msg))
@@ -1055,6 +1110,23 @@ pub fn check_crate(tcx: &ty::ctxt, access_levels: &AccessLevels) {
     *tcx.node_lint_levels.borrow_mut() = cx.node_levels.into_inner();
 }
 
+/// Perform MIR lint checking on the fn `id`, as its MIR is built and before
+/// any transformation is run on it.
+///
+/// Must run before `check_crate`, which emits the lints found here.
+pub fn check_mir<'tcx>(tcx: &ty::ctxt<'tcx>, id: ast::NodeId, mir: &Mir<'tcx>) {
+    // Move the passes out of the store while they run, as `run_lints!` does.
+    let mut passes = tcx.sess.lint_store.borrow_mut().mir_passes.take().unwrap();
+    let cx = MirContext {
+        tcx: tcx,
+        id: id,
+    };
+    for pass in &mut passes {
+        pass.check_mir(&cx, mir);
+    }
+    tcx.sess.lint_store.borrow_mut().mir_passes = Some(passes);
+}
+
 pub fn check_ast_crate(sess: &Session, krate: &ast::Crate) {
     let mut cx = EarlyContext::new(sess, krate);
 
@@ -1079,7 +1151,7 @@ pub fn check_ast_crate(sess: &Session, krate: &ast::Crate) {
     // If we missed any lints added to the session, then there's a bug somewhere
     // in the iteration code.
     for (_, v) in sess.lints.borrow().iter() {
-        for &(lint, span, ref msg) in v {
+        for &(lint, span, ref msg, _) in v {
             sess.span_bug(span,
                           &format!("unprocessed lint {}: {}",
                                    lint.as_str(), *msg))
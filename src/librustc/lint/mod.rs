@@ -38,9 +38,10 @@ use rustc_front::intravisit::FnKind;
 use syntax::visit as ast_visit;
 use syntax::ast;
 use rustc_front::hir;
+use mir::repr::Mir;
 
-pub use lint::context::{LateContext, EarlyContext, LintContext, LintStore,
-                        raw_emit_lint, check_crate, check_ast_crate, gather_attrs,
+pub use lint::context::{LateContext, EarlyContext, MirContext, LintContext, LintStore,
+                        raw_emit_lint, check_crate, check_ast_crate, check_mir, gather_attrs,
                         GatherNodeLevels};
 
 /// Specification of a single lint.
@@ -213,9 +214,20 @@ pub trait EarlyLintPass: LintPass {
     fn exit_lint_attrs(&mut self, _: &EarlyContext, _: &[ast::Attribute]) { }
 }
 
+/// Trait for lint checks on the MIR of each fn, which, unlike the HIR, makes
+/// control flow and temporaries explicit.
+///
+/// These run on the MIR as built, before any MIR pass transforms it, and
+/// before the late lint pass, which emits what they find at the lint levels
+/// in effect at the fn.
+pub trait MirLintPass: LintPass {
+    fn check_mir<'a, 'tcx>(&mut self, _: &MirContext<'a, 'tcx>, _: &Mir<'tcx>) { }
+}
+
 /// A lint pass boxed up as a trait object.
 pub type EarlyLintPassObject = Box<EarlyLintPass + 'static>;
 pub type LateLintPassObject = Box<LateLintPass + 'static>;
+pub type MirLintPassObject = Box<MirLintPass + 'static>;
 
 /// Identifies a lint known to the compiler.
 #[derive(Clone, Copy, Debug)]
@@ -92,6 +92,18 @@ pub fn lookup_const_by_id<'a, 'tcx: 'a>(tcx: &'a ty::ctxt<'tcx>,
                                         def_id: DefId,
                                         maybe_ref_id: Option<ast::NodeId>)
                                         -> Option<&'tcx Expr> {
+    let maybe_substs = maybe_ref_id.map(|ref_id| tcx.node_id_item_substs(ref_id).substs);
+    lookup_const_by_id_with_substs(tcx, def_id, maybe_substs)
+}
+
+/// Like `lookup_const_by_id`, but takes the substitutions of the reference
+/// directly, for callers (such as MIR trans) which have no expression to
+/// look them up from. These are only needed to select the impl of a
+/// trait-associated const.
+pub fn lookup_const_by_id_with_substs<'a, 'tcx: 'a>(tcx: &'a ty::ctxt<'tcx>,
+                                                    def_id: DefId,
+                                                    maybe_substs: Option<subst::Substs<'tcx>>)
+                                                    -> Option<&'tcx Expr> {
     if let Some(node_id) = tcx.map.as_local_node_id(def_id) {
         match tcx.map.find(node_id) {
             None => None,
@@ -103,16 +115,14 @@ pub fn lookup_const_by_id<'a, 'tcx: 'a>(tcx: &'a ty::ctxt<'tcx>,
             },
             Some(ast_map::NodeTraitItem(ti)) => match ti.node {
                 hir::ConstTraitItem(_, _) => {
-                    match maybe_ref_id {
+                    match maybe_substs {
                         // If we have a trait item, and we know the expression
                         // that's the source of the obligation to resolve it,
                         // `resolve_trait_associated_const` will select an impl
                         // or the default.
-                        Some(ref_id) => {
+                        Some(substs) => {
                             let trait_id = tcx.trait_of_item(def_id)
                                               .unwrap();
-                            let substs = tcx.node_id_item_substs(ref_id)
-                                            .substs;
                             resolve_trait_associated_const(tcx, ti, trait_id,
                                                            substs)
                         }
@@ -152,14 +162,12 @@ pub fn lookup_const_by_id<'a, 'tcx: 'a>(tcx: &'a ty::ctxt<'tcx>,
             cstore::FoundAst::Found(&InlinedItem::TraitItem(trait_id, ref ti)) => match ti.node {
                 hir::ConstTraitItem(_, _) => {
                     used_ref_id = true;
-                    match maybe_ref_id {
+                    match maybe_substs {
                         // As mentioned in the comments above for in-crate
                         // constants, we only try to find the expression for
                         // a trait-associated const if the caller gives us
                         // the expression that refers to it.
-                        Some(ref_id) => {
-                            let substs = tcx.node_id_item_substs(ref_id)
-                                            .substs;
+                        Some(substs) => {
                             resolve_trait_associated_const(tcx, ti, trait_id,
                                                            substs).map(|e| e.id)
                         }
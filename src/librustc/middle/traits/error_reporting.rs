@@ -9,6 +9,8 @@
 // except according to those terms.
 
 use super::{
+    CandidateExplanation,
+    CandidateOutcome,
     FulfillmentError,
     FulfillmentErrorCode,
     MismatchedProjectionTypes,
@@ -17,7 +19,9 @@ use super::{
     OutputTypeParameterMismatch,
     TraitNotObjectSafe,
     PredicateObligation,
+    SelectionContext,
     SelectionError,
+    TraitObligation,
     ObjectSafetyViolation,
     MethodViolationCode,
     object_safety_violations,
@@ -28,9 +32,11 @@ use middle::def_id::DefId;
 use middle::infer::InferCtxt;
 use middle::ty::{self, ToPredicate, HasTypeFlags, ToPolyTraitRef, TraitRef, Ty};
 use middle::ty::fold::TypeFoldable;
+use session::Session;
 use util::nodemap::{FnvHashMap, FnvHashSet};
 
 use std::fmt;
+use std::iter;
 use syntax::codemap::Span;
 use syntax::attr::{AttributeMethods, AttrMetaMethods};
 
@@ -238,6 +244,11 @@ pub fn report_selection_error<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
                                 infcx.tcx.sess.fileline_note(obligation.cause.span, &s);
                             }
                             note_obligation_cause(infcx, obligation);
+
+                            if infcx.tcx.sess.opts.debugging_opts.explain_trait_selection {
+                                let trait_obligation = obligation.with(trait_predicate);
+                                explain_trait_selection(infcx, &trait_obligation);
+                            }
                         }
                     }
 
@@ -325,6 +336,63 @@ pub fn report_selection_error<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
     }
 }
 
+/// How many levels of nested obligations `-Z explain-trait-selection`
+/// explains.
+const EXPLAIN_DEPTH: usize = 4;
+
+/// Notes the candidates which were considered for `obligation`, and why
+/// each of them failed.
+fn explain_trait_selection<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                     obligation: &TraitObligation<'tcx>) {
+    let explanations = SelectionContext::new(infcx).explain_selection(obligation, EXPLAIN_DEPTH);
+    let trait_ref = obligation.predicate.to_poly_trait_ref();
+    let predicate = format!("{}: {}", trait_ref.self_ty(), trait_ref);
+    note_candidates(infcx.tcx.sess, obligation.cause.span, &predicate, &explanations, 0);
+}
+
+fn note_candidates(sess: &Session,
+                   span: Span,
+                   predicate: &str,
+                   explanations: &[CandidateExplanation],
+                   depth: usize) {
+    let indent = iter::repeat("  ").take(depth).collect::<String>();
+    if explanations.is_empty() {
+        sess.fileline_note(span, &format!("{}no candidates were found for `{}`",
+                                          indent, predicate));
+        return;
+    }
+
+    sess.fileline_note(span, &format!("{}candidates considered for `{}`:", indent, predicate));
+    for explanation in explanations {
+        let description = &explanation.description;
+        let msg = match explanation.outcome {
+            CandidateOutcome::Mismatch => format!("{} does not match", description),
+            CandidateOutcome::Negative => format!("{} is a negative impl", description),
+            CandidateOutcome::Rejected(ref reason) => {
+                format!("{} was rejected: {}", description, reason)
+            }
+            CandidateOutcome::NestedFailed(ref nested, _) => {
+                format!("{} requires `{}`, which does not hold", description, nested.predicate)
+            }
+            CandidateOutcome::Ambiguous => {
+                format!("{} may apply, depending on types which are not known yet", description)
+            }
+            CandidateOutcome::Applies => format!("{} applies", description),
+        };
+        let msg = format!("{}- {}", indent, msg);
+        match explanation.span {
+            Some(candidate_span) => sess.span_note(candidate_span, &msg),
+            None => sess.fileline_note(span, &msg),
+        }
+
+        if let CandidateOutcome::NestedFailed(ref nested, Some(ref nested_explanations)) =
+                explanation.outcome {
+            let nested_predicate = nested.predicate.to_string();
+            note_candidates(sess, span, &nested_predicate, nested_explanations, depth + 1);
+        }
+    }
+}
+
 pub fn report_object_safety_error<'tcx>(tcx: &ty::ctxt<'tcx>,
                                         span: Span,
                                         trait_def_id: DefId,
@@ -254,6 +254,33 @@ pub struct EvaluationCache<'tcx> {
     hashmap: RefCell<FnvHashMap<ty::PolyTraitRef<'tcx>, EvaluationResult>>
 }
 
+/// A candidate considered while selecting an impl for an obligation, and
+/// what became of it. See `SelectionContext::explain_selection`.
+pub struct CandidateExplanation<'tcx> {
+    /// A description of the candidate, e.g. "impl `Clone` for `Vec<T>`".
+    pub description: String,
+    /// Where the candidate is defined, if it is in this crate.
+    pub span: Option<Span>,
+    pub outcome: CandidateOutcome<'tcx>,
+}
+
+pub enum CandidateOutcome<'tcx> {
+    /// The types of the impl do not match those of the obligation.
+    Mismatch,
+    /// The impl matches, but it is a negative impl.
+    Negative,
+    /// The candidate matches, but could not be confirmed.
+    Rejected(String),
+    /// The candidate applies only if the nested obligation holds, which it
+    /// does not. If that obligation is itself a trait obligation, the
+    /// candidates considered for it follow.
+    NestedFailed(PredicateObligation<'tcx>, Option<Vec<CandidateExplanation<'tcx>>>),
+    /// Whether the candidate applies depends on types which are not yet
+    /// known.
+    Ambiguous,
+    Applies,
+}
+
 impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
     pub fn new(infcx: &'cx InferCtxt<'cx, 'tcx>)
                -> SelectionContext<'cx, 'tcx> {
@@ -2927,6 +2954,165 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             obligation.cause.clone()
         }
     }
+
+    ///////////////////////////////////////////////////////////////////////////
+    // EXPLANATION
+    //
+    // For `-Z explain-trait-selection`, error reporting asks which
+    // candidates were considered for an obligation that could not be
+    // selected, and why each of them failed.
+
+    /// Lists the candidates for `obligation`: every relevant impl of its
+    /// trait, whether or not it matches, and every where clause, builtin
+    /// rule and so forth which matches. Each candidate which matches is
+    /// confirmed, and its nested obligations are evaluated to find the one
+    /// which does not hold; nested trait obligations are explained in turn,
+    /// up to `depth` levels deep. Nothing is left behind in the inference
+    /// context.
+    pub fn explain_selection(&mut self,
+                             obligation: &TraitObligation<'tcx>,
+                             depth: usize)
+                             -> Vec<CandidateExplanation<'tcx>>
+    {
+        debug!("explain_selection({:?}, depth={})", obligation, depth);
+
+        self.infcx.probe(|_| {
+            let obligation = self.infcx.resolve_type_vars_if_possible(obligation);
+            let stack = self.push_stack(TraitObligationStackList::empty(), &obligation);
+            let candidates = match self.assemble_candidates(&stack) {
+                Ok(candidate_set) => candidate_set.vec,
+                Err(..) => vec![],
+            };
+
+            let mut explanations = vec![];
+            let def = self.tcx().lookup_trait_def(obligation.predicate.def_id());
+            def.for_each_relevant_impl(
+                self.tcx(),
+                obligation.predicate.0.trait_ref.self_ty(),
+                |impl_def_id| {
+                    let candidate = ImplCandidate(impl_def_id);
+                    if !candidates.contains(&candidate) {
+                        let (description, span) = self.describe_candidate(&candidate);
+                        explanations.push(CandidateExplanation {
+                            description: description,
+                            span: span,
+                            outcome: CandidateOutcome::Mismatch,
+                        });
+                    }
+                });
+
+            for candidate in candidates {
+                let (description, span) = self.describe_candidate(&candidate);
+                let outcome = self.explain_candidate(&stack, candidate, depth);
+                explanations.push(CandidateExplanation {
+                    description: description,
+                    span: span,
+                    outcome: outcome,
+                });
+            }
+            explanations
+        })
+    }
+
+    fn explain_candidate<'o>(&mut self,
+                             stack: &TraitObligationStack<'o, 'tcx>,
+                             candidate: SelectionCandidate<'tcx>,
+                             depth: usize)
+                             -> CandidateOutcome<'tcx>
+    {
+        if let ImplCandidate(def_id) = candidate {
+            if let Some(hir::ImplPolarity::Negative) = self.tcx().trait_impl_polarity(def_id) {
+                return CandidateOutcome::Negative;
+            }
+        }
+
+        self.infcx.probe(|_| {
+            let nested = match self.confirm_candidate(stack.obligation, candidate) {
+                Ok(selection) => selection.nested_obligations(),
+                Err(e) => {
+                    return CandidateOutcome::Rejected(self.describe_selection_error(&e));
+                }
+            };
+
+            let mut outcome = CandidateOutcome::Applies;
+            for nested_obligation in &nested {
+                match self.evaluate_predicate_recursively(stack.list(), nested_obligation) {
+                    EvaluatedToErr => {
+                        let nested_obligation =
+                            self.infcx.resolve_type_vars_if_possible(nested_obligation);
+                        let explanation = match nested_obligation.predicate {
+                            ty::Predicate::Trait(ref data) if depth > 0 => {
+                                let trait_obligation = nested_obligation.with(data.clone());
+                                Some(self.explain_selection(&trait_obligation, depth - 1))
+                            }
+                            _ => None,
+                        };
+                        return CandidateOutcome::NestedFailed(nested_obligation, explanation);
+                    }
+                    EvaluatedToAmbig | EvaluatedToUnknown => {
+                        outcome = CandidateOutcome::Ambiguous;
+                    }
+                    EvaluatedToOk => {}
+                }
+            }
+            outcome
+        })
+    }
+
+    fn describe_candidate(&self, candidate: &SelectionCandidate<'tcx>) -> (String, Option<Span>) {
+        match *candidate {
+            ImplCandidate(def_id) |
+            DefaultImplCandidate(def_id) |
+            DefaultImplObjectCandidate(def_id) => {
+                let description = match self.tcx().impl_trait_ref(def_id) {
+                    Some(trait_ref) => {
+                        format!("impl `{}` for `{}`", trait_ref, trait_ref.self_ty())
+                    }
+                    None => format!("impl `{}`", self.tcx().item_path_str(def_id)),
+                };
+                (description, self.tcx().map.span_if_local(def_id))
+            }
+            ParamCandidate(ref trait_ref) => {
+                let trait_ref = self.infcx.resolve_type_vars_if_possible(trait_ref);
+                (format!("the where clause `{}: {}`", trait_ref.self_ty(), trait_ref), None)
+            }
+            BuiltinCandidate(bound) => {
+                let trait_name = match bound {
+                    ty::BoundSend => "Send",
+                    ty::BoundSized => "Sized",
+                    ty::BoundCopy => "Copy",
+                    ty::BoundSync => "Sync",
+                };
+                (format!("the builtin rules for `{}`", trait_name), None)
+            }
+            ProjectionCandidate => {
+                ("a bound in the definition of the projected type".to_string(), None)
+            }
+            ClosureCandidate(def_id, _) => {
+                ("the closure".to_string(), self.tcx().map.span_if_local(def_id))
+            }
+            FnPointerCandidate => ("the builtin impl for fn pointers".to_string(), None),
+            ObjectCandidate | BuiltinObjectCandidate => {
+                ("the builtin impl for trait objects".to_string(), None)
+            }
+            BuiltinUnsizeCandidate => ("the builtin rules for unsizing".to_string(), None),
+            ErrorCandidate => ("an earlier error".to_string(), None),
+        }
+    }
+
+    fn describe_selection_error(&self, error: &SelectionError<'tcx>) -> String {
+        match *error {
+            Unimplemented => "not implemented".to_string(),
+            OutputTypeParameterMismatch(ref expected, ref actual, ref e) => {
+                let expected = self.infcx.resolve_type_vars_if_possible(expected);
+                let actual = self.infcx.resolve_type_vars_if_possible(actual);
+                format!("expected `{}`, found `{}` ({})", expected, actual, e)
+            }
+            TraitNotObjectSafe(def_id) => {
+                format!("the trait `{}` is not object safe", self.tcx().item_path_str(def_id))
+            }
+        }
+    }
 }
 
 impl<'tcx> SelectionCache<'tcx> {
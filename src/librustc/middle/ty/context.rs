@@ -13,6 +13,7 @@
 // FIXME: (@jroesch) @eddyb should remove this when he renames ctxt
 #![allow(non_camel_case_types)]
 
+use dep_graph::DepGraph;
 use front::map as ast_map;
 use session::Session;
 use lint;
@@ -33,6 +34,7 @@ use middle::ty::{FreevarMap, GenericPredicates};
 use middle::ty::{BareFnTy, InferTy, ParamTy, ProjectionTy, TraitTy};
 use middle::ty::{TyVar, TyVid, IntVar, IntVid, FloatVar, FloatVid};
 use middle::ty::TypeVariants::*;
+use mir::repr::Mir;
 use util::nodemap::{NodeMap, NodeSet, DefIdMap, DefIdSet};
 use util::nodemap::FnvHashMap;
 
@@ -317,6 +319,15 @@ pub struct ctxt<'tcx> {
     pub extern_const_statics: RefCell<DefIdMap<NodeId>>,
     pub extern_const_fns: RefCell<DefIdMap<NodeId>>,
 
+    /// The MIR of external fns, decoded from crate metadata the first time
+    /// it is asked for. `None` if the crate did not export MIR for the fn.
+    pub extern_mir: RefCell<DefIdMap<Option<Rc<Mir<'tcx>>>>>,
+
+    /// The `#[overflow_checks]` setting of each fn or method inlined from
+    /// another crate, as resolved in the crate defining it; the impls and
+    /// modules enclosing it there are not part of our map.
+    pub inlined_overflow_checks: RefCell<NodeMap<Option<bool>>>,
+
     pub node_lint_levels: RefCell<FnvHashMap<(NodeId, lint::LintId),
                                               lint::LevelSource>>,
 
@@ -382,6 +393,10 @@ pub struct ctxt<'tcx> {
     /// fragmented data to the set of unfragmented pieces that
     /// constitute it.
     pub fragment_infos: RefCell<DefIdMap<Vec<ty::FragmentInfo>>>,
+
+    /// The dependencies between the results computed for this crate, which
+    /// are only recorded under `-Z incremental`.
+    pub dep_graph: DepGraph,
 }
 
 impl<'tcx> ctxt<'tcx> {
@@ -526,6 +541,8 @@ impl<'tcx> ctxt<'tcx> {
             populated_external_primitive_impls: RefCell::new(DefIdSet()),
             extern_const_statics: RefCell::new(DefIdMap()),
             extern_const_fns: RefCell::new(DefIdMap()),
+            extern_mir: RefCell::new(DefIdMap()),
+            inlined_overflow_checks: RefCell::new(NodeMap()),
             node_lint_levels: RefCell::new(FnvHashMap()),
             transmute_restrictions: RefCell::new(Vec::new()),
             stability: RefCell::new(stability),
@@ -536,6 +553,7 @@ impl<'tcx> ctxt<'tcx> {
             custom_coerce_unsized_kinds: RefCell::new(DefIdMap()),
             cast_kinds: RefCell::new(NodeMap()),
             fragment_infos: RefCell::new(DefIdMap()),
+            dep_graph: DepGraph::new(s.opts.debugging_opts.incremental),
        }, f)
     }
 }
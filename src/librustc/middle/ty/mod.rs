@@ -31,6 +31,7 @@ use middle::traits;
 use middle::ty;
 use middle::ty::fold::TypeFolder;
 use middle::ty::walk::TypeWalker;
+use mir::repr::Mir;
 use util::common::memoized;
 use util::nodemap::{NodeMap, NodeSet, DefIdMap};
 use util::nodemap::FnvHashMap;
@@ -1582,7 +1583,14 @@ impl<'tcx, 'container> AdtDefData<'tcx, 'container> {
         if attr::contains_name(&attrs, "fundamental") {
             flags = flags | AdtFlags::IS_FUNDAMENTAL;
         }
-        if attr::contains_name(&attrs, "unsafe_no_drop_flag") {
+        // Crates translated with `-Z orbit` elaborate their drops on the
+        // MIR, and so lay out their types without drop flags.
+        let crate_has_drop_flags = if did.is_local() {
+            !tcx.sess.opts.debugging_opts.orbit
+        } else {
+            tcx.sess.cstore.has_drop_flags(did.krate)
+        };
+        if !crate_has_drop_flags || attr::contains_name(&attrs, "unsafe_no_drop_flag") {
             flags = flags | AdtFlags::IS_NO_DROP_FLAG;
         }
         if tcx.lookup_simd(did) {
@@ -2347,6 +2355,15 @@ impl<'tcx> ctxt<'tcx> {
             || self.sess.cstore.item_type(self, did))
     }
 
+    /// Looks up the MIR which the crate defining `did` exported for it, which
+    /// it does for `#[inline]` and generic fns. The MIR of the local crate is
+    /// kept in the `MirMap` instead.
+    pub fn lookup_extern_mir(&self, did: DefId) -> Option<Rc<Mir<'tcx>>> {
+        lookup_locally_or_in_crate_store(
+            "extern_mir", did, &self.extern_mir,
+            || self.sess.cstore.maybe_get_item_mir(self, did).map(Rc::new))
+    }
+
     /// Given the did of a trait, returns its canonical trait ref.
     pub fn lookup_trait_def(&self, did: DefId) -> &'tcx TraitDef<'tcx> {
         lookup_locally_or_in_crate_store(
@@ -2424,6 +2441,79 @@ impl<'tcx> ctxt<'tcx> {
         })
     }
 
+    /// Whether the crate metadata carries the body and MIR of the local fn or
+    /// method `def_id`, so that other crates can instantiate, inline or
+    /// evaluate it: generic, `#[inline]` and `const` fns and methods, and
+    /// default trait methods.
+    pub fn exports_body(&self, def_id: DefId) -> bool {
+        let id = match self.map.as_local_node_id(def_id) {
+            Some(id) => id,
+            None => return false,
+        };
+        match self.map.find(id) {
+            Some(ast_map::NodeItem(item)) => match item.node {
+                hir::ItemFn(_, _, constness, _, ref generics, _) => {
+                    !generics.ty_params.is_empty() ||
+                    attr::requests_inline(&item.attrs) ||
+                    constness == hir::Constness::Const
+                }
+                _ => false,
+            },
+            Some(ast_map::NodeImplItem(impl_item)) => match impl_item.node {
+                hir::ImplItemKind::Method(ref sig, _) => {
+                    !self.lookup_item_type(def_id).generics.types.is_empty() ||
+                    attr::requests_inline(&impl_item.attrs) ||
+                    sig.constness == hir::Constness::Const
+                }
+                _ => false,
+            },
+            Some(ast_map::NodeTraitItem(trait_item)) => match trait_item.node {
+                hir::MethodTraitItem(_, ref body) => body.is_some(),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Determine whether arithmetic in the fn, method or closure `id` should
+    /// panic on overflow. The innermost `#[overflow_checks(on|off)]` on an
+    /// enclosing fn, impl or module decides, then the crate's; failing those,
+    /// `-Z force-overflow-checks` or `-C debug-assertions`. Closures can't
+    /// carry the attribute (see `check_attr`), so they take the setting of
+    /// the fn they are defined in.
+    pub fn check_overflow(&self, id: NodeId) -> bool {
+        match self.overflow_checks_attr(id) {
+            Some(on) => on,
+            None => match self.sess.opts.debugging_opts.force_overflow_checks {
+                Some(on) => on,
+                None => self.sess.opts.debug_assertions,
+            },
+        }
+    }
+
+    /// The `#[overflow_checks]` setting that applies to `id`, if any. Only
+    /// the attributes of items are read; for a closure the search starts at
+    /// the fn enclosing it. For a fn inlined from another crate this is the
+    /// setting its own crate resolved for it, which is recorded in that
+    /// crate's metadata.
+    pub fn overflow_checks_attr(&self, id: NodeId) -> Option<bool> {
+        let mut id = id;
+        loop {
+            if let Some(on) = attr::find_overflow_checks(None, self.map.attrs(id)) {
+                return Some(on);
+            }
+            if let Some(&inlined) = self.inlined_overflow_checks.borrow().get(&id) {
+                return inlined;
+            }
+            let parent = self.map.get_parent(id);
+            if parent == id {
+                break;
+            }
+            id = parent;
+        }
+        attr::find_overflow_checks(None, &self.map.krate().attrs)
+    }
+
     pub fn item_variances(&self, item_id: DefId) -> Rc<ItemVariances> {
         lookup_locally_or_in_crate_store(
             "item_variance_map", item_id, &self.item_variance_map,
@@ -17,11 +17,12 @@ use rustc_data_structures::tuple_slice::TupleSlice;
 use rustc_front::hir::InlineAsm;
 use syntax::ast::Name;
 use syntax::codemap::Span;
+use syntax::parse::token::InternedString;
 use std::fmt::{Debug, Formatter, Error};
 use std::u32;
 
 /// Lowered representation of a single function.
-#[derive(RustcEncodable, RustcDecodable)]
+#[derive(Clone, RustcEncodable, RustcDecodable)]
 pub struct Mir<'tcx> {
     /// List of basic blocks. References to basic block use a newtyped index type `BasicBlock`
     /// that indexes into this vector.
@@ -129,7 +130,7 @@ pub enum BorrowKind {
 
 // A "variable" is a binding declared by the user as part of the fn
 // decl, a let, etc.
-#[derive(RustcEncodable, RustcDecodable)]
+#[derive(Clone, RustcEncodable, RustcDecodable)]
 pub struct VarDecl<'tcx> {
     pub mutability: Mutability,
     pub name: Name,
@@ -138,7 +139,7 @@ pub struct VarDecl<'tcx> {
 
 // A "temp" is a temporary that we place on the stack. They are
 // anonymous, always mutable, and have only a type.
-#[derive(RustcEncodable, RustcDecodable)]
+#[derive(Clone, RustcEncodable, RustcDecodable)]
 pub struct TempDecl<'tcx> {
     pub ty: Ty<'tcx>,
 }
@@ -154,7 +155,7 @@ pub struct TempDecl<'tcx> {
 //
 // there is only one argument, of type `(i32, u32)`, but two bindings
 // (`x` and `y`).
-#[derive(RustcEncodable, RustcDecodable)]
+#[derive(Clone, RustcEncodable, RustcDecodable)]
 pub struct ArgDecl<'tcx> {
     pub ty: Ty<'tcx>,
 }
@@ -190,13 +191,13 @@ impl Debug for BasicBlock {
 ///////////////////////////////////////////////////////////////////////////
 // BasicBlock and Terminator
 
-#[derive(Debug, RustcEncodable, RustcDecodable)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct BasicBlockData<'tcx> {
     pub statements: Vec<Statement<'tcx>>,
     pub terminator: Terminator<'tcx>,
 }
 
-#[derive(RustcEncodable, RustcDecodable)]
+#[derive(Clone, RustcEncodable, RustcDecodable)]
 pub enum Terminator<'tcx> {
     /// block should have one successor in the graph; we jump there
     Goto {
@@ -206,6 +207,9 @@ pub enum Terminator<'tcx> {
     /// block should initiate unwinding; should be one successor
     /// that does cleanup and branches to DIVERGE_BLOCK
     Panic {
+        /// what to report, together with the file and line of `span`
+        msg: PanicMessage<'tcx>,
+        span: Span,
         target: BasicBlock,
     },
 
@@ -269,7 +273,7 @@ impl<'tcx> Terminator<'tcx> {
         use self::Terminator::*;
         match *self {
             Goto { target: ref b } => slice::ref_slice(b),
-            Panic { target: ref b } => slice::ref_slice(b),
+            Panic { target: ref b, .. } => slice::ref_slice(b),
             If { cond: _, targets: ref b } => b.as_slice(),
             Switch { targets: ref b, .. } => b,
             SwitchInt { targets: ref b, .. } => b,
@@ -283,7 +287,7 @@ impl<'tcx> Terminator<'tcx> {
         use self::Terminator::*;
         match *self {
             Goto { target: ref mut b } => slice::mut_ref_slice(b),
-            Panic { target: ref mut b } => slice::mut_ref_slice(b),
+            Panic { target: ref mut b, .. } => slice::mut_ref_slice(b),
             If { cond: _, targets: ref mut b } => b.as_mut_slice(),
             Switch { targets: ref mut b, .. } => b,
             SwitchInt { targets: ref mut b, .. } => b,
@@ -294,7 +298,22 @@ impl<'tcx> Terminator<'tcx> {
     }
 }
 
-#[derive(Debug, RustcEncodable, RustcDecodable)]
+/// The reason a `Terminator::Panic` panics.
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub enum PanicMessage<'tcx> {
+    /// `index` was not below `len`; reported by the `panic_bounds_check`
+    /// lang item, which prints both
+    BoundsCheck {
+        len: Operand<'tcx>,
+        index: Operand<'tcx>,
+    },
+
+    /// any other failed check, reported by the `panic` lang item with this
+    /// message, e.g. "arithmetic operation overflowed"
+    Message(InternedString),
+}
+
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct CallData<'tcx> {
     /// where the return value is written to
     pub destination: Lvalue<'tcx>,
@@ -321,8 +340,10 @@ impl<'tcx> Debug for Terminator<'tcx> {
         match *self {
             Goto { target } =>
                 write!(fmt, "goto -> {:?}", target),
-            Panic { target } =>
-                write!(fmt, "panic -> {:?}", target),
+            Panic { msg: PanicMessage::BoundsCheck { ref len, ref index }, target, .. } =>
+                write!(fmt, "panic(bounds check: {:?} < {:?}) -> {:?}", index, len, target),
+            Panic { msg: PanicMessage::Message(ref msg), target, .. } =>
+                write!(fmt, "panic({:?}) -> {:?}", msg, target),
             If { cond: ref lv, ref targets } =>
                 write!(fmt, "if({:?}) -> {:?}", lv, targets),
             Switch { discr: ref lv, adt_def: _, ref targets } =>
@@ -351,13 +372,13 @@ impl<'tcx> Debug for Terminator<'tcx> {
 ///////////////////////////////////////////////////////////////////////////
 // Statements
 
-#[derive(RustcEncodable, RustcDecodable)]
+#[derive(Clone, RustcEncodable, RustcDecodable)]
 pub struct Statement<'tcx> {
     pub span: Span,
     pub kind: StatementKind<'tcx>,
 }
 
-#[derive(Debug, RustcEncodable, RustcDecodable)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub enum StatementKind<'tcx> {
     Assign(Lvalue<'tcx>, Rvalue<'tcx>),
     Drop(DropKind, Lvalue<'tcx>),
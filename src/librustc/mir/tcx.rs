@@ -14,6 +14,7 @@
  */
 
 use mir::repr::*;
+use middle::const_eval::ConstVal;
 use middle::subst::Substs;
 use middle::ty::{self, AdtDef, Ty};
 use rustc_front::hir;
@@ -150,6 +151,62 @@ impl<'tcx> Mir<'tcx> {
                 self.lvalue_ty(tcx, &proj.base).projection_ty(tcx, &proj.elem)
         }
     }
+
+    /// The type of `rvalue`, or `None` if it cannot be determined from the
+    /// MIR alone (the region of a `Slice`, or the type of an empty array).
+    pub fn rvalue_ty(&self,
+                     tcx: &ty::ctxt<'tcx>,
+                     rvalue: &Rvalue<'tcx>)
+                     -> Option<Ty<'tcx>>
+    {
+        match *rvalue {
+            Rvalue::Use(ref operand) =>
+                Some(self.operand_ty(tcx, operand)),
+            Rvalue::Repeat(ref operand, ref count) => {
+                if let Literal::Value { value: ConstVal::Uint(n) } = count.literal {
+                    Some(tcx.mk_array(self.operand_ty(tcx, operand), n as usize))
+                } else {
+                    None
+                }
+            }
+            Rvalue::Ref(region, bk, ref lvalue) => {
+                let lvalue_ty = self.lvalue_ty(tcx, lvalue).to_ty(tcx);
+                Some(tcx.mk_ref(tcx.mk_region(region),
+                                ty::TypeAndMut { ty: lvalue_ty, mutbl: bk.to_mutbl_lossy() }))
+            }
+            Rvalue::Len(..) =>
+                Some(tcx.types.usize),
+            Rvalue::Cast(_, _, ty) =>
+                Some(ty),
+            Rvalue::BinaryOp(op, ref lhs, ref rhs) => {
+                let lhs_ty = self.operand_ty(tcx, lhs);
+                let rhs_ty = self.operand_ty(tcx, rhs);
+                Some(self.binop_ty(tcx, op, lhs_ty, rhs_ty))
+            }
+            Rvalue::UnaryOp(_, ref operand) =>
+                Some(self.operand_ty(tcx, operand)),
+            Rvalue::Box(ty) =>
+                Some(tcx.mk_box(ty)),
+            Rvalue::Aggregate(ref kind, ref operands) => match *kind {
+                AggregateKind::Vec =>
+                    operands.first().map(|first| {
+                        tcx.mk_array(self.operand_ty(tcx, first), operands.len())
+                    }),
+                AggregateKind::Tuple =>
+                    Some(tcx.mk_tup(operands.iter().map(|o| self.operand_ty(tcx, o)).collect())),
+                AggregateKind::Adt(adt_def, _, substs) => match adt_def.adt_kind() {
+                    ty::AdtKind::Struct => Some(tcx.mk_struct(adt_def, substs)),
+                    ty::AdtKind::Enum => Some(tcx.mk_enum(adt_def, substs)),
+                },
+                AggregateKind::Closure(def_id, closure_substs) =>
+                    Some(tcx.mk_closure_from_closure_substs(def_id,
+                                                            Box::new((*closure_substs).clone()))),
+            },
+            Rvalue::Slice { .. } |
+            Rvalue::InlineAsm(..) =>
+                None,
+        }
+    }
 }
 
 impl BorrowKind {
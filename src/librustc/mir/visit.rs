@@ -107,8 +107,16 @@ pub trait Visitor<'tcx> {
 
     fn super_terminator(&mut self, block: BasicBlock, terminator: &Terminator<'tcx>) {
         match *terminator {
-            Terminator::Goto { target } |
-            Terminator::Panic { target } => {
+            Terminator::Goto { target } => {
+                self.visit_branch(block, target);
+            }
+
+            Terminator::Panic { ref msg, span, target } => {
+                if let PanicMessage::BoundsCheck { ref len, ref index } = *msg {
+                    self.visit_operand(len);
+                    self.visit_operand(index);
+                }
+                self.visit_span(span);
                 self.visit_branch(block, target);
             }
 
@@ -394,8 +402,16 @@ pub trait MutVisitor<'tcx> {
                         block: BasicBlock,
                         terminator: &mut Terminator<'tcx>) {
         match *terminator {
-            Terminator::Goto { target } |
-            Terminator::Panic { target } => {
+            Terminator::Goto { target } => {
+                self.visit_branch(block, target);
+            }
+
+            Terminator::Panic { ref mut msg, ref mut span, target } => {
+                if let PanicMessage::BoundsCheck { ref mut len, ref mut index } = *msg {
+                    self.visit_operand(len);
+                    self.visit_operand(index);
+                }
+                self.visit_span(span);
                 self.visit_branch(block, target);
             }
 
@@ -46,6 +46,12 @@ pub struct Config {
     pub uint_type: UintTy,
 }
 
+#[derive(Clone, Copy)]
+pub enum ErrorOutputType {
+    HumanReadable(ColorConfig),
+    Json,
+}
+
 #[derive(Clone, Copy, PartialEq)]
 pub enum OptLevel {
     No, // -O0
@@ -129,7 +135,7 @@ pub struct Options {
     pub debugging_opts: DebuggingOptions,
     pub prints: Vec<PrintRequest>,
     pub cg: CodegenOptions,
-    pub color: ColorConfig,
+    pub error_format: ErrorOutputType,
     pub show_span: Option<String>,
     pub externs: HashMap<String, Vec<String>>,
     pub crate_name: Option<String>,
@@ -238,7 +244,7 @@ pub fn basic_options() -> Options {
         debugging_opts: basic_debugging_options(),
         prints: Vec::new(),
         cg: basic_codegen_options(),
-        color: Auto,
+        error_format: ErrorOutputType::HumanReadable(Auto),
         show_span: None,
         externs: HashMap::new(),
         crate_name: None,
@@ -283,6 +289,15 @@ impl Passes {
     }
 }
 
+/// The format of the file written by `-Z profile-passes`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ProfileFormat {
+    /// A tree of events, see `util::profiling::Profile`.
+    Json,
+    /// The trace event format of `chrome://tracing`.
+    ChromeTrace,
+}
+
 /// Declare a macro that will define all CodegenOptions/DebuggingOptions fields and parsers all
 /// at once. The goal of this macro is to define an interface that can be
 /// programmatically used by the option parser in order to initialize the struct
@@ -306,7 +321,7 @@ macro_rules! options {
         $struct_name { $($opt: $init),* }
     }
 
-    pub fn $buildfn(matches: &getopts::Matches, color: ColorConfig) -> $struct_name
+    pub fn $buildfn(matches: &getopts::Matches, error_format: ErrorOutputType) -> $struct_name
     {
         let mut op = $defaultfn();
         for option in matches.opt_strs($prefix) {
@@ -320,20 +335,20 @@ macro_rules! options {
                 if !setter(&mut op, value) {
                     match (value, opt_type_desc) {
                         (Some(..), None) => {
-                            early_error(color, &format!("{} option `{}` takes no \
-                                                         value", $outputname, key))
+                            early_error(error_format, &format!("{} option `{}` takes no \
+                                                                value", $outputname, key))
                         }
                         (None, Some(type_desc)) => {
-                            early_error(color, &format!("{0} option `{1}` requires \
-                                                         {2} ({3} {1}=<value>)",
-                                                        $outputname, key,
-                                                        type_desc, $prefix))
+                            early_error(error_format, &format!("{0} option `{1}` requires \
+                                                                {2} ({3} {1}=<value>)",
+                                                               $outputname, key,
+                                                               type_desc, $prefix))
                         }
                         (Some(value), Some(type_desc)) => {
-                            early_error(color, &format!("incorrect value `{}` for {} \
-                                                         option `{}` - {} was expected",
-                                                        value, $outputname,
-                                                        key, type_desc))
+                            early_error(error_format, &format!("incorrect value `{}` for {} \
+                                                                option `{}` - {} was expected",
+                                                               value, $outputname,
+                                                               key, type_desc))
                         }
                         (None, None) => unreachable!()
                     }
@@ -342,8 +357,8 @@ macro_rules! options {
                 break;
             }
             if !found {
-                early_error(color, &format!("unknown {} option: `{}`",
-                                            $outputname, key));
+                early_error(error_format, &format!("unknown {} option: `{}`",
+                                                   $outputname, key));
             }
         }
         return op;
@@ -366,13 +381,15 @@ macro_rules! options {
         pub const parse_uint: Option<&'static str> = Some("a number");
         pub const parse_passes: Option<&'static str> =
             Some("a space-separated list of passes, or `all`");
+        pub const parse_profile_format: Option<&'static str> =
+            Some("either `json` or `chrome`");
         pub const parse_opt_uint: Option<&'static str> =
             Some("a number");
     }
 
     #[allow(dead_code)]
     mod $mod_set {
-        use super::{$struct_name, Passes, SomePasses, AllPasses};
+        use super::{$struct_name, Passes, SomePasses, AllPasses, ProfileFormat};
 
         $(
             pub fn $opt(cg: &mut $struct_name, v: Option<&str>) -> bool {
@@ -476,6 +493,15 @@ macro_rules! options {
                 }
             }
         }
+
+        fn parse_profile_format(slot: &mut ProfileFormat, v: Option<&str>) -> bool {
+            match v {
+                Some("json") => *slot = ProfileFormat::Json,
+                Some("chrome") => *slot = ProfileFormat::ChromeTrace,
+                _ => return false,
+            }
+            true
+        }
     }
 ) }
 
@@ -585,6 +611,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "list the symbols defined by a library crate"),
     save_analysis: bool = (false, parse_bool,
         "write syntax and type analysis information in addition to normal output"),
+    save_analysis_json: bool = (false, parse_bool,
+        "write syntax and type analysis information as JSON in addition to normal output"),
     print_move_fragments: bool = (false, parse_bool,
         "print out move-fragment data for every fn"),
     flowgraph_print_loans: bool = (false, parse_bool,
@@ -622,6 +650,44 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "force nonzeroing move optimization on"),
     keep_mtwt_tables: bool = (false, parse_bool,
           "don't clear the resolution tables after analysis"),
+    apply_suggestions: bool = (false, parse_bool,
+          "rewrite the source files with the machine-applicable suggestions \
+           made by the compiler"),
+    fix_unused: bool = (false, parse_bool,
+          "suggest removing unused imports and unnecessary qualifications, and \
+           rewrite the source files with only those fixes"),
+    incremental: bool = (false, parse_bool,
+          "record the dependencies of each codegen unit next to the output and \
+           only rebuild the codegen units whose inputs changed"),
+    profile_passes: Option<String> = (None, parse_opt_string,
+          "write a profile of the compiler passes, and of the type checking and \
+           translation of each item, to the given file"),
+    profile_format: ProfileFormat = (ProfileFormat::Json, parse_profile_format,
+          "the format of the -Z profile-passes output: `json` or `chrome`"),
+    query: Option<String> = (None, parse_opt_string,
+          "print the type of the expression at `<file>:<line>:<col>` and the \
+           definition it refers to, then stop after analysis"),
+    explain_trait_selection: bool = (false, parse_bool,
+          "when a trait is not implemented, list the candidates considered and why \
+           each of them was rejected"),
+    macro_backtrace: bool = (false, parse_bool,
+          "show the source of every macro invocation a diagnostic is inside, and \
+           the `macro_rules!` arm that matched"),
+    dump_mir: Option<String> = (None, parse_opt_string,
+          "write the MIR of the fns whose path contains the given string (`all` for \
+           every fn; several can be separated by `|`) before and after each MIR pass"),
+    dump_mir_dir: Option<String> = (None, parse_opt_string,
+          "the directory -Z dump-mir writes to (default: the current directory)"),
+    orbit: bool = (false, parse_bool,
+          "translate every fn which has MIR through the MIR backend"),
+    mir_inline: bool = (false, parse_bool,
+          "inline small fns into their callers in the MIR, translating the callers \
+           through the MIR backend (always done with -Z orbit)"),
+    mir_const_check: bool = (false, parse_bool,
+          "check const and static initializers and array lengths by running them in \
+           the MIR interpreter, and report the errors found"),
+    validate_mir: bool = (false, parse_bool,
+          "check the invariants of the MIR after building it and after every MIR pass"),
 }
 
 pub fn default_lib_output() -> CrateType {
@@ -852,6 +918,7 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             auto   = colorize, if output goes to a tty (default);
             always = always colorize output;
             never  = never colorize output", "auto|always|never"),
+        opt::opt_u("", "error-format", "How errors and other messages are produced", "human|json"),
 
         opt::flagopt_u("", "pretty",
                    "Pretty-print the input instead of compiling;
@@ -891,15 +958,38 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         None => Auto,
 
         Some(arg) => {
-            early_error(Auto, &format!("argument for --color must be auto, always \
-                                        or never (instead was `{}`)",
-                                       arg))
+            early_error(ErrorOutputType::HumanReadable(Auto),
+                        &format!("argument for --color must be auto, always \
+                                  or never (instead was `{}`)",
+                                 arg))
         }
     };
 
+    // We need the opts_present check because the driver will send us Matches
+    // with only stable options if no unstable options are used. Since
+    // error-format is unstable, it will not be present. We have to use
+    // opts_present not opt_present because the latter will panic.
+    let error_format = if matches.opts_present(&["error-format".to_owned()]) {
+        match matches.opt_str("error-format").as_ref().map(|s| &s[..]) {
+            Some("human") => ErrorOutputType::HumanReadable(color),
+            Some("json") => ErrorOutputType::Json,
+
+            None => ErrorOutputType::HumanReadable(color),
+
+            Some(arg) => {
+                early_error(ErrorOutputType::HumanReadable(color),
+                            &format!("argument for --error-format must be human or json \
+                                      (instead was `{}`)",
+                                     arg))
+            }
+        }
+    } else {
+        ErrorOutputType::HumanReadable(color)
+    };
+
     let unparsed_crate_types = matches.opt_strs("crate-type");
     let crate_types = parse_crate_types_from_list(unparsed_crate_types)
-        .unwrap_or_else(|e| early_error(color, &e[..]));
+        .unwrap_or_else(|e| early_error(error_format, &e[..]));
 
     let mut lint_opts = vec!();
     let mut describe_lints = false;
@@ -916,11 +1006,11 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     let lint_cap = matches.opt_str("cap-lints").map(|cap| {
         lint::Level::from_str(&cap).unwrap_or_else(|| {
-            early_error(color, &format!("unknown lint level: `{}`", cap))
+            early_error(error_format, &format!("unknown lint level: `{}`", cap))
         })
     });
 
-    let debugging_opts = build_debugging_options(matches, color);
+    let debugging_opts = build_debugging_options(matches, error_format);
 
     let parse_only = debugging_opts.parse_only;
     let no_trans = debugging_opts.no_trans;
@@ -944,8 +1034,8 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
                     "link" => OutputType::Exe,
                     "dep-info" => OutputType::DepInfo,
                     part => {
-                        early_error(color, &format!("unknown emission type: `{}`",
-                                                    part))
+                        early_error(error_format, &format!("unknown emission type: `{}`",
+                                                           part))
                     }
                 };
                 let path = parts.next().map(PathBuf::from);
@@ -957,7 +1047,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         output_types.insert(OutputType::Exe, None);
     }
 
-    let mut cg = build_codegen_options(matches, color);
+    let mut cg = build_codegen_options(matches, error_format);
 
     // Issue #30063: if user requests llvm-related output to one
     // particular path, disable codegen-units.
@@ -969,11 +1059,11 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
             }).collect();
         if !incompatible.is_empty() {
             for ot in &incompatible {
-                early_warn(color, &format!("--emit={} with -o incompatible with \
-                                            -C codegen-units=N for N > 1",
-                                           ot.shorthand()));
+                early_warn(error_format, &format!("--emit={} with -o incompatible with \
+                                                   -C codegen-units=N for N > 1",
+                                                  ot.shorthand()));
             }
-            early_warn(color, "resetting to default -C codegen-units=1");
+            early_warn(error_format, "resetting to default -C codegen-units=1");
             cg.codegen_units = 1;
         }
     }
@@ -986,7 +1076,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
     let opt_level = {
         if matches.opt_present("O") {
             if cg.opt_level.is_some() {
-                early_error(color, "-O and -C opt-level both provided");
+                early_error(error_format, "-O and -C opt-level both provided");
             }
             Default
         } else {
@@ -997,9 +1087,9 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
                 Some(2) => Default,
                 Some(3) => Aggressive,
                 Some(arg) => {
-                    early_error(color, &format!("optimization level needs to be \
-                                                 between 0-3 (instead was `{}`)",
-                                                arg));
+                    early_error(error_format, &format!("optimization level needs to be \
+                                                        between 0-3 (instead was `{}`)",
+                                                       arg));
                 }
             }
         }
@@ -1008,7 +1098,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
     let gc = debugging_opts.gc;
     let debuginfo = if matches.opt_present("g") {
         if cg.debuginfo.is_some() {
-            early_error(color, "-g and -C debuginfo both provided");
+            early_error(error_format, "-g and -C debuginfo both provided");
         }
         FullDebugInfo
     } else {
@@ -1017,16 +1107,16 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
             Some(1) => LimitedDebugInfo,
             Some(2) => FullDebugInfo,
             Some(arg) => {
-                early_error(color, &format!("debug info level needs to be between \
-                                             0-2 (instead was `{}`)",
-                                            arg));
+                early_error(error_format, &format!("debug info level needs to be between \
+                                                    0-2 (instead was `{}`)",
+                                                   arg));
             }
         }
     };
 
     let mut search_paths = SearchPaths::new();
     for s in &matches.opt_strs("L") {
-        search_paths.add_path(&s[..], color);
+        search_paths.add_path(&s[..], error_format);
     }
 
     let libs = matches.opt_strs("l").into_iter().map(|s| {
@@ -1038,9 +1128,9 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
             (Some(name), "framework") => (name, cstore::NativeFramework),
             (Some(name), "static") => (name, cstore::NativeStatic),
             (_, s) => {
-                early_error(color, &format!("unknown library kind `{}`, expected \
-                                             one of dylib, framework, or static",
-                                            s));
+                early_error(error_format, &format!("unknown library kind `{}`, expected \
+                                                    one of dylib, framework, or static",
+                                                   s));
             }
         };
         (name.to_string(), kind)
@@ -1055,14 +1145,14 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
             "file-names" => PrintRequest::FileNames,
             "sysroot" => PrintRequest::Sysroot,
             req => {
-                early_error(color, &format!("unknown print request `{}`", req))
+                early_error(error_format, &format!("unknown print request `{}`", req))
             }
         }
     }).collect::<Vec<_>>();
 
     if !cg.remark.is_empty() && debuginfo == NoDebugInfo {
-        early_warn(color, "-C remark will not show source locations without \
-                           --debuginfo");
+        early_warn(error_format, "-C remark will not show source locations without \
+                                  --debuginfo");
     }
 
     let mut externs = HashMap::new();
@@ -1070,11 +1160,11 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         let mut parts = arg.splitn(2, '=');
         let name = match parts.next() {
             Some(s) => s,
-            None => early_error(color, "--extern value must not be empty"),
+            None => early_error(error_format, "--extern value must not be empty"),
         };
         let location = match parts.next() {
             Some(s) => s,
-            None => early_error(color, "--extern value must be of the format `foo=bar`"),
+            None => early_error(error_format, "--extern value must be of the format `foo=bar`"),
         };
 
         externs.entry(name.to_string()).or_insert(vec![]).push(location.to_string());
@@ -1103,7 +1193,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         debugging_opts: debugging_opts,
         prints: prints,
         cg: cg,
-        color: color,
+        error_format: error_format,
         show_span: None,
         externs: externs,
         crate_name: crate_name,
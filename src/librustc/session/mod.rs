@@ -55,7 +55,10 @@ pub struct Session {
     pub local_crate_source_file: Option<PathBuf>,
     pub working_dir: PathBuf,
     pub lint_store: RefCell<lint::LintStore>,
-    pub lints: RefCell<NodeMap<Vec<(lint::LintId, codemap::Span, String)>>>,
+    pub lints: RefCell<NodeMap<Vec<(lint::LintId,
+                                    codemap::Span,
+                                    String,
+                                    Option<diagnostic::CodeSuggestion>)>>>,
     pub plugin_llvm_passes: RefCell<Vec<String>>,
     pub plugin_attributes: RefCell<Vec<(String, AttributeType)>>,
     pub crate_types: RefCell<Vec<config::CrateType>>,
@@ -79,6 +82,15 @@ pub struct Session {
     /// available in this crate
     pub available_macros: RefCell<HashSet<Name>>,
 
+    /// The suggestions made by lints buffered with `add_lint_with_suggestion`,
+    /// i.e. the removals of unused imports and qualifications. These are the
+    /// only edits `-Z fix-unused` writes back on its own.
+    lint_fixes: RefCell<Vec<diagnostic::CodeSuggestion>>,
+
+    /// How many of the suggestions have already been written back to the
+    /// source under `-Z apply-suggestions` or `-Z fix-unused`.
+    applied_suggestions: Cell<usize>,
+
     next_node_id: Cell<ast::NodeId>,
 }
 
@@ -146,6 +158,7 @@ impl Session {
         self.diagnostic().handler().has_errors()
     }
     pub fn abort_if_errors(&self) {
+        self.apply_suggestions();
         self.diagnostic().handler().abort_if_errors();
 
         let delayed_bug = self.delayed_span_bug.borrow();
@@ -156,6 +169,37 @@ impl Session {
             _ => {}
         }
     }
+    /// Writes the suggestions made so far back to the source files, if
+    /// requested with `-Z apply-suggestions`. Under `-Z fix-unused` alone,
+    /// only the lint fixes for unused imports and qualifications are written.
+    /// This is done from `abort_if_errors`, so that the edits also reach the
+    /// disk when the compilation is about to fail.
+    fn apply_suggestions(&self) {
+        let lint_fixes;
+        let all_suggestions;
+        let suggestions: &[diagnostic::CodeSuggestion] =
+            if self.opts.debugging_opts.apply_suggestions {
+                all_suggestions = self.diagnostic().suggestions();
+                &all_suggestions[..]
+            } else if self.opts.debugging_opts.fix_unused {
+                lint_fixes = self.lint_fixes.borrow();
+                &lint_fixes[..]
+            } else {
+                return;
+            };
+        if suggestions.len() == self.applied_suggestions.get() {
+            return;
+        }
+        self.applied_suggestions.set(suggestions.len());
+        match diagnostic::suggestions::apply_suggestions(self.codemap(), suggestions) {
+            Ok(files) => {
+                for file in &files {
+                    self.note(&format!("applied suggestions to `{}`", file));
+                }
+            }
+            Err(e) => self.err(&format!("failed to apply suggestions: {}", e)),
+        }
+    }
     pub fn span_warn(&self, sp: Span, msg: &str) {
         if self.can_print_warnings {
             self.diagnostic().span_warn(sp, msg)
@@ -184,12 +228,20 @@ impl Session {
         self.diagnostic().span_end_note(sp, msg)
     }
 
-    /// Prints out a message with a suggested edit of the code.
+    /// Prints out a message with a suggested edit of the code, which is
+    /// also written back to the source under `-Z apply-suggestions`.
     ///
     /// See `diagnostic::RenderSpan::Suggestion` for more information.
     pub fn span_suggestion(&self, sp: Span, msg: &str, suggestion: String) {
         self.diagnostic().span_suggestion(sp, msg, suggestion)
     }
+    /// Like `span_suggestion`, for the fix attached to a lint by
+    /// `add_lint_with_suggestion`. Such fixes are also written back under
+    /// `-Z fix-unused`.
+    pub fn span_lint_fix(&self, suggestion: diagnostic::CodeSuggestion) {
+        self.lint_fixes.borrow_mut().push(suggestion.clone());
+        self.span_suggestion(suggestion.span, &suggestion.msg, suggestion.replacement)
+    }
     pub fn span_help(&self, sp: Span, msg: &str) {
         self.diagnostic().span_help(sp, msg)
     }
@@ -233,13 +285,22 @@ impl Session {
                     id: ast::NodeId,
                     sp: Span,
                     msg: String) {
+        self.add_lint_with_suggestion(lint, id, sp, msg, None)
+    }
+    /// Like `add_lint`, but also makes `suggestion` if the lint is emitted.
+    pub fn add_lint_with_suggestion(&self,
+                                    lint: &'static lint::Lint,
+                                    id: ast::NodeId,
+                                    sp: Span,
+                                    msg: String,
+                                    suggestion: Option<diagnostic::CodeSuggestion>) {
         let lint_id = lint::LintId::of(lint);
         let mut lints = self.lints.borrow_mut();
         match lints.get_mut(&id) {
-            Some(arr) => { arr.push((lint_id, sp, msg)); return; }
+            Some(arr) => { arr.push((lint_id, sp, msg, suggestion)); return; }
             None => {}
         }
-        lints.insert(id, vec!((lint_id, sp, msg)));
+        lints.insert(id, vec!((lint_id, sp, msg, suggestion)));
     }
     pub fn reserve_node_ids(&self, count: ast::NodeId) -> ast::NodeId {
         let id = self.next_node_id.get();
@@ -406,8 +467,18 @@ pub fn build_session(sopts: config::Options,
         .unwrap_or(true);
 
     let codemap = codemap::CodeMap::new();
+    let emitter: Box<Emitter + Send> = match sopts.error_format {
+        config::ErrorOutputType::HumanReadable(color_config) => {
+            let mut emitter = diagnostic::EmitterWriter::stderr(color_config, Some(registry));
+            emitter.set_macro_backtrace(sopts.debugging_opts.macro_backtrace);
+            Box::new(emitter)
+        }
+        config::ErrorOutputType::Json => {
+            Box::new(diagnostic::json::JsonEmitter::stderr(Some(registry)))
+        }
+    };
     let diagnostic_handler =
-        diagnostic::Handler::new(sopts.color, Some(registry), can_print_warnings);
+        diagnostic::Handler::with_emitter(can_print_warnings, emitter);
     let span_diagnostic_handler =
         diagnostic::SpanHandler::new(diagnostic_handler, codemap);
 
@@ -476,6 +547,8 @@ pub fn build_session_(sopts: config::Options,
         next_node_id: Cell::new(1),
         injected_allocator: Cell::new(None),
         available_macros: RefCell::new(HashSet::new()),
+        lint_fixes: RefCell::new(Vec::new()),
+        applied_suggestions: Cell::new(0),
     };
 
     sess
@@ -488,13 +561,22 @@ pub fn expect<T, M>(sess: &Session, opt: Option<T>, msg: M) -> T where
     diagnostic::expect(sess.diagnostic(), opt, msg)
 }
 
-pub fn early_error(color: diagnostic::ColorConfig, msg: &str) -> ! {
-    let mut emitter = diagnostic::EmitterWriter::stderr(color, None);
+pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
+    let mut emitter = early_emitter(output);
     emitter.emit(None, msg, None, diagnostic::Fatal);
     panic!(diagnostic::FatalError);
 }
 
-pub fn early_warn(color: diagnostic::ColorConfig, msg: &str) {
-    let mut emitter = diagnostic::EmitterWriter::stderr(color, None);
+pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
+    let mut emitter = early_emitter(output);
     emitter.emit(None, msg, None, diagnostic::Warning);
 }
+
+fn early_emitter(output: config::ErrorOutputType) -> Box<Emitter> {
+    match output {
+        config::ErrorOutputType::HumanReadable(color_config) => {
+            Box::new(diagnostic::EmitterWriter::stderr(color_config, None))
+        }
+        config::ErrorOutputType::Json => Box::new(diagnostic::json::JsonEmitter::stderr(None)),
+    }
+}
@@ -23,6 +23,7 @@ use std::time::Instant;
 use rustc_front::hir;
 use rustc_front::intravisit;
 use rustc_front::intravisit::Visitor;
+use util::profiling;
 
 // The name of the associated type for `Fn` return types
 pub const FN_OUTPUT_NAME: &'static str = "Output";
@@ -36,6 +37,7 @@ pub fn time<T, F>(do_it: bool, what: &str, f: F) -> T where
     F: FnOnce() -> T,
 {
     thread_local!(static DEPTH: Cell<usize> = Cell::new(0));
+    let _pass = profiling::pass(what);
     if !do_it { return f(); }
 
     let old = DEPTH.with(|slot| {
@@ -76,7 +78,7 @@ macro_rules! option_try(
 
 // Memory reporting
 #[cfg(unix)]
-fn get_resident() -> Option<usize> {
+pub fn get_resident() -> Option<usize> {
     use std::fs::File;
     use std::io::Read;
 
@@ -91,7 +93,7 @@ fn get_resident() -> Option<usize> {
 
 #[cfg(windows)]
 #[cfg_attr(stage0, allow(improper_ctypes))]
-fn get_resident() -> Option<usize> {
+pub fn get_resident() -> Option<usize> {
     type BOOL = i32;
     type DWORD = u32;
     type HANDLE = *mut u8;
@@ -0,0 +1,274 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Hierarchical profiles of a compilation (`-Z profile-passes`).
+//!
+//! Once `start` has been called on a thread, every pass timed on that thread
+//! by `util::common::time` (whether or not `-Z time-passes` is given) and
+//! every item handed to `item` is recorded as an `Event`, nested inside the
+//! events which were running when it started. `finish` returns the events
+//! recorded so far, which can be written either as a tree in our own JSON
+//! format or as a Chrome trace, which flame-graph viewers understand.
+//!
+//! The resident set size is measured at both ends of a pass, but not of an
+//! item: there are far too many items for reading it to be cheap, and the
+//! cost of doing so would end up in the timings.
+//!
+//! When profiling is disabled, `pass` and `item` only check a thread-local.
+
+use util::common::get_resident;
+
+use serialize::json;
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// Bumped whenever the format written by `Profile::write_json` changes.
+const PROFILE_VERSION: u32 = 1;
+
+#[derive(RustcEncodable)]
+pub struct Profile {
+    version: u32,
+    /// The outermost events, in the order in which they started.
+    pub events: Vec<Event>,
+}
+
+#[derive(RustcEncodable)]
+pub struct Event {
+    pub name: String,
+    /// `"pass"` for a pass, otherwise the pass which processed the item
+    /// (`"typeck"`, `"trans"`, ...).
+    pub category: &'static str,
+    /// Nanoseconds since profiling started.
+    pub start: u64,
+    pub end: u64,
+    /// The resident set size in bytes when the event started and ended, if it
+    /// was measured.
+    pub rss_start: Option<usize>,
+    pub rss_end: Option<usize>,
+    /// The events which ran while this one was running, in order.
+    pub children: Vec<Event>,
+}
+
+struct Profiler {
+    epoch: Instant,
+    /// The events which have not ended yet, outermost first.
+    open: Vec<Event>,
+    /// The outermost events which have ended.
+    events: Vec<Event>,
+}
+
+thread_local!(static PROFILER: RefCell<Option<Profiler>> = RefCell::new(None));
+
+/// A running event, which ends when this is dropped.
+pub struct Activity {
+    _private: (),
+}
+
+impl Drop for Activity {
+    fn drop(&mut self) {
+        PROFILER.with(|profiler| {
+            if let Some(ref mut profiler) = *profiler.borrow_mut() {
+                profiler.end();
+            }
+        })
+    }
+}
+
+impl Profiler {
+    fn now(&self) -> u64 {
+        nanos(self.epoch.elapsed())
+    }
+
+    fn end(&mut self) {
+        let mut event = match self.open.pop() {
+            Some(event) => event,
+            None => return,
+        };
+        event.end = self.now();
+        if event.rss_start.is_some() {
+            event.rss_end = get_resident();
+        }
+        match self.open.last_mut() {
+            Some(parent) => parent.children.push(event),
+            None => self.events.push(event),
+        }
+    }
+}
+
+fn nanos(d: Duration) -> u64 {
+    d.as_secs() * 1_000_000_000 + d.subsec_nanos() as u64
+}
+
+/// Starts recording the events of this thread.
+pub fn start() {
+    PROFILER.with(|profiler| {
+        *profiler.borrow_mut() = Some(Profiler {
+            epoch: Instant::now(),
+            open: vec![],
+            events: vec![],
+        });
+    })
+}
+
+/// Stops recording the events of this thread, returning the profile if
+/// `start` was called. Events which are still running are ended now.
+pub fn finish() -> Option<Profile> {
+    PROFILER.with(|profiler| {
+        profiler.borrow_mut().take().map(|mut profiler| {
+            while !profiler.open.is_empty() {
+                profiler.end();
+            }
+            Profile {
+                version: PROFILE_VERSION,
+                events: profiler.events,
+            }
+        })
+    })
+}
+
+fn begin<N>(category: &'static str, measure_rss: bool, name: N) -> Option<Activity>
+    where N: FnOnce() -> String
+{
+    PROFILER.with(|profiler| {
+        let mut profiler = profiler.borrow_mut();
+        let profiler = match *profiler {
+            Some(ref mut profiler) => profiler,
+            None => return None,
+        };
+        let rss = if measure_rss { get_resident() } else { None };
+        let start = profiler.now();
+        profiler.open.push(Event {
+            name: name(),
+            category: category,
+            start: start,
+            end: start,
+            rss_start: rss,
+            rss_end: None,
+            children: vec![],
+        });
+        Some(Activity { _private: () })
+    })
+}
+
+/// Records the pass `what` until the result is dropped.
+pub fn pass(what: &str) -> Option<Activity> {
+    begin("pass", true, || what.to_string())
+}
+
+/// Records the processing of an item by the pass `category` until the
+/// result is dropped. `name` is only called when profiling is enabled.
+pub fn item<N>(category: &'static str, name: N) -> Option<Activity>
+    where N: FnOnce() -> String
+{
+    begin(category, false, name)
+}
+
+impl Profile {
+    /// Writes the profile as a tree of events.
+    pub fn write_json(&self, w: &mut Write) -> io::Result<()> {
+        write!(w, "{}", json::as_json(self))
+    }
+
+    /// Writes the profile in the trace event format understood by
+    /// `chrome://tracing` and most flame-graph viewers. The nesting of events
+    /// is implied by their timestamps.
+    pub fn write_chrome_trace(&self, w: &mut Write) -> io::Result<()> {
+        fn flatten(event: &Event, out: &mut Vec<TraceEvent>) {
+            let rss_delta = match (event.rss_start, event.rss_end) {
+                (Some(start), Some(end)) => Some(end as i64 - start as i64),
+                _ => None,
+            };
+            out.push(TraceEvent {
+                name: event.name.clone(),
+                cat: event.category,
+                ph: "X",
+                ts: event.start as f64 / 1000.0,
+                dur: (event.end - event.start) as f64 / 1000.0,
+                pid: 0,
+                tid: 0,
+                args: TraceArgs { rss_delta: rss_delta },
+            });
+            for child in &event.children {
+                flatten(child, out);
+            }
+        }
+
+        let mut trace = Trace { traceEvents: vec![], displayTimeUnit: "ms" };
+        for event in &self.events {
+            flatten(event, &mut trace.traceEvents);
+        }
+        write!(w, "{}", json::as_json(&trace))
+    }
+}
+
+// The following data types are provided just for serialisation.
+
+#[derive(RustcEncodable)]
+#[allow(non_snake_case)]
+struct Trace {
+    traceEvents: Vec<TraceEvent>,
+    displayTimeUnit: &'static str,
+}
+
+#[derive(RustcEncodable)]
+struct TraceEvent {
+    name: String,
+    cat: &'static str,
+    /// "X" is a complete event, with both a start and a duration.
+    ph: &'static str,
+    /// Microseconds.
+    ts: f64,
+    dur: f64,
+    pid: u32,
+    tid: u32,
+    args: TraceArgs,
+}
+
+#[derive(RustcEncodable)]
+struct TraceArgs {
+    /// Bytes.
+    rss_delta: Option<i64>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{start, finish, pass, item};
+
+    #[test]
+    fn test_events_are_nested() {
+        start();
+        {
+            let _outer = pass("outer");
+            let _first = item("typeck", || "first".to_string());
+        }
+        {
+            let _second = pass("second");
+        }
+        let profile = finish().unwrap();
+        assert_eq!(profile.events.len(), 2);
+        let outer = &profile.events[0];
+        assert_eq!(outer.name, "outer");
+        assert_eq!(outer.children.len(), 1);
+        assert_eq!(outer.children[0].name, "first");
+        assert_eq!(outer.children[0].category, "typeck");
+        assert!(outer.children[0].rss_start.is_none());
+        assert!(outer.start <= outer.children[0].start);
+        assert!(outer.children[0].end <= outer.end);
+        assert_eq!(profile.events[1].name, "second");
+        assert!(finish().is_none());
+    }
+
+    #[test]
+    fn test_disabled_profile_records_nothing() {
+        let _pass = pass("ignored");
+        assert!(finish().is_none());
+    }
+}
@@ -116,6 +116,22 @@ impl Svh {
     }
 }
 
+/// Hashes the contents of a single item, with the same insensitivity to
+/// spans and node ids as `Svh::calculate`. Items nested inside `item` (the
+/// contents of a module, or items declared in a fn body) are not included;
+/// they get hashes of their own. If `with_bodies` is false, the bodies of
+/// fns and methods are skipped, so only their signatures are hashed.
+pub fn hash_item(krate: &hir::Crate, item: &hir::Item, with_bodies: bool) -> u64 {
+    let mut state = SipHasher::new();
+    {
+        let mut visitor = svh_visitor::make(&mut state, krate);
+        visitor.nested_items = false;
+        visitor.fn_bodies = with_bodies;
+        visit::Visitor::visit_item(&mut visitor, item);
+    }
+    state.finish()
+}
+
 impl fmt::Display for Svh {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.pad(self.as_str())
@@ -144,10 +160,14 @@ mod svh_visitor {
     pub struct StrictVersionHashVisitor<'a> {
         pub krate: &'a Crate,
         pub st: &'a mut SipHasher,
+        /// Whether to descend into nested items.
+        pub nested_items: bool,
+        /// Whether to hash the bodies of fns, or just their signatures.
+        pub fn_bodies: bool,
     }
 
     pub fn make<'a>(st: &'a mut SipHasher, krate: &'a Crate) -> StrictVersionHashVisitor<'a> {
-        StrictVersionHashVisitor { st: st, krate: krate }
+        StrictVersionHashVisitor { st: st, krate: krate, nested_items: true, fn_bodies: true }
     }
 
     // To off-load the bulk of the hash-computation on #[derive(Hash)],
@@ -303,7 +323,9 @@ mod svh_visitor {
 
     impl<'a> Visitor<'a> for StrictVersionHashVisitor<'a> {
         fn visit_nested_item(&mut self, item: ItemId) {
-            self.visit_item(self.krate.item(item.id))
+            if self.nested_items {
+                self.visit_item(self.krate.item(item.id))
+            }
         }
 
         fn visit_variant_data(&mut self, s: &'a VariantData, name: Name,
@@ -320,6 +342,17 @@ mod svh_visitor {
             visit::walk_variant(self, v, g, item_id)
         }
 
+        fn visit_fn(&mut self, fk: FnKind<'a>, fd: &'a FnDecl,
+                    b: &'a Block, s: Span, _: NodeId) {
+            SawFn.hash(self.st);
+            if self.fn_bodies {
+                visit::walk_fn(self, fk, fd, b, s)
+            } else {
+                visit::walk_fn_decl(self, fd);
+                visit::walk_fn_kind(self, fk)
+            }
+        }
+
         // All of the remaining methods just record (in the hash
         // SipHasher) that the visitor saw that particular variant
         // (with its payload), and continue walking as the default
@@ -391,11 +424,6 @@ mod svh_visitor {
             SawGenerics.hash(self.st); visit::walk_generics(self, g)
         }
 
-        fn visit_fn(&mut self, fk: FnKind<'a>, fd: &'a FnDecl,
-                    b: &'a Block, s: Span, _: NodeId) {
-            SawFn.hash(self.st); visit::walk_fn(self, fk, fd, b, s)
-        }
-
         fn visit_trait_item(&mut self, ti: &'a TraitItem) {
             SawTraitItem.hash(self.st); visit::walk_trait_item(self, ti)
         }
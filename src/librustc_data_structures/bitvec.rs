@@ -9,6 +9,7 @@
 // except according to those terms.
 
 /// A very simple BitVector type.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct BitVector {
     data: Vec<u64>
 }
@@ -32,6 +33,38 @@ impl BitVector {
         (value | mask) != value
     }
 
+    pub fn remove(&mut self, bit: usize) -> bool {
+        let (word, mask) = word_mask(bit);
+        let data = &mut self.data[word];
+        let value = *data;
+        *data = value & !mask;
+        (value & !mask) != value
+    }
+
+    /// Removes every bit set in `other`, returning whether any was set in
+    /// `self`.
+    pub fn subtract(&mut self, other: &BitVector) -> bool {
+        assert!(self.data.len() == other.data.len());
+        let mut changed = false;
+        for (i, j) in self.data.iter_mut().zip(&other.data) {
+            let value = *i;
+            *i = value & !*j;
+            if value != *i { changed = true; }
+        }
+        changed
+    }
+
+    pub fn clear(&mut self) {
+        for word in &mut self.data {
+            *word = 0;
+        }
+    }
+
+    /// The indices of the bits which are set, in increasing order.
+    pub fn iter<'a>(&'a self) -> BitVectorIter<'a> {
+        BitVectorIter { vector: self, next: 0 }
+    }
+
     pub fn insert_all(&mut self, all: &BitVector) -> bool {
         assert!(self.data.len() == all.data.len());
         let mut changed = false;
@@ -50,6 +83,26 @@ impl BitVector {
     }
 }
 
+pub struct BitVectorIter<'a> {
+    vector: &'a BitVector,
+    next: usize,
+}
+
+impl<'a> Iterator for BitVectorIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.next < self.vector.data.len() * 64 {
+            let bit = self.next;
+            self.next += 1;
+            if self.vector.contains(bit) {
+                return Some(bit);
+            }
+        }
+        None
+    }
+}
+
 /// A "bit matrix" is basically a square matrix of booleans
 /// represented as one gigantic bitvector. In other words, it is as if
 /// you have N bitvectors, each of length N. Note that `elements` here is `N`/
@@ -168,6 +221,24 @@ fn union_two_vecs() {
     assert!(vec1.contains(64));
 }
 
+#[test]
+fn remove_and_subtract() {
+    let mut vec1 = BitVector::new(130);
+    let mut vec2 = BitVector::new(130);
+    vec1.insert(1);
+    vec1.insert(65);
+    vec1.insert(129);
+    assert!(vec1.remove(1));
+    assert!(!vec1.remove(1));
+    assert!(vec2.insert(65));
+    assert!(vec2.insert(100));
+    assert!(vec1.subtract(&vec2));
+    assert!(!vec1.subtract(&vec2));
+    assert_eq!(vec1.iter().collect::<Vec<_>>(), vec![129]);
+    vec1.clear();
+    assert_eq!(vec1.iter().next(), None);
+}
+
 #[test]
 fn grow() {
     let mut vec1 = BitVector::new(65);
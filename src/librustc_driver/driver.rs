@@ -20,6 +20,8 @@ use rustc::middle::{stability, ty, reachable};
 use rustc::middle::dependency_format;
 use rustc::middle;
 use rustc::util::common::time;
+use rustc::util::profiling;
+use rustc::util::nodemap::NodeSet;
 use rustc_borrowck as borrowck;
 use rustc_resolve as resolve;
 use rustc_metadata::macro_import;
@@ -63,6 +65,8 @@ pub fn compile_input(sess: Session,
                      output: &Option<PathBuf>,
                      addl_plugins: Option<Vec<String>>,
                      control: CompileController) {
+    let _profile = ProfileWriter::new(&sess);
+
     macro_rules! controller_entry_point{($point: ident, $tsess: expr, $make_state: expr) => ({
         let state = $make_state;
         (control.$point.callback)(state);
@@ -115,7 +119,9 @@ pub fn compile_input(sess: Session,
 
         // Discard MTWT tables that aren't required past lowering to HIR.
         if !sess.opts.debugging_opts.keep_mtwt_tables &&
-           !sess.opts.debugging_opts.save_analysis {
+           !sess.opts.debugging_opts.save_analysis &&
+           !sess.opts.debugging_opts.save_analysis_json &&
+           sess.opts.debugging_opts.query.is_none() {
             syntax::ext::mtwt::clear_tables();
         }
 
@@ -206,6 +212,40 @@ pub fn compile_input(sess: Session,
     phase_6_link_output(&sess, &trans, &outputs);
 }
 
+/// Writes the profile requested by `-Z profile-passes` when compilation
+/// ends, whether it succeeded or not.
+struct ProfileWriter<'a> {
+    sess: &'a Session,
+}
+
+impl<'a> ProfileWriter<'a> {
+    fn new(sess: &'a Session) -> Option<ProfileWriter<'a>> {
+        sess.opts.debugging_opts.profile_passes.as_ref().map(|_| {
+            profiling::start();
+            ProfileWriter { sess: sess }
+        })
+    }
+}
+
+impl<'a> Drop for ProfileWriter<'a> {
+    fn drop(&mut self) {
+        let profile = match profiling::finish() {
+            Some(profile) => profile,
+            None => return,
+        };
+        let path = self.sess.opts.debugging_opts.profile_passes.as_ref().unwrap();
+        let result = fs::File::create(path).and_then(|mut file| {
+            match self.sess.opts.debugging_opts.profile_format {
+                config::ProfileFormat::Json => profile.write_json(&mut file),
+                config::ProfileFormat::ChromeTrace => profile.write_chrome_trace(&mut file),
+            }
+        });
+        if let Err(e) = result {
+            self.sess.warn(&format!("could not write profile to `{}`: {}", path, e));
+        }
+    }
+}
+
 /// The name used for source code that doesn't originate in a file
 /// (e.g. source from stdin or a string)
 pub fn anon_src() -> String {
@@ -512,8 +552,8 @@ pub fn phase_2_configure_and_expand(sess: &Session,
         }
     });
 
-    let Registry { syntax_exts, early_lint_passes, late_lint_passes, lint_groups,
-                   llvm_passes, attributes, .. } = registry;
+    let Registry { syntax_exts, early_lint_passes, late_lint_passes, mir_lint_passes,
+                   lint_groups, llvm_passes, attributes, .. } = registry;
 
     {
         let mut ls = sess.lint_store.borrow_mut();
@@ -523,6 +563,9 @@ pub fn phase_2_configure_and_expand(sess: &Session,
         for pass in late_lint_passes {
             ls.register_late_pass(Some(sess), true, pass);
         }
+        for pass in mir_lint_passes {
+            ls.register_mir_pass(Some(sess), true, pass);
+        }
 
         for (name, to) in lint_groups {
             ls.register_group(Some(sess), true, name, to);
@@ -781,6 +824,12 @@ pub fn phase_3_run_analysis_passes<'tcx, F, R>(sess: &'tcx Session,
                                             "MIR dump",
                                             || mir::mir_map::build_mir_for_crate(tcx));
 
+                                   if sess.opts.debugging_opts.mir_const_check {
+                                       time(time_passes,
+                                            "MIR constant checking",
+                                            || mir::interpret::check_crate(tcx, &mir_map));
+                                   }
+
                                    time(time_passes,
                                         "liveness checking",
                                         || middle::liveness::check_crate(tcx));
@@ -855,10 +904,19 @@ pub fn phase_4_translate_to_llvm<'tcx>(tcx: &ty::ctxt<'tcx>,
          "erasing regions from MIR",
          || mir::transform::erase_regions::erase_regions(tcx, &mut mir_map));
 
+    let mir_inlined = if tcx.sess.opts.debugging_opts.orbit ||
+                         tcx.sess.opts.debugging_opts.mir_inline {
+        time(time_passes,
+             "MIR inlining",
+             || mir::transform::inline::inline_calls(tcx, &mut mir_map))
+    } else {
+        NodeSet()
+    };
+
     // Option dance to work around the lack of stack once closures.
     time(time_passes,
          "translation",
-         move || trans::trans_crate(tcx, &mir_map, analysis))
+         move || trans::trans_crate(tcx, &mir_map, &mir_inlined, analysis))
 }
 
 /// Run LLVM itself, producing a bitcode file, assembly file or object file
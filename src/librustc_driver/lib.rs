@@ -67,7 +67,7 @@ use rustc_resolve as resolve;
 use rustc_trans::back::link;
 use rustc_trans::save;
 use rustc::session::{config, Session, build_session};
-use rustc::session::config::{Input, PrintRequest, OutputType};
+use rustc::session::config::{Input, PrintRequest, OutputType, ErrorOutputType};
 use rustc::middle::cstore::CrateStore;
 use rustc::lint::Lint;
 use rustc::lint;
@@ -99,6 +99,7 @@ pub mod test;
 
 pub mod driver;
 pub mod pretty;
+pub mod query;
 pub mod target_features;
 
 
@@ -147,7 +148,7 @@ pub fn run_compiler<'a>(args: &[String], callbacks: &mut CompilerCalls<'a>) {
 
     let descriptions = diagnostics_registry();
 
-    do_or_return!(callbacks.early_callback(&matches, &descriptions, sopts.color));
+    do_or_return!(callbacks.early_callback(&matches, &descriptions, sopts.error_format));
 
     let (odir, ofile) = make_output(&matches);
     let (input, input_file_path) = match make_input(&matches.free) {
@@ -238,7 +239,7 @@ pub trait CompilerCalls<'a> {
     fn early_callback(&mut self,
                       _: &getopts::Matches,
                       _: &diagnostics::registry::Registry,
-                      _: diagnostic::ColorConfig)
+                      _: ErrorOutputType)
                       -> Compilation {
         Compilation::Continue
     }
@@ -314,7 +315,7 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
     fn early_callback(&mut self,
                       matches: &getopts::Matches,
                       descriptions: &diagnostics::registry::Registry,
-                      color: diagnostic::ColorConfig)
+                      output: ErrorOutputType)
                       -> Compilation {
         match matches.opt_str("explain") {
             Some(ref code) => {
@@ -329,7 +330,7 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
                         print!("{}", &description[1..]);
                     }
                     None => {
-                        early_error(color, &format!("no extended information for {}", code));
+                        early_error(output, &format!("no extended information for {}", code));
                     }
                 }
                 return Compilation::Stop;
@@ -363,10 +364,10 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
                 if should_stop == Compilation::Stop {
                     return None;
                 }
-                early_error(sopts.color, "no input filename given");
+                early_error(sopts.error_format, "no input filename given");
             }
             1 => panic!("make_input should have provided valid inputs"),
-            _ => early_error(sopts.color, "multiple input filenames provided"),
+            _ => early_error(sopts.error_format, "multiple input filenames provided"),
         }
 
         None
@@ -425,20 +426,49 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
             control.after_llvm.stop = Compilation::Stop;
         }
 
-        if sess.opts.debugging_opts.save_analysis {
-            control.after_analysis.callback = box |state| {
+        if sess.opts.debugging_opts.save_analysis ||
+           sess.opts.debugging_opts.save_analysis_json {
+            let format = if sess.opts.debugging_opts.save_analysis_json {
+                save::Format::Json
+            } else {
+                save::Format::Csv
+            };
+            control.after_analysis.callback = box move |state| {
                 time(state.session.time_passes(), "save analysis", || {
                     save::process_crate(state.tcx.unwrap(),
                                         state.lcx.unwrap(),
                                         state.krate.unwrap(),
                                         state.analysis.unwrap(),
                                         state.crate_name.unwrap(),
-                                        state.out_dir)
+                                        state.out_dir,
+                                        format)
                 });
             };
             control.make_glob_map = resolve::MakeGlobMap::Yes;
         }
 
+        if let Some(ref position) = sess.opts.debugging_opts.query {
+            if query::parse_position(position).is_none() {
+                early_error(sess.opts.error_format,
+                            "`-Z query` expects a position of the form `<file>:<line>:<col>`");
+            }
+            let position = position.clone();
+            control.after_analysis.callback = box move |state| {
+                let (file, line, col) = query::parse_position(&position).unwrap();
+                let cm = state.session.codemap();
+                let pos = match query::lookup_position(cm, file, line, col) {
+                    Some(pos) => pos,
+                    None => state.session.fatal(&format!("no position {} in the crate", position)),
+                };
+                let result = query::query(state.tcx.unwrap(),
+                                          state.lcx.unwrap(),
+                                          state.krate.unwrap(),
+                                          pos);
+                query::print_result(cm, result.as_ref());
+            };
+            control.after_analysis.stop = Compilation::Stop;
+        }
+
         control
     }
 }
@@ -456,7 +486,7 @@ impl RustcDefaultCalls {
                     println!("{}", String::from_utf8(v).unwrap());
                 }
                 &Input::Str(_) => {
-                    early_error(sess.opts.color, "cannot list metadata for stdin");
+                    early_error(sess.opts.error_format, "cannot list metadata for stdin");
                 }
             }
             return Compilation::Stop;
@@ -483,7 +513,7 @@ impl RustcDefaultCalls {
                 PrintRequest::CrateName => {
                     let input = match input {
                         Some(input) => input,
-                        None => early_error(sess.opts.color, "no input file provided"),
+                        None => early_error(sess.opts.error_format, "no input file provided"),
                     };
                     let attrs = attrs.as_ref().unwrap();
                     let t_outputs = driver::build_output_filenames(input, odir, ofile, attrs, sess);
@@ -773,7 +803,7 @@ pub fn handle_options(mut args: Vec<String>) -> Option<getopts::Matches> {
                             &opt.opt_group.short_name
                         };
                         if m.opt_present(opt_name) {
-                            early_error(diagnostic::Auto,
+                            early_error(ErrorOutputType::HumanReadable(diagnostic::Auto),
                                         &format!("use of unstable option '{}' requires -Z \
                                                   unstable-options",
                                                  opt_name));
@@ -782,7 +812,7 @@ pub fn handle_options(mut args: Vec<String>) -> Option<getopts::Matches> {
                 }
                 m
             }
-            Err(f) => early_error(diagnostic::Auto, &f.to_string()),
+            Err(f) => early_error(ErrorOutputType::HumanReadable(diagnostic::Auto), &f.to_string()),
         }
     }
 
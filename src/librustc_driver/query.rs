@@ -0,0 +1,257 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Queries about a position in the source of a crate, for IDEs.
+//!
+//! Given the analysed crate and a position in it, `query` finds the innermost
+//! expression, pattern, type or item at that position and returns its type
+//! and the definition it refers to. References are resolved through the
+//! `SaveContext` of save-analysis, so this agrees with what `-Z
+//! save-analysis` would record for them.
+//!
+//! `rustc -Z query=<file>:<line>:<col>` runs a query after analysis and
+//! prints the result as JSON.
+
+use rustc::middle::def_id::DefId;
+use rustc::middle::ty;
+use rustc_front::lowering::LoweringContext;
+use rustc_trans::save::{self, Data, SaveContext};
+
+use serialize::json;
+use syntax::ast::{self, NodeId};
+use syntax::ast_util;
+use syntax::codemap::{BytePos, CodeMap, Pos, Span};
+use syntax::visit::{self, Visitor};
+
+pub struct QueryResult {
+    /// The span of the expression, pattern, type or item found.
+    pub span: Span,
+    /// Its type, if it has one.
+    pub ty: Option<String>,
+    /// The definition it refers to, or which it is.
+    pub def: Option<Definition>,
+}
+
+pub struct Definition {
+    pub def_id: DefId,
+    /// The path of the definition, e.g. `foo::Bar::baz`.
+    pub path: String,
+    /// The span of the definition, if it is in this crate.
+    pub span: Option<Span>,
+}
+
+/// Parses the argument of `-Z query`, `<file>:<line>:<col>`.
+pub fn parse_position(s: &str) -> Option<(&str, usize, usize)> {
+    let mut parts = s.rsplitn(3, ':');
+    let col = parts.next().and_then(|col| col.parse().ok());
+    let line = parts.next().and_then(|line| line.parse().ok());
+    match (parts.next(), line, col) {
+        (Some(file), Some(line), Some(col)) => Some((file, line, col)),
+        _ => None,
+    }
+}
+
+/// The position of the `col`th character of line `line` (both 1-based) of
+/// the source file `file`.
+pub fn lookup_position(cm: &CodeMap, file: &str, line: usize, col: usize) -> Option<BytePos> {
+    let files = cm.files.borrow();
+    let fm = match files.iter().find(|fm| fm.name == file) {
+        Some(fm) => fm,
+        None => return None,
+    };
+    let line_start = match fm.lines.borrow().get(line.wrapping_sub(1)) {
+        Some(&line_start) => line_start,
+        None => return None,
+    };
+    let src = match fm.src {
+        Some(ref src) => src,
+        None => return None,
+    };
+    let line_src = src[(line_start - fm.start_pos).to_usize()..].lines().next().unwrap_or("");
+    line_src.char_indices().nth(col.wrapping_sub(1)).map(|(offset, _)| {
+        line_start + BytePos::from_usize(offset)
+    })
+}
+
+/// Queries the innermost expression, pattern, type or item of `krate` at
+/// `pos`.
+pub fn query<'l, 'tcx>(tcx: &'l ty::ctxt<'tcx>,
+                       lcx: &'l LoweringContext<'l>,
+                       krate: &ast::Crate,
+                       pos: BytePos)
+                       -> Option<QueryResult> {
+    let mut visitor = QueryVisitor {
+        tcx: tcx,
+        save_ctxt: SaveContext::new(tcx, lcx),
+        pos: pos,
+        result: None,
+    };
+    visit::walk_crate(&mut visitor, krate);
+    visitor.result
+}
+
+/// Prints `result` as a JSON object, or `null` if nothing was found.
+pub fn print_result(cm: &CodeMap, result: Option<&QueryResult>) {
+    let output = result.map(|result| {
+        QueryOutput {
+            span: cm.span_to_string(result.span),
+            ty: result.ty.clone(),
+            def_path: result.def.as_ref().map(|def| def.path.clone()),
+            def_span: result.def.as_ref()
+                                .and_then(|def| def.span)
+                                .map(|span| cm.span_to_string(span)),
+        }
+    });
+    println!("{}", json::as_json(&output));
+}
+
+#[derive(RustcEncodable)]
+struct QueryOutput {
+    span: String,
+    ty: Option<String>,
+    def_path: Option<String>,
+    def_span: Option<String>,
+}
+
+struct QueryVisitor<'l, 'tcx: 'l> {
+    tcx: &'l ty::ctxt<'tcx>,
+    save_ctxt: SaveContext<'l, 'tcx>,
+    pos: BytePos,
+    result: Option<QueryResult>,
+}
+
+impl<'l, 'tcx> QueryVisitor<'l, 'tcx> {
+    fn contains(&self, span: Span) -> bool {
+        !save::generated_code(span) && span.lo <= self.pos && self.pos < span.hi
+    }
+
+    /// Nodes are visited outside in, so each match replaces the previous one.
+    fn found(&mut self, span: Span, ty: Option<String>, def_id: Option<DefId>) {
+        self.result = Some(QueryResult {
+            span: span,
+            ty: ty,
+            def: def_id.map(|def_id| self.definition(def_id)),
+        });
+    }
+
+    fn definition(&self, def_id: DefId) -> Definition {
+        Definition {
+            def_id: def_id,
+            path: self.tcx.item_path_str(def_id),
+            span: self.tcx.map.as_local_node_id(def_id).and_then(|id| self.tcx.map.opt_span(id)),
+        }
+    }
+
+    fn node_type(&self, id: NodeId) -> Option<String> {
+        self.tcx.node_types().get(&id).map(|ty| ty.to_string())
+    }
+
+    fn item_type(&self, def_id: DefId) -> Option<String> {
+        self.tcx.tcache.borrow().get(&def_id).map(|scheme| scheme.ty.to_string())
+    }
+
+    /// The definition the path `id` resolves to, unless it is only partially
+    /// resolved (e.g. an associated type).
+    fn path_def(&self, id: NodeId, path: &ast::Path) -> Option<DefId> {
+        let resolved = self.tcx.def_map.borrow().get(&id).map_or(false, |res| res.depth == 0);
+        if resolved {
+            self.save_ctxt.get_path_data(id, path).and_then(ref_id)
+        } else {
+            None
+        }
+    }
+
+    fn found_definition(&mut self, id: NodeId, span: Span) {
+        let def_id = self.tcx.map.local_def_id(id);
+        let ty = self.item_type(def_id);
+        self.found(span, ty, Some(def_id));
+    }
+}
+
+/// The definition referred to by save-analysis data.
+fn ref_id(data: Data) -> Option<DefId> {
+    match data {
+        Data::VariableRefData(data) => Some(data.ref_id),
+        Data::TypeRefData(data) => Some(data.ref_id),
+        Data::ModRefData(data) => Some(data.ref_id),
+        Data::FunctionCallData(data) => Some(data.ref_id),
+        Data::MethodCallData(data) => data.ref_id.or(data.decl_id),
+        _ => None,
+    }
+}
+
+impl<'l, 'tcx, 'v> Visitor<'v> for QueryVisitor<'l, 'tcx> {
+    fn visit_item(&mut self, item: &ast::Item) {
+        if self.contains(item.span) {
+            self.found_definition(item.id, item.span);
+        }
+        visit::walk_item(self, item);
+    }
+
+    fn visit_trait_item(&mut self, trait_item: &ast::TraitItem) {
+        if self.contains(trait_item.span) {
+            self.found_definition(trait_item.id, trait_item.span);
+        }
+        visit::walk_trait_item(self, trait_item);
+    }
+
+    fn visit_impl_item(&mut self, impl_item: &ast::ImplItem) {
+        if self.contains(impl_item.span) {
+            self.found_definition(impl_item.id, impl_item.span);
+        }
+        visit::walk_impl_item(self, impl_item);
+    }
+
+    fn visit_expr(&mut self, expr: &ast::Expr) {
+        if self.contains(expr.span) {
+            let def_id = match expr.node {
+                ast::ExprPath(_, ref path) => self.path_def(expr.id, path),
+                ast::ExprField(..) |
+                ast::ExprStruct(..) |
+                ast::ExprMethodCall(..) => {
+                    self.save_ctxt.get_expr_data(expr).and_then(ref_id)
+                }
+                _ => None,
+            };
+            let ty = self.node_type(expr.id);
+            self.found(expr.span, ty, def_id);
+        }
+        visit::walk_expr(self, expr);
+    }
+
+    fn visit_pat(&mut self, pat: &ast::Pat) {
+        if self.contains(pat.span) {
+            let def_id = match pat.node {
+                ast::PatIdent(_, ref ident, _) => {
+                    let path = ast_util::ident_to_path(ident.span, ident.node);
+                    self.path_def(pat.id, &path)
+                }
+                ast::PatEnum(ref path, _) |
+                ast::PatStruct(ref path, _, _) |
+                ast::PatQPath(_, ref path) => self.path_def(pat.id, path),
+                _ => None,
+            };
+            let ty = self.node_type(pat.id);
+            self.found(pat.span, ty, def_id);
+        }
+        visit::walk_pat(self, pat);
+    }
+
+    fn visit_ty(&mut self, ty: &ast::Ty) {
+        if self.contains(ty.span) {
+            let def_id = match ty.node {
+                ast::TyPath(_, ref path) => self.path_def(ty.id, path),
+                _ => None,
+            };
+            self.found(ty.span, None, def_id);
+        }
+        visit::walk_ty(self, ty);
+    }
+}
@@ -31,6 +31,7 @@ use rustc_typeck::middle::infer::glb::Glb;
 use rustc_typeck::middle::infer::sub::Sub;
 use rustc_metadata::cstore::CStore;
 use rustc::front::map as hir_map;
+use rustc::mir::repr::{BasicBlock, BasicBlockData, Mir, Terminator};
 use rustc::session::{self, config};
 use std::rc::Rc;
 use syntax::{abi, ast};
@@ -42,6 +43,7 @@ use syntax::feature_gate::UnstableFeatures;
 
 use rustc_front::lowering::{lower_crate, LoweringContext};
 use rustc_front::hir;
+use rustc_mir::transform::validate;
 
 struct Env<'a, 'tcx: 'a> {
     infcx: &'a infer::InferCtxt<'a, 'tcx>,
@@ -851,3 +853,27 @@ fn walk_ty_skip_subtree() {
         assert!(expected.is_empty());
     })
 }
+
+#[test]
+fn validate_mir_rejects_nonexistent_targets() {
+    let source = "#![feature(no_core)] #![no_core] fn f() {}";
+    test_env(source,
+             errors(&["BB(0) (terminator): jumps to nonexistent block BB(4)",
+                      "BB(3) (terminator): jumps to nonexistent block BB(7)"]),
+             |env| {
+        let tcx = env.tcx();
+        let id = env.lookup_item(&["f".to_string()]);
+        let block = |terminator| BasicBlockData { statements: vec![], terminator: terminator };
+        let mir = Mir {
+            basic_blocks: vec![block(Terminator::Goto { target: BasicBlock::new(4) }),
+                               block(Terminator::Return),
+                               block(Terminator::Diverge),
+                               block(Terminator::Goto { target: BasicBlock::new(7) })],
+            return_ty: ty::FnConverging(tcx.mk_nil()),
+            var_decls: vec![],
+            arg_decls: vec![],
+            temp_decls: vec![],
+        };
+        validate::validate(tcx, "building it", id, &mir);
+    })
+}
@@ -667,6 +667,7 @@ pub fn lower_item_underscore(lctx: &LoweringContext, i: &Item_) -> hir::Item_ {
                            items)
         }
         ItemMac(_) => panic!("Shouldn't still be around"),
+        ItemError => panic!("Shouldn't lower an item which failed to parse"),
     }
 }
 
@@ -1537,6 +1538,7 @@ pub fn lower_stmt(lctx: &LoweringContext, s: &Stmt) -> hir::Stmt {
             }
         }
         StmtMac(..) => panic!("Shouldn't exist here"),
+        StmtError => panic!("Shouldn't lower a statement which failed to parse"),
     }
 }
 
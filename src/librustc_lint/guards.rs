@@ -0,0 +1,446 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lints for locks and cells acquired again while a guard for them is
+//! still live, which deadlocks (for `Mutex` and `RwLock`) or panics (for
+//! `RefCell`).
+//!
+//! These work on the MIR, where every temporary and every drop is explicit,
+//! so a guard held by, say, the scrutinee of a `match` is live until the
+//! end of the `match` just as it is at runtime. A forward dataflow analysis
+//! tracks which locals may hold an exclusive guard (a `MutexGuard`,
+//! `RwLockWriteGuard` or `RefMut`), and for which lock: a guard is acquired
+//! by a call to `lock`, `write` or `borrow_mut`, passed on when the local
+//! holding it is moved into a value whose type contains a guard (as by
+//! `unwrap`), and released when that local is dropped or moved elsewhere
+//! (as by `drop`).
+//!
+//! Locks are told apart by the lvalue they are borrowed from, looking
+//! through references and overloaded derefs assigned once; a lock reached
+//! through a local assigned more than once is not tracked.
+
+use lint::{LintPass, LintArray, MirContext, MirLintPass};
+use middle::ty::{self, Ty};
+use rustc::mir::repr::*;
+
+use syntax::codemap::Span;
+
+declare_lint! {
+    pub RELOCK_WHILE_HELD,
+    Warn,
+    "detect a `Mutex` or `RwLock` locked again while an exclusive guard for it is live"
+}
+
+declare_lint! {
+    pub REBORROW_WHILE_HELD,
+    Warn,
+    "detect a `RefCell` borrowed again while a `RefMut` for it is live"
+}
+
+/// The types which can be locked.
+const LOCKS: &'static [&'static str] = &[
+    "std::sync::mutex::Mutex",
+    "std::sync::rwlock::RwLock",
+    "core::cell::RefCell",
+];
+
+/// The guards for exclusive access, which conflict with any other guard.
+const EXCLUSIVE_GUARDS: &'static [&'static str] = &[
+    "std::sync::mutex::MutexGuard",
+    "std::sync::rwlock::RwLockWriteGuard",
+    "core::cell::RefMut",
+];
+
+/// The guards for shared access, which only conflict with exclusive ones.
+const SHARED_GUARDS: &'static [&'static str] = &[
+    "std::sync::rwlock::RwLockReadGuard",
+    "core::cell::Ref",
+];
+
+/// The methods of `LOCKS` which acquire a guard and wait for (or panic
+/// on) any conflicting one.
+const ACQUIRE_METHODS: &'static [&'static str] = &["lock", "read", "write", "borrow", "borrow_mut"];
+
+/// How far to look through references for the lvalue a lock is borrowed from.
+const RESOLVE_LIMIT: usize = 16;
+
+#[derive(Copy, Clone)]
+pub struct HeldGuards;
+
+impl LintPass for HeldGuards {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(RELOCK_WHILE_HELD, REBORROW_WHILE_HELD)
+    }
+}
+
+impl MirLintPass for HeldGuards {
+    fn check_mir<'a, 'tcx>(&mut self, cx: &MirContext<'a, 'tcx>, mir: &Mir<'tcx>) {
+        let mut checker = GuardChecker {
+            cx: cx,
+            mir: mir,
+            locks: vec![],
+        };
+        let acquisitions = checker.find_acquisitions();
+        if checker.locks.is_empty() {
+            return;
+        }
+        checker.check(&acquisitions);
+    }
+}
+
+/// A call which acquires a guard for a lock.
+struct Acquisition {
+    /// The index of the lock in `GuardChecker::locks`.
+    lock: usize,
+    /// Whether the guard is for exclusive access.
+    exclusive: bool,
+    /// Whether the lock is a `RefCell`, rather than a `Mutex` or `RwLock`.
+    cell: bool,
+    span: Span,
+}
+
+/// The locals which may hold an exclusive guard, by lock: the bit for
+/// local `l` and lock `k` is `l * locks + k`.
+type HeldState = Vec<bool>;
+
+struct GuardChecker<'a, 'b: 'a, 'tcx: 'b> {
+    cx: &'a MirContext<'b, 'tcx>,
+    mir: &'a Mir<'tcx>,
+    /// The lvalues locks are borrowed from, each of which is one lock.
+    locks: Vec<Lvalue<'tcx>>,
+}
+
+impl<'a, 'b, 'tcx> GuardChecker<'a, 'b, 'tcx> {
+    /// Finds the call acquiring a guard at the end of each block, if any.
+    fn find_acquisitions(&mut self) -> Vec<Option<Acquisition>> {
+        let mir = self.mir;
+        mir.all_basic_blocks().into_iter().map(|bb| {
+            match mir.basic_block_data(bb).terminator {
+                Terminator::Call { ref data, .. } => self.acquisition(data),
+                _ => None,
+            }
+        }).collect()
+    }
+
+    fn acquisition(&mut self, data: &CallData<'tcx>) -> Option<Acquisition> {
+        let tcx = self.cx.tcx;
+        let (def_id, span) = match data.func {
+            Operand::Constant(Constant { literal: Literal::Item { def_id, .. }, span, .. }) => {
+                (def_id, span)
+            }
+            _ => return None,
+        };
+        if data.args.len() != 1 ||
+           !ACQUIRE_METHODS.contains(&&tcx.item_name(def_id).as_str()[..]) {
+            return None;
+        }
+        let cell = match self.mir.operand_ty(tcx, &data.args[0]).sty {
+            ty::TyRef(_, ty::TypeAndMut { ty, .. }) => match adt_path(tcx, ty) {
+                Some(ref path) if LOCKS.contains(&&path[..]) => path == "core::cell::RefCell",
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let return_ty = self.mir.lvalue_ty(tcx, &data.destination).to_ty(tcx);
+        let exclusive = if contains_guard(tcx, return_ty, EXCLUSIVE_GUARDS) {
+            true
+        } else if contains_guard(tcx, return_ty, SHARED_GUARDS) {
+            false
+        } else {
+            return None;
+        };
+        let lvalue = match self.resolve_operand(&data.args[0], RESOLVE_LIMIT) {
+            Some(lvalue) => lvalue,
+            None => return None,
+        };
+        let lock = match self.locks.iter().position(|lock| *lock == lvalue) {
+            Some(lock) => lock,
+            None => {
+                self.locks.push(lvalue);
+                self.locks.len() - 1
+            }
+        };
+        Some(Acquisition {
+            lock: lock,
+            exclusive: exclusive,
+            cell: cell,
+            span: span,
+        })
+    }
+
+    /// The lvalue the reference `operand` points to.
+    fn resolve_operand(&self, operand: &Operand<'tcx>, limit: usize) -> Option<Lvalue<'tcx>> {
+        let lvalue = match *operand {
+            Operand::Consume(ref lvalue) => lvalue,
+            Operand::Constant(_) => return None,
+        };
+        if limit == 0 {
+            return None;
+        }
+        match self.definition(lvalue) {
+            Some(Definition::Rvalue(&Rvalue::Ref(_, _, ref referent))) => {
+                self.resolve_lvalue(referent, limit - 1)
+            }
+            Some(Definition::Rvalue(&Rvalue::Use(ref operand))) => {
+                self.resolve_operand(operand, limit - 1)
+            }
+            // An overloaded deref, as of an `Arc` or `Rc`.
+            Some(Definition::Call(data)) if data.args.len() == 1 => {
+                let def_id = match data.func {
+                    Operand::Constant(Constant { literal: Literal::Item { def_id, .. }, .. }) => {
+                        def_id
+                    }
+                    _ => return None,
+                };
+                let name = self.cx.tcx.item_name(def_id);
+                if name.as_str() != "deref" && name.as_str() != "deref_mut" {
+                    return None;
+                }
+                self.resolve_operand(&data.args[0], limit - 1).map(|pointer| {
+                    Lvalue::Projection(Box::new(LvalueProjection {
+                        base: pointer,
+                        elem: ProjectionElem::Deref,
+                    }))
+                })
+            }
+            Some(_) => None,
+            None => Some(Lvalue::Projection(Box::new(LvalueProjection {
+                base: lvalue.clone(),
+                elem: ProjectionElem::Deref,
+            }))),
+        }
+    }
+
+    /// `lvalue`, with derefs of locals replaced by what they point to.
+    fn resolve_lvalue(&self, lvalue: &Lvalue<'tcx>, limit: usize) -> Option<Lvalue<'tcx>> {
+        match *lvalue {
+            Lvalue::Projection(ref proj) => {
+                if proj.elem == ProjectionElem::Deref && self.local_index(&proj.base).is_some() {
+                    return self.resolve_operand(&Operand::Consume(proj.base.clone()), limit);
+                }
+                self.resolve_lvalue(&proj.base, limit).map(|base| {
+                    Lvalue::Projection(Box::new(LvalueProjection {
+                        base: base,
+                        elem: proj.elem.clone(),
+                    }))
+                })
+            }
+            _ => Some(lvalue.clone()),
+        }
+    }
+
+    /// The only assignment to the local `lvalue`, if there is exactly one.
+    /// Locals which are never assigned, like args, have no definition.
+    fn definition(&self, lvalue: &Lvalue<'tcx>) -> Option<Definition<'a, 'tcx>> {
+        let mir = self.mir;
+        let mut definition = None;
+        let mut count = 0;
+        for bb in mir.all_basic_blocks() {
+            let data = mir.basic_block_data(bb);
+            for statement in &data.statements {
+                if let StatementKind::Assign(ref dest, ref rvalue) = statement.kind {
+                    if dest == lvalue {
+                        definition = Some(Definition::Rvalue(rvalue));
+                        count += 1;
+                    }
+                }
+            }
+            if let Terminator::Call { ref data, .. } = data.terminator {
+                if data.destination == *lvalue {
+                    definition = Some(Definition::Call(data));
+                    count += 1;
+                }
+            }
+        }
+        match count {
+            0 => None,
+            1 => definition,
+            _ => Some(Definition::Many),
+        }
+    }
+
+    fn check(&self, acquisitions: &[Option<Acquisition>]) {
+        let mir = self.mir;
+        let blocks = mir.all_basic_blocks();
+        let bits = self.local_count() * self.locks.len();
+
+        // Iterate to a fixed point, then run through each block once more to
+        // report what it acquires.
+        let mut on_entry: Vec<HeldState> = blocks.iter().map(|_| vec![false; bits]).collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &bb in &blocks {
+                let mut state = on_entry[bb.index()].clone();
+                self.transfer(bb, acquisitions, &mut state, false);
+                for &succ in mir.basic_block_data(bb).terminator.successors() {
+                    for (bit, &held) in state.iter().enumerate() {
+                        if held && !on_entry[succ.index()][bit] {
+                            on_entry[succ.index()][bit] = true;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+        for &bb in &blocks {
+            let mut state = on_entry[bb.index()].clone();
+            self.transfer(bb, acquisitions, &mut state, true);
+        }
+    }
+
+    fn transfer(&self,
+                bb: BasicBlock,
+                acquisitions: &[Option<Acquisition>],
+                state: &mut HeldState,
+                report: bool) {
+        let data = self.mir.basic_block_data(bb);
+        for statement in &data.statements {
+            match statement.kind {
+                StatementKind::Assign(ref dest, ref rvalue) => {
+                    let operands: Vec<_> = match *rvalue {
+                        Rvalue::Use(ref operand) |
+                        Rvalue::Repeat(ref operand, _) |
+                        Rvalue::Cast(_, ref operand, _) |
+                        Rvalue::UnaryOp(_, ref operand) => vec![operand],
+                        Rvalue::BinaryOp(_, ref lhs, ref rhs) => vec![lhs, rhs],
+                        Rvalue::Aggregate(_, ref operands) => operands.iter().collect(),
+                        _ => vec![],
+                    };
+                    self.move_into(dest, &operands, state);
+                }
+                StatementKind::Drop(_, ref lvalue) => {
+                    if let Some(local) = self.local_index(lvalue) {
+                        self.release(local, state);
+                    }
+                }
+            }
+        }
+
+        if let Terminator::Call { ref data, .. } = data.terminator {
+            match acquisitions[bb.index()] {
+                Some(ref acquisition) => {
+                    let held = (0..self.local_count()).any(|local| {
+                        state[local * self.locks.len() + acquisition.lock]
+                    });
+                    if held && report {
+                        self.report(acquisition);
+                    }
+                    if let Some(local) = self.local_index(&data.destination) {
+                        self.release(local, state);
+                        if acquisition.exclusive {
+                            state[local * self.locks.len() + acquisition.lock] = true;
+                        }
+                    }
+                }
+                None => {
+                    let operands: Vec<_> = data.args.iter().collect();
+                    self.move_into(&data.destination, &operands, state);
+                }
+            }
+        }
+    }
+
+    /// Assigns `dest` from `operands`, passing on the guards these hold if
+    /// `dest` can hold a guard and releasing them otherwise.
+    fn move_into(&self, dest: &Lvalue<'tcx>, operands: &[&Operand<'tcx>], state: &mut HeldState) {
+        let tcx = self.cx.tcx;
+        let locks = self.locks.len();
+        let mut moved = vec![false; locks];
+        for operand in operands {
+            if let Operand::Consume(ref lvalue) = **operand {
+                if let Some(local) = self.local_index(lvalue) {
+                    for lock in 0..locks {
+                        moved[lock] |= state[local * locks + lock];
+                    }
+                    self.release(local, state);
+                }
+            }
+        }
+        if let Some(local) = self.local_index(dest) {
+            self.release(local, state);
+            let dest_ty = self.mir.lvalue_ty(tcx, dest).to_ty(tcx);
+            if contains_guard(tcx, dest_ty, EXCLUSIVE_GUARDS) {
+                for lock in 0..locks {
+                    state[local * locks + lock] = moved[lock];
+                }
+            }
+        }
+    }
+
+    fn release(&self, local: usize, state: &mut HeldState) {
+        let locks = self.locks.len();
+        for lock in 0..locks {
+            state[local * locks + lock] = false;
+        }
+    }
+
+    fn report(&self, acquisition: &Acquisition) {
+        if acquisition.cell {
+            self.cx.span_lint(REBORROW_WHILE_HELD,
+                              acquisition.span,
+                              "`RefCell` borrowed while a `RefMut` for it may still be live; \
+                               this will panic");
+        } else {
+            self.cx.span_lint(RELOCK_WHILE_HELD,
+                              acquisition.span,
+                              "lock acquired while an exclusive guard for it may still be live; \
+                               this will deadlock or panic");
+        }
+    }
+
+    fn local_count(&self) -> usize {
+        self.mir.var_decls.len() + self.mir.temp_decls.len() + self.mir.arg_decls.len() + 1
+    }
+
+    fn local_index(&self, lvalue: &Lvalue) -> Option<usize> {
+        let mir = self.mir;
+        match *lvalue {
+            Lvalue::Var(index) => Some(index as usize),
+            Lvalue::Temp(index) => Some(mir.var_decls.len() + index as usize),
+            Lvalue::Arg(index) => {
+                Some(mir.var_decls.len() + mir.temp_decls.len() + index as usize)
+            }
+            Lvalue::ReturnPointer => Some(self.local_count() - 1),
+            Lvalue::Static(_) | Lvalue::Projection(_) => None,
+        }
+    }
+}
+
+enum Definition<'a, 'tcx: 'a> {
+    Rvalue(&'a Rvalue<'tcx>),
+    Call(&'a CallData<'tcx>),
+    Many,
+}
+
+fn adt_path<'tcx>(tcx: &ty::ctxt<'tcx>, ty: Ty<'tcx>) -> Option<String> {
+    match ty.sty {
+        ty::TyStruct(def, _) => Some(tcx.item_path_str(def.did)),
+        _ => None,
+    }
+}
+
+/// Whether `ty` is, or contains by value, one of the guards `guards`.
+fn contains_guard<'tcx>(tcx: &ty::ctxt<'tcx>, ty: Ty<'tcx>, guards: &[&str]) -> bool {
+    let mut walker = ty.walk();
+    while let Some(ty) = walker.next() {
+        match ty.sty {
+            ty::TyRef(..) | ty::TyRawPtr(..) => walker.skip_current_subtree(),
+            _ => {
+                if let Some(ref path) = adt_path(tcx, ty) {
+                    if guards.contains(&&path[..]) {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+    false
+}
@@ -59,11 +59,13 @@ use lint::LintId;
 
 mod bad_style;
 mod builtin;
+mod guards;
 mod types;
 mod unused;
 
 use bad_style::*;
 use builtin::*;
+use guards::*;
 use types::*;
 use unused::*;
 
@@ -95,6 +97,14 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
             )
     }
 
+    macro_rules! add_mir_builtin {
+        ($sess:ident, $($name:ident),*,) => (
+            {$(
+                store.register_mir_pass($sess, false, box $name);
+                )*}
+            )
+    }
+
     macro_rules! add_lint_group {
         ($sess:ident, $name:expr, $($lint:ident),*) => (
             store.register_group($sess, false, $name, vec![$(LintId::of($lint)),*]);
@@ -138,6 +148,10 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
                           MissingDebugImplementations,
                           );
 
+    add_mir_builtin!(sess,
+                     HeldGuards,
+                     );
+
     add_lint_group!(sess, "bad_style",
                     NON_CAMEL_CASE_TYPES, NON_SNAKE_CASE, NON_UPPER_CASE_GLOBALS);
 
@@ -146,6 +160,9 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
                     UNUSED_MUT, UNREACHABLE_CODE, UNUSED_MUST_USE,
                     UNUSED_UNSAFE, PATH_STATEMENTS, UNUSED_ATTRIBUTES);
 
+    add_lint_group!(sess, "held_guards",
+                    RELOCK_WHILE_HELD, REBORROW_WHILE_HELD);
+
     // We have one lint pass defined specially
     store.register_late_pass(sess, false, box lint::GatherNodeLevels);
 
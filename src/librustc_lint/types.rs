@@ -15,7 +15,7 @@ use middle::ty::{self, Ty};
 use middle::const_eval::{eval_const_expr_partial, ConstVal};
 use middle::const_eval::EvalHint::ExprTypeChecked;
 use util::nodemap::{FnvHashSet};
-use lint::{LateContext, LintContext, LintArray};
+use lint::{self, LateContext, LintContext, LintArray};
 use lint::{LintPass, LateLintPass};
 
 use std::cmp;
@@ -29,7 +29,7 @@ use syntax::ast::{TyIs, TyUs, TyI8, TyU8, TyI16, TyU16, TyI32, TyU32, TyI64, TyU
 
 use rustc_front::hir;
 use rustc_front::intravisit::{self, Visitor};
-use rustc_front::util::is_shift_binop;
+use rustc_front::util::{binop_to_string, is_shift_binop};
 
 declare_lint! {
     UNUSED_COMPARISONS,
@@ -49,6 +49,12 @@ declare_lint! {
     "shift exceeds the type's number of bits"
 }
 
+declare_lint! {
+    UNCHECKED_ARITHMETIC,
+    Allow,
+    "integer `+`, `-`, `*` or cast which may overflow without overflow checks"
+}
+
 #[derive(Copy, Clone)]
 pub struct TypeLimits {
     /// Id of the last visited negated expression
@@ -65,7 +71,8 @@ impl TypeLimits {
 
 impl LintPass for TypeLimits {
     fn get_lints(&self) -> LintArray {
-        lint_array!(UNUSED_COMPARISONS, OVERFLOWING_LITERALS, EXCEEDING_BITSHIFTS)
+        lint_array!(UNUSED_COMPARISONS, OVERFLOWING_LITERALS, EXCEEDING_BITSHIFTS,
+                    UNCHECKED_ARITHMETIC)
     }
 }
 
@@ -103,6 +110,8 @@ impl LateLintPass for TypeLimits {
                 }
             },
             hir::ExprBinary(binop, ref l, ref r) => {
+                check_arithmetic(cx, e, binop, l, r);
+
                 if is_comparison(binop) && !check_limits(cx.tcx, binop, &**l, &**r) {
                     cx.span_lint(UNUSED_COMPARISONS, e.span,
                                  "comparison is useless due to type limits");
@@ -133,6 +142,12 @@ impl LateLintPass for TypeLimits {
                     };
                 }
             },
+            hir::ExprAssignOp(binop, ref l, ref r) => {
+                check_arithmetic(cx, e, binop, l, r);
+            },
+            hir::ExprCast(ref expr, _) => {
+                check_cast(cx, e, expr);
+            },
             hir::ExprLit(ref lit) => {
                 match cx.tcx.node_id_to_type(e.id).sty {
                     ty::TyInt(t) => {
@@ -221,28 +236,6 @@ impl LateLintPass for TypeLimits {
             })
         }
 
-        // for isize & usize, be conservative with the warnings, so that the
-        // warnings are consistent between 32- and 64-bit platforms
-        fn int_ty_range(int_ty: ast::IntTy) -> (i64, i64) {
-            match int_ty {
-                ast::TyIs => (i64::MIN,        i64::MAX),
-                ast::TyI8 =>    (i8::MIN  as i64, i8::MAX  as i64),
-                ast::TyI16 =>   (i16::MIN as i64, i16::MAX as i64),
-                ast::TyI32 =>   (i32::MIN as i64, i32::MAX as i64),
-                ast::TyI64 =>   (i64::MIN,        i64::MAX)
-            }
-        }
-
-        fn uint_ty_range(uint_ty: ast::UintTy) -> (u64, u64) {
-            match uint_ty {
-                ast::TyUs => (u64::MIN,         u64::MAX),
-                ast::TyU8 =>    (u8::MIN   as u64, u8::MAX   as u64),
-                ast::TyU16 =>   (u16::MIN  as u64, u16::MAX  as u64),
-                ast::TyU32 =>   (u32::MIN  as u64, u32::MAX  as u64),
-                ast::TyU64 =>   (u64::MIN,         u64::MAX)
-            }
-        }
-
         fn float_ty_range(float_ty: ast::FloatTy) -> (f64, f64) {
             match float_ty {
                 ast::TyF32 => (f32::MIN as f64, f32::MAX as f64),
@@ -335,6 +328,234 @@ impl LateLintPass for TypeLimits {
     }
 }
 
+// for isize & usize, be conservative with the warnings, so that the
+// warnings are consistent between 32- and 64-bit platforms
+fn int_ty_range(int_ty: ast::IntTy) -> (i64, i64) {
+    match int_ty {
+        ast::TyIs => (i64::MIN,        i64::MAX),
+        ast::TyI8 =>    (i8::MIN  as i64, i8::MAX  as i64),
+        ast::TyI16 =>   (i16::MIN as i64, i16::MAX as i64),
+        ast::TyI32 =>   (i32::MIN as i64, i32::MAX as i64),
+        ast::TyI64 =>   (i64::MIN,        i64::MAX)
+    }
+}
+
+fn uint_ty_range(uint_ty: ast::UintTy) -> (u64, u64) {
+    match uint_ty {
+        ast::TyUs => (u64::MIN,         u64::MAX),
+        ast::TyU8 =>    (u8::MIN   as u64, u8::MAX   as u64),
+        ast::TyU16 =>   (u16::MIN  as u64, u16::MAX  as u64),
+        ast::TyU32 =>   (u32::MIN  as u64, u32::MAX  as u64),
+        ast::TyU64 =>   (u64::MIN,         u64::MAX)
+    }
+}
+
+/// The range of values of integer type `ty`. `isize` and `usize` get the
+/// range of 64-bit integers if `widest` is set, so that they are assumed to
+/// hold any value they can hold on some platform, and that of 32-bit integers
+/// otherwise, so that they are assumed to hold only those values they can
+/// hold on every platform.
+fn type_range(ty: Ty, widest: bool) -> Option<ValueRange> {
+    match ty.sty {
+        ty::TyInt(ast::TyIs) if !widest => Some(ValueRange::Signed(i32::MIN as i64,
+                                                                    i32::MAX as i64)),
+        ty::TyUint(ast::TyUs) if !widest => Some(ValueRange::Unsigned(0, u32::MAX as u64)),
+        ty::TyInt(t) => {
+            let (min, max) = int_ty_range(t);
+            Some(ValueRange::Signed(min, max))
+        }
+        ty::TyUint(t) => {
+            let (min, max) = uint_ty_range(t);
+            Some(ValueRange::Unsigned(min, max))
+        }
+        _ => None,
+    }
+}
+
+/// An inclusive range of integer values.
+#[derive(Copy, Clone, Debug)]
+enum ValueRange {
+    Signed(i64, i64),
+    Unsigned(u64, u64),
+}
+
+impl ValueRange {
+    fn fits_in(self, other: ValueRange) -> bool {
+        match (self, other) {
+            (ValueRange::Signed(lo, hi), ValueRange::Signed(min, max)) => lo >= min && hi <= max,
+            (ValueRange::Unsigned(lo, hi), ValueRange::Unsigned(min, max)) => {
+                lo >= min && hi <= max
+            }
+            (ValueRange::Signed(lo, hi), ValueRange::Unsigned(min, max)) => {
+                lo >= 0 && lo as u64 >= min && hi as u64 <= max
+            }
+            (ValueRange::Unsigned(lo, hi), ValueRange::Signed(min, max)) => {
+                (max >= 0 && hi <= max as u64) && (min <= 0 || lo >= min as u64)
+            }
+        }
+    }
+
+    /// `self`, as a range of values of the signedness of `target`, if it
+    /// fits in it.
+    fn cast_to(self, target: ValueRange) -> Option<ValueRange> {
+        if !self.fits_in(target) {
+            return None;
+        }
+        Some(match (self, target) {
+            (ValueRange::Signed(lo, hi), ValueRange::Unsigned(..)) => {
+                ValueRange::Unsigned(lo as u64, hi as u64)
+            }
+            (ValueRange::Unsigned(lo, hi), ValueRange::Signed(..)) => {
+                ValueRange::Signed(lo as i64, hi as i64)
+            }
+            _ => self,
+        })
+    }
+
+    /// The range of `self op other`, or `None` if it cannot be computed
+    /// without overflowing 64 bits.
+    fn binop(self, op: hir::BinOp_, other: ValueRange) -> Option<ValueRange> {
+        fn bounds<T: Ord + Copy>(values: &[Option<T>]) -> Option<(T, T)> {
+            let mut bounds: Option<(T, T)> = None;
+            for &value in values {
+                let value = match value {
+                    Some(value) => value,
+                    None => return None,
+                };
+                bounds = Some(match bounds {
+                    Some((lo, hi)) => (cmp::min(lo, value), cmp::max(hi, value)),
+                    None => (value, value),
+                });
+            }
+            bounds
+        }
+
+        match (self, other) {
+            (ValueRange::Signed(a, b), ValueRange::Signed(c, d)) => {
+                let corners = match op {
+                    hir::BiAdd => vec![a.checked_add(c), b.checked_add(d)],
+                    hir::BiSub => vec![a.checked_sub(d), b.checked_sub(c)],
+                    hir::BiMul => vec![a.checked_mul(c), a.checked_mul(d),
+                                       b.checked_mul(c), b.checked_mul(d)],
+                    _ => return None,
+                };
+                bounds(&corners).map(|(lo, hi)| ValueRange::Signed(lo, hi))
+            }
+            (ValueRange::Unsigned(a, b), ValueRange::Unsigned(c, d)) => {
+                let corners = match op {
+                    hir::BiAdd => vec![a.checked_add(c), b.checked_add(d)],
+                    hir::BiSub => vec![a.checked_sub(d), b.checked_sub(c)],
+                    hir::BiMul => vec![a.checked_mul(c), b.checked_mul(d)],
+                    _ => return None,
+                };
+                bounds(&corners).map(|(lo, hi)| ValueRange::Unsigned(lo, hi))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The range of values `e`, of integer type, provably lies in, if it is
+/// narrower than that of its type: for constants, casts from narrower types,
+/// masks and remainders by constants, and arithmetic on such values.
+fn expr_range(cx: &LateContext, e: &hir::Expr) -> Option<ValueRange> {
+    match eval_const_expr_partial(cx.tcx, e, ExprTypeChecked, None) {
+        Ok(ConstVal::Int(v)) => return Some(ValueRange::Signed(v, v)),
+        Ok(ConstVal::Uint(v)) => return Some(ValueRange::Unsigned(v, v)),
+        _ => {}
+    }
+    if cx.tcx.is_method_call(e.id) {
+        return None;
+    }
+    match e.node {
+        hir::ExprCast(ref inner, _) => {
+            let range = expr_range(cx, inner)
+                .or_else(|| type_range(cx.tcx.expr_ty(inner), true));
+            let target = type_range(cx.tcx.expr_ty(e), false);
+            match (range, target) {
+                (Some(range), Some(target)) => range.cast_to(target),
+                _ => None,
+            }
+        }
+        hir::ExprBinary(op, ref l, ref r) => {
+            match (op.node, expr_range(cx, l), expr_range(cx, r)) {
+                (hir::BiBitAnd, Some(ValueRange::Unsigned(_, max)), _) |
+                (hir::BiBitAnd, _, Some(ValueRange::Unsigned(_, max))) => {
+                    Some(ValueRange::Unsigned(0, max))
+                }
+                (hir::BiRem, _, Some(ValueRange::Unsigned(_, max))) if max > 0 => {
+                    Some(ValueRange::Unsigned(0, max - 1))
+                }
+                (hir::BiAdd, Some(a), Some(b)) |
+                (hir::BiSub, Some(a), Some(b)) |
+                (hir::BiMul, Some(a), Some(b)) => {
+                    let target = type_range(cx.tcx.expr_ty(e), false);
+                    match (a.binop(op.node, b), target) {
+                        (Some(range), Some(target)) if range.fits_in(target) => Some(range),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Lints `+`, `-` or `*` of the integer operands `l` and `r`, in `e`, unless
+/// their ranges show it cannot overflow.
+fn check_arithmetic(cx: &LateContext, e: &hir::Expr, op: hir::BinOp,
+                    l: &hir::Expr, r: &hir::Expr) {
+    let method = match op.node {
+        hir::BiAdd => "add",
+        hir::BiSub => "sub",
+        hir::BiMul => "mul",
+        _ => return,
+    };
+    if cx.current_level(UNCHECKED_ARITHMETIC) == lint::Allow || cx.tcx.is_method_call(e.id) {
+        return;
+    }
+    let ty = cx.tcx.expr_ty(l);
+    let target = match type_range(ty, false) {
+        Some(target) => target,
+        None => return,
+    };
+    let bounded = match (expr_range(cx, l), expr_range(cx, r)) {
+        (Some(a), Some(b)) => a.binop(op.node, b).map_or(false, |range| range.fits_in(target)),
+        _ => false,
+    };
+    if !bounded {
+        cx.span_lint_help(UNCHECKED_ARITHMETIC, e.span,
+                          &format!("`{}` on `{}` may overflow",
+                                   binop_to_string(op.node), ty),
+                          &format!("use `checked_{0}`, `wrapping_{0}` or `saturating_{0}` \
+                                    to handle overflow explicitly", method));
+    }
+}
+
+/// Lints the cast `e` of the integer `inner`, unless its range shows it fits
+/// in the target type.
+fn check_cast(cx: &LateContext, e: &hir::Expr, inner: &hir::Expr) {
+    if cx.current_level(UNCHECKED_ARITHMETIC) == lint::Allow {
+        return;
+    }
+    let (from, to) = (cx.tcx.expr_ty(inner), cx.tcx.expr_ty(e));
+    let target = match type_range(to, false) {
+        Some(target) => target,
+        None => return,
+    };
+    let range = match expr_range(cx, inner).or_else(|| type_range(from, true)) {
+        Some(range) => range,
+        None => return,
+    };
+    if !range.fits_in(target) {
+        cx.span_lint_help(UNCHECKED_ARITHMETIC, e.span,
+                          &format!("cast from `{}` to `{}` may truncate or wrap", from, to),
+                          &format!("check that the value is within the range of `{}` \
+                                    first", to));
+    }
+}
+
 declare_lint! {
     IMPROPER_CTYPES,
     Warn,
@@ -167,6 +167,7 @@ pub fn decode_inlined_item<'tcx>(cdata: &cstore::crate_metadata,
         region::resolve_inlined_item(&tcx.sess, &tcx.region_maps, ii);
         decode_side_tables(dcx, ast_doc);
         copy_item_types(dcx, ii, orig_did);
+        record_overflow_checks(dcx, ii, orig_did);
         match *ii {
           InlinedItem::Item(ref i) => {
             debug!(">>> DECODED ITEM >>>\n{}\n<<< DECODED ITEM <<<",
@@ -1234,6 +1235,17 @@ fn decode_side_tables(dcx: &DecodeContext,
 
 // copy the tcache entries from the original item to the new
 // inlined item
+fn record_overflow_checks(dcx: &DecodeContext, ii: &InlinedItem, orig_did: DefId) {
+    let item_node_id = match ii {
+        &InlinedItem::Item(ref i) => i.id,
+        &InlinedItem::TraitItem(_, ref ti) => ti.id,
+        &InlinedItem::ImplItem(_, ref ii) => ii.id,
+        &InlinedItem::Foreign(_) => return
+    };
+    let on = decoder::get_overflow_checks(dcx.cdata, orig_did.index);
+    dcx.tcx.inlined_overflow_checks.borrow_mut().insert(item_node_id, on);
+}
+
 fn copy_item_types(dcx: &DecodeContext, ii: &InlinedItem, orig_did: DefId) {
     fn copy_item_type(dcx: &DecodeContext,
                       inlined_id: ast::NodeId,
@@ -51,6 +51,7 @@ pub const tag_def_key: usize = 0x2c;
 pub const tag_index: usize = 0x110; // top-level only
 pub const tag_xref_index: usize = 0x111; // top-level only
 pub const tag_xref_data: usize = 0x112; // top-level only
+pub const tag_crate_no_drop_flags: usize = 0x113; // top-level only
 
 pub const tag_meta_item_name_value: usize = 0x2f;
 
@@ -238,6 +239,10 @@ pub const tag_impl_coerce_unsized_kind: usize = 0xa5;
 
 pub const tag_items_data_item_constness: usize = 0xa6;
 
+pub const tag_item_span: usize = 0xa7;
+
+pub const tag_items_data_item_overflow_checks: usize = 0xa8;
+
 pub const tag_rustc_version: usize = 0x10f;
 pub fn rustc_version() -> String {
     format!(
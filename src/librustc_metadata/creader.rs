@@ -269,6 +269,19 @@ impl<'a> CrateReader<'a> {
         }
     }
 
+    /// Code translated without `-Z orbit` relies on the drop flags of the
+    /// types it drops, so it cannot use the types of a crate which has none.
+    fn verify_drop_flags(&self, name: &str, span: Span, metadata: &MetadataBlob) {
+        if !self.sess.opts.debugging_opts.orbit &&
+           !decoder::crate_has_drop_flags(metadata.as_slice()) {
+            self.sess.span_err(span,
+                               &format!("the crate `{}` has been compiled with `-Z orbit`, \
+                                         which crates using it must be compiled with as well",
+                                        name));
+            self.sess.abort_if_errors();
+        }
+    }
+
     fn register_crate(&mut self,
                       root: &Option<CratePaths>,
                       ident: &str,
@@ -279,6 +292,7 @@ impl<'a> CrateReader<'a> {
                       -> (ast::CrateNum, Rc<cstore::crate_metadata>,
                           cstore::CrateSource) {
         self.verify_rustc_version(name, span, &lib.metadata);
+        self.verify_drop_flags(name, span, &lib.metadata);
 
         // Claim this crate number and cache it
         let cnum = self.next_crate_num;
@@ -30,6 +30,7 @@ use std::rc::Rc;
 use std::path::PathBuf;
 use syntax::ast;
 use syntax::attr;
+use syntax::codemap::Span;
 use syntax::parse::token;
 use rustc_back::svh::Svh;
 use rustc_back::target::Target;
@@ -320,6 +321,11 @@ impl<'tcx> CrateStore<'tcx> for cstore::CStore {
         self.get_crate_data(cnum).is_allocator()
     }
 
+    fn has_drop_flags(&self, cnum: ast::CrateNum) -> bool
+    {
+        self.get_crate_data(cnum).has_drop_flags()
+    }
+
     fn crate_attrs(&self, cnum: ast::CrateNum) -> Vec<ast::Attribute>
     {
         decoder::get_crate_attributes(self.get_crate_data(cnum).data())
@@ -371,6 +377,18 @@ impl<'tcx> CrateStore<'tcx> for cstore::CStore {
         local_path.into_iter().chain(path).collect()
     }
 
+    fn extern_def_path(&self, def: DefId) -> hir_map::DefPath
+    {
+        let cdata = self.get_crate_data(def.krate);
+        decoder::def_path(&*cdata, def.index)
+    }
+
+    fn def_span(&self, tcx: &ty::ctxt<'tcx>, def: DefId) -> Option<Span>
+    {
+        let cdata = self.get_crate_data(def.krate);
+        decoder::get_item_span(&*cdata, tcx.sess.codemap(), def.index)
+    }
+
     fn tuple_struct_definition_if_ctor(&self, did: DefId) -> Option<DefId>
     {
         let cdata = self.get_crate_data(did.krate);
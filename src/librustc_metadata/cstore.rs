@@ -318,6 +318,10 @@ impl crate_metadata {
         let attrs = decoder::get_crate_attributes(self.data());
         attr::contains_name(&attrs, "needs_allocator")
     }
+
+    pub fn has_drop_flags(&self) -> bool {
+        decoder::crate_has_drop_flags(self.data())
+    }
 }
 
 impl MetadataBlob {
@@ -1222,6 +1222,11 @@ pub fn get_crate_triple(data: &[u8]) -> Option<String> {
     triple_doc.map(|s| s.as_str().to_string())
 }
 
+pub fn crate_has_drop_flags(data: &[u8]) -> bool {
+    let cratedoc = rbml::Doc::new(data);
+    reader::maybe_get_doc(cratedoc, tag_crate_no_drop_flags).is_none()
+}
+
 pub fn get_crate_name(data: &[u8]) -> String {
     maybe_get_crate_name(data).expect("no crate name in crate")
 }
@@ -1490,6 +1495,13 @@ pub fn is_const_fn(cdata: Cmd, id: DefIndex) -> bool {
     }
 }
 
+pub fn get_overflow_checks(cdata: Cmd, id: DefIndex) -> Option<bool> {
+    let item_doc = cdata.lookup_item(id);
+    reader::maybe_get_doc(item_doc, tag_items_data_item_overflow_checks).map(|doc| {
+        reader::doc_as_u8(doc) != 0
+    })
+}
+
 pub fn is_static(cdata: Cmd, id: DefIndex) -> bool {
     let item_doc = cdata.lookup_item(id);
     match item_family(item_doc) {
@@ -1659,6 +1671,17 @@ fn def_key(item_doc: rbml::Doc) -> hir_map::DefKey {
     }
 }
 
+/// The span of the definition `id`, translated into `codemap`, if the crate
+/// recorded one.
+pub fn get_item_span(cdata: Cmd, codemap: &codemap::CodeMap, id: DefIndex) -> Option<Span> {
+    let item_doc = cdata.lookup_item(id);
+    reader::maybe_get_doc(item_doc, tag_item_span).map(|span_doc| {
+        let mut decoder = reader::Decoder::new(span_doc);
+        let span = Span::decode(&mut decoder).unwrap();
+        translate_span(cdata, codemap, &Cell::new(0), span)
+    })
+}
+
 pub fn def_path(cdata: Cmd, id: DefIndex) -> hir_map::DefPath {
     debug!("def_path(id={:?})", id);
     hir_map::definitions::make_def_path(id, |parent| {
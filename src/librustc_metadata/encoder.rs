@@ -125,6 +125,7 @@ fn encode_def_id_and_key(ecx: &EncodeContext,
 {
     encode_def_id(rbml_w, def_id);
     encode_def_key(ecx, rbml_w, def_id);
+    encode_def_span(ecx, rbml_w, def_id);
 }
 
 fn encode_def_key(ecx: &EncodeContext,
@@ -137,6 +138,31 @@ fn encode_def_key(ecx: &EncodeContext,
     rbml_w.end_tag();
 }
 
+/// Records where the definition is in the source, for tools which link
+/// uses of it in other crates to it. Only crates built for save-analysis
+/// pay for this.
+fn encode_def_span(ecx: &EncodeContext,
+                   rbml_w: &mut Encoder,
+                   def_id: DefId)
+{
+    let opts = &ecx.tcx.sess.opts.debugging_opts;
+    if !opts.save_analysis && !opts.save_analysis_json {
+        return;
+    }
+    let span = match ecx.tcx.map.as_local_node_id(def_id).and_then(|id| ecx.tcx.map.opt_span(id)) {
+        Some(span) => span,
+        None => return,
+    };
+    // Only the filemaps of this crate are exported (see `encode_codemap`),
+    // so spans in any other file could not be translated when decoding.
+    if ecx.tcx.sess.codemap().lookup_byte_offset(span.lo).fm.is_imported() {
+        return;
+    }
+    rbml_w.start_tag(tag_item_span);
+    span.encode(rbml_w);
+    rbml_w.end_tag();
+}
+
 fn encode_trait_ref<'a, 'tcx>(rbml_w: &mut Encoder,
                               ecx: &EncodeContext<'a, 'tcx>,
                               trait_ref: ty::TraitRef<'tcx>,
@@ -490,6 +516,14 @@ fn encode_constness(rbml_w: &mut Encoder, constness: hir::Constness) {
     rbml_w.end_tag();
 }
 
+/// Records the `#[overflow_checks]` setting that applies to the fn `id`, so
+/// that crates inlining it check its arithmetic the way this one would.
+fn encode_overflow_checks(ecx: &EncodeContext, rbml_w: &mut Encoder, id: NodeId) {
+    if let Some(on) = ecx.tcx.overflow_checks_attr(id) {
+        rbml_w.wr_tagged_u8(tag_items_data_item_overflow_checks, on as u8);
+    }
+}
+
 fn encode_explicit_self(rbml_w: &mut Encoder,
                         explicit_self: &ty::ExplicitSelfCategory) {
     let tag = tag_item_trait_method_explicit_self;
@@ -749,15 +783,14 @@ fn encode_info_for_method<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
             encode_attributes(rbml_w, &impl_item.attrs);
             let scheme = ecx.tcx.lookup_item_type(m.def_id);
             let any_types = !scheme.generics.types.is_empty();
-            let needs_inline = any_types || is_default_impl ||
-                               attr::requests_inline(&impl_item.attrs);
-            if needs_inline || sig.constness == hir::Constness::Const {
+            if is_default_impl || ecx.tcx.exports_body(m.def_id) {
                 encode_inlined_item(ecx,
                                     rbml_w,
                                     InlinedItemRef::ImplItem(ecx.tcx.map.local_def_id(parent_id),
                                                              impl_item));
             }
             encode_constness(rbml_w, sig.constness);
+            encode_overflow_checks(ecx, rbml_w, impl_item.id);
             if !any_types {
                 let m_id = ecx.local_id(m.def_id);
                 encode_symbol(ecx, rbml_w, m_id);
@@ -977,14 +1010,14 @@ fn encode_info_for_item<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
         encode_name(rbml_w, item.name);
         encode_path(rbml_w, path);
         encode_attributes(rbml_w, &item.attrs);
-        let needs_inline = tps_len > 0 || attr::requests_inline(&item.attrs);
-        if needs_inline || constness == hir::Constness::Const {
+        if ecx.tcx.exports_body(def_id) {
             encode_inlined_item(ecx, rbml_w, InlinedItemRef::Item(item));
         }
         if tps_len == 0 {
             encode_symbol(ecx, rbml_w, item.id);
         }
         encode_constness(rbml_w, constness);
+        encode_overflow_checks(ecx, rbml_w, item.id);
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
         encode_method_argument_names(rbml_w, &**decl);
@@ -1369,6 +1402,7 @@ fn encode_info_for_item<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
                         encode_item_sort(rbml_w, 'p');
                         encode_inlined_item(ecx, rbml_w,
                                             InlinedItemRef::TraitItem(def_id, trait_item));
+                        encode_overflow_checks(ecx, rbml_w, trait_item.id);
                     } else {
                         encode_item_sort(rbml_w, 'r');
                     }
@@ -2053,6 +2087,14 @@ fn encode_crate_triple(rbml_w: &mut Encoder, triple: &str) {
     rbml_w.wr_tagged_str(tag_crate_triple, triple);
 }
 
+/// Records that the types of the crate have no drop-flag fields, since it
+/// was translated through the MIR with `-Z orbit` (see `trans::adt`).
+fn encode_no_drop_flags(rbml_w: &mut Encoder, ecx: &EncodeContext) {
+    if ecx.tcx.sess.opts.debugging_opts.orbit {
+        rbml_w.wr_tagged_u8(tag_crate_no_drop_flags, 1);
+    }
+}
+
 fn dylib_dependency_formats(ecx: &EncodeContext) -> String {
     match ecx.tcx.sess.dependency_formats.borrow().get(&config::CrateTypeDylib) {
         Some(arr) => {
@@ -2263,6 +2305,7 @@ fn encode_metadata_inner(rbml_w: &mut Encoder,
     encode_crate_name(rbml_w, &ecx.link_meta.crate_name);
     encode_crate_triple(rbml_w, &ecx.tcx.sess.opts.target_triple);
     encode_hash(rbml_w, &ecx.link_meta.crate_hash);
+    encode_no_drop_flags(rbml_w, &ecx);
     encode_dylib_dependency_formats(rbml_w, &ecx);
 
     let mut i = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap();
@@ -13,9 +13,13 @@
 use rustc_data_structures::fnv::FnvHashMap;
 
 use build::{BlockAnd, BlockAndExtension, Builder};
+use build::{OVERFLOW_MSG, NEG_OVERFLOW_MSG, SHIFT_OVERFLOW_MSG};
+use build::{DIV_ZERO_MSG, DIV_OVERFLOW_MSG, REM_ZERO_MSG, REM_OVERFLOW_MSG};
 use build::expr::category::{Category, RvalueFunc};
 use hair::*;
+use rustc::middle::ty::Ty;
 use rustc::mir::repr::*;
+use syntax::codemap::Span;
 
 impl<'a,'tcx> Builder<'a,'tcx> {
     /// Compile `expr`, yielding an rvalue.
@@ -52,12 +56,23 @@ impl<'a,'tcx> Builder<'a,'tcx> {
                 block.and(Rvalue::Ref(region, borrow_kind, arg_lvalue))
             }
             ExprKind::Binary { op, lhs, rhs } => {
+                let lhs = this.hir.mirror(lhs);
+                let lhs_ty = lhs.ty;
                 let lhs = unpack!(block = this.as_operand(block, lhs));
                 let rhs = unpack!(block = this.as_operand(block, rhs));
-                block.and(Rvalue::BinaryOp(op, lhs, rhs))
+                this.build_binary_op(block, op, expr_span, lhs_ty, lhs, rhs)
             }
             ExprKind::Unary { op, arg } => {
                 let arg = unpack!(block = this.as_operand(block, arg));
+                // negating the minimum value of a signed type overflows
+                if op == UnOp::Neg && expr.ty.is_signed() && this.hir.check_overflow() {
+                    let min = this.hir.int_min_literal(expr.ty);
+                    let min = this.literal_operand(expr_span, expr.ty, min);
+                    let bool_ty = this.hir.bool_ty();
+                    let is_min = this.push_binop(block, expr_span, bool_ty,
+                                                 BinOp::Eq, arg.clone(), min);
+                    block = this.panic_if(block, is_min, NEG_OVERFLOW_MSG, expr_span);
+                }
                 block.and(Rvalue::UnaryOp(op, arg))
             }
             ExprKind::Box { value } => {
@@ -208,4 +223,167 @@ impl<'a,'tcx> Builder<'a,'tcx> {
             }
         }
     }
+
+    /// Builds `lhs op rhs`. Integer `/` and `%` panic if `rhs` is zero or
+    /// the result overflows. If overflow checks are on, integer `+`, `-` and
+    /// `*` which overflow, and shifts by at least the width of `lhs_ty`,
+    /// panic as well. The panic messages are the ones trans uses.
+    pub fn build_binary_op(&mut self,
+                           mut block: BasicBlock,
+                           op: BinOp,
+                           span: Span,
+                           lhs_ty: Ty<'tcx>,
+                           lhs: Operand<'tcx>,
+                           rhs: Operand<'tcx>)
+                           -> BlockAnd<Rvalue<'tcx>> {
+        if !lhs_ty.is_integral() {
+            return block.and(Rvalue::BinaryOp(op, lhs, rhs));
+        }
+        if op == BinOp::Div || op == BinOp::Rem {
+            block = self.div_rem_check(block, op, span, lhs_ty, &lhs, &rhs);
+            return block.and(Rvalue::BinaryOp(op, lhs, rhs));
+        }
+        if !self.hir.check_overflow() {
+            return block.and(Rvalue::BinaryOp(op, lhs, rhs));
+        }
+
+        let (overflow, msg) = match op {
+            BinOp::Add | BinOp::Sub | BinOp::Mul => {
+                let result = self.temp(lhs_ty);
+                self.cfg.push_assign(block, span, &result,
+                                     Rvalue::BinaryOp(op, lhs.clone(), rhs.clone()));
+                let result = Operand::Consume(result);
+                block = self.arith_overflow_check(block, op, span, lhs_ty, lhs, rhs, &result);
+                return block.and(Rvalue::Use(result));
+            }
+            BinOp::Shl | BinOp::Shr => {
+                // casting to `u64` makes negative amounts too large as well
+                let u64_ty = self.hir.tcx().types.u64;
+                let amount = self.temp(u64_ty);
+                self.cfg.push_assign(block, span, &amount,
+                                     Rvalue::Cast(CastKind::Misc, rhs.clone(), u64_ty));
+                let bits = self.hir.int_bits(lhs_ty);
+                let bits = self.hir.int_literal(u64_ty, bits as i64);
+                let bits = self.literal_operand(span, u64_ty, bits);
+                let bool_ty = self.hir.bool_ty();
+                let overflow = self.push_binop(block, span, bool_ty, BinOp::Ge,
+                                               Operand::Consume(amount), bits);
+                (overflow, SHIFT_OVERFLOW_MSG)
+            }
+            _ => return block.and(Rvalue::BinaryOp(op, lhs, rhs)),
+        };
+        block = self.panic_if(block, overflow, msg, span);
+        block.and(Rvalue::BinaryOp(op, lhs, rhs))
+    }
+
+    /// Panics if `rhs` is zero, or if `lhs op rhs` overflows, which only
+    /// happens for a signed `lhs` of the minimum value and `rhs` of -1.
+    /// Unlike the other checks, these are made even without overflow checks,
+    /// as in `base::fail_if_zero_or_overflows`.
+    fn div_rem_check(&mut self,
+                     mut block: BasicBlock,
+                     op: BinOp,
+                     span: Span,
+                     ty: Ty<'tcx>,
+                     lhs: &Operand<'tcx>,
+                     rhs: &Operand<'tcx>)
+                     -> BasicBlock {
+        let (zero_msg, overflow_msg) = if op == BinOp::Div {
+            (DIV_ZERO_MSG, DIV_OVERFLOW_MSG)
+        } else {
+            (REM_ZERO_MSG, REM_OVERFLOW_MSG)
+        };
+        let bool_ty = self.hir.bool_ty();
+        let zero = self.hir.int_literal(ty, 0);
+        let zero = self.literal_operand(span, ty, zero);
+        let is_zero = self.push_binop(block, span, bool_ty, BinOp::Eq, rhs.clone(), zero);
+        block = self.panic_if(block, is_zero, zero_msg, span);
+        if !ty.is_signed() {
+            return block;
+        }
+
+        let minus_one = self.hir.int_literal(ty, -1);
+        let minus_one = self.literal_operand(span, ty, minus_one);
+        let is_minus_one = self.push_binop(block, span, bool_ty, BinOp::Eq,
+                                           rhs.clone(), minus_one);
+        let min = self.hir.int_min_literal(ty);
+        let min = self.literal_operand(span, ty, min);
+        let is_min = self.push_binop(block, span, bool_ty, BinOp::Eq, lhs.clone(), min);
+        let overflow = self.push_binop(block, span, bool_ty, BinOp::BitAnd, is_minus_one, is_min);
+        self.panic_if(block, overflow, overflow_msg, span)
+    }
+
+    /// Panics if `result`, which was computed as `lhs op rhs` with
+    /// wrapping, overflowed.
+    fn arith_overflow_check(&mut self,
+                            mut block: BasicBlock,
+                            op: BinOp,
+                            span: Span,
+                            ty: Ty<'tcx>,
+                            lhs: Operand<'tcx>,
+                            rhs: Operand<'tcx>,
+                            result: &Operand<'tcx>)
+                            -> BasicBlock {
+        let (signed, bool_ty) = (ty.is_signed(), self.hir.bool_ty());
+        let zero = self.hir.int_literal(ty, 0);
+        let zero = self.literal_operand(span, ty, zero);
+        let overflow = match op {
+            // unsigned: the result wrapped below `lhs`
+            BinOp::Add if !signed => {
+                self.push_binop(block, span, bool_ty, BinOp::Lt, result.clone(), lhs)
+            }
+            // signed: the result moved away from `lhs` in the wrong direction
+            BinOp::Add => {
+                let negative = self.push_binop(block, span, bool_ty, BinOp::Lt, rhs, zero);
+                let below = self.push_binop(block, span, bool_ty, BinOp::Lt, result.clone(), lhs);
+                self.push_binop(block, span, bool_ty, BinOp::Ne, negative, below)
+            }
+            BinOp::Sub if !signed => {
+                self.push_binop(block, span, bool_ty, BinOp::Lt, lhs, rhs)
+            }
+            BinOp::Sub => {
+                let negative = self.push_binop(block, span, bool_ty, BinOp::Lt, rhs, zero);
+                let above = self.push_binop(block, span, bool_ty, BinOp::Gt, result.clone(), lhs);
+                self.push_binop(block, span, bool_ty, BinOp::Ne, negative, above)
+            }
+            BinOp::Mul => {
+                // `result / lhs != rhs`, unless `lhs` is zero, and, when
+                // signed, unless `lhs` is -1, which overflows only for a
+                // `rhs` of the minimum value (and would overflow the division).
+                let nonzero = self.push_binop(block, span, bool_ty, BinOp::Ne, lhs.clone(), zero);
+                let (check, done) = (self.cfg.start_new_block(), self.cfg.start_new_block());
+                self.cfg.terminate(block, Terminator::If {
+                    cond: nonzero,
+                    targets: (check, done),
+                });
+                block = check;
+                if signed {
+                    let minus_one = self.hir.int_literal(ty, -1);
+                    let minus_one = self.literal_operand(span, ty, minus_one);
+                    let is_minus_one = self.push_binop(block, span, bool_ty, BinOp::Eq,
+                                                       lhs.clone(), minus_one);
+                    let (minus_one, other) = (self.cfg.start_new_block(),
+                                              self.cfg.start_new_block());
+                    self.cfg.terminate(block, Terminator::If {
+                        cond: is_minus_one,
+                        targets: (minus_one, other),
+                    });
+                    let min = self.hir.int_min_literal(ty);
+                    let min = self.literal_operand(span, ty, min);
+                    let is_min = self.push_binop(minus_one, span, bool_ty, BinOp::Eq,
+                                                 rhs.clone(), min);
+                    let minus_one = self.panic_if(minus_one, is_min, OVERFLOW_MSG, span);
+                    self.cfg.terminate(minus_one, Terminator::Goto { target: done });
+                    block = other;
+                }
+                let quotient = self.push_binop(block, span, ty, BinOp::Div, result.clone(), lhs);
+                let overflow = self.push_binop(block, span, bool_ty, BinOp::Ne, quotient, rhs);
+                let block = self.panic_if(block, overflow, OVERFLOW_MSG, span);
+                self.cfg.terminate(block, Terminator::Goto { target: done });
+                return done;
+            }
+            _ => self.hir.span_bug(span, &format!("no overflow check for {:?}", op)),
+        };
+        self.panic_if(block, overflow, OVERFLOW_MSG, span)
+    }
 }
@@ -35,13 +35,15 @@ impl<'a,'tcx> Builder<'a,'tcx> {
 
         let expr_ty = expr.ty.clone();
         let temp = this.temp(expr_ty.clone());
-        let temp_lifetime = match expr.temp_lifetime {
-            Some(t) => t,
-            None => {
-                this.hir.span_bug(expr.span, "no temp_lifetime for expr");
+        match expr.temp_lifetime {
+            Some(temp_lifetime) => {
+                this.schedule_drop(expr.span, temp_lifetime, DropKind::Deep, &temp, expr_ty);
             }
-        };
-        this.schedule_drop(expr.span, temp_lifetime, DropKind::Deep, &temp, expr_ty);
+            // Only the temporaries of static initializers have no lifetime;
+            // they are never dropped.
+            None if this.is_const => {}
+            None => this.hir.span_bug(expr.span, "no temp_lifetime for expr"),
+        }
 
         // Careful here not to cause an infinite cycle. If we always
         // called `into`, then for lvalues like `x.f`, it would
@@ -184,16 +184,18 @@ impl<'a,'tcx> Builder<'a,'tcx> {
                 // -- is that equal to `x + (x + 1)` or `2*(x+1)`?
 
                 // As above, RTL.
+                let lhs = this.hir.mirror(lhs);
+                let lhs_ty = lhs.ty;
                 let rhs = unpack!(block = this.as_operand(block, rhs));
                 let lhs = unpack!(block = this.as_lvalue(block, lhs));
 
                 // we don't have to drop prior contents or anything
                 // because AssignOp is only legal for Copy types
                 // (overloaded ops should be desugared into a call).
-                this.cfg.push_assign(block, expr_span, &lhs,
-                                     Rvalue::BinaryOp(op,
-                                                      Operand::Consume(lhs.clone()),
-                                                      rhs));
+                let result = unpack!(block = this.build_binary_op(block, op, expr_span, lhs_ty,
+                                                                  Operand::Consume(lhs.clone()),
+                                                                  rhs));
+                this.cfg.push_assign(block, expr_span, &lhs, result);
 
                 block.unit()
             }
@@ -22,6 +22,7 @@ use rustc::mir::repr::*;
 use hair::*;
 use syntax::ast::{Name, NodeId};
 use syntax::codemap::Span;
+use syntax::parse::token::InternedString;
 
 // helper functions, broken out by category:
 mod simplify;
@@ -92,7 +93,8 @@ impl<'a,'tcx> Builder<'a,'tcx> {
         // not entirely precise
         if !otherwise.is_empty() {
             let join_block = self.join_otherwise_blocks(otherwise);
-            self.panic(join_block);
+            let msg = PanicMessage::Message(InternedString::new("no match arm matched"));
+            self.panic(join_block, msg, span);
         }
 
         // all the arm blocks will rejoin here
@@ -60,6 +60,21 @@ impl<'a,'tcx> Builder<'a,'tcx> {
         temp
     }
 
+    /// Pushes `temp = lhs op rhs` onto `block`, for a new temporary `temp`
+    /// of type `ty`, and returns `temp`.
+    pub fn push_binop(&mut self,
+                      block: BasicBlock,
+                      span: Span,
+                      ty: Ty<'tcx>,
+                      op: BinOp,
+                      lhs: Operand<'tcx>,
+                      rhs: Operand<'tcx>)
+                      -> Operand<'tcx> {
+        let temp = self.temp(ty);
+        self.cfg.push_assign(block, span, &temp, Rvalue::BinaryOp(op, lhs, rhs));
+        Operand::Consume(temp)
+    }
+
     pub fn item_ref_operand(&mut self,
                             span: Span,
                             item_ref: ItemRef<'tcx>)
@@ -18,15 +18,31 @@ use rustc_front::hir;
 use syntax::ast;
 use syntax::codemap::Span;
 
+pub use self::scope::{Location, ScopeAuxiliary};
+
+// The messages of the checks the builder emits, which are the ones trans
+// uses. The MIR interpreter recognizes them in `PanicMessage::Message`.
+pub const OVERFLOW_MSG: &'static str = "arithmetic operation overflowed";
+pub const NEG_OVERFLOW_MSG: &'static str = "attempted to negate with overflow";
+pub const SHIFT_OVERFLOW_MSG: &'static str = "shift operation overflowed";
+pub const DIV_ZERO_MSG: &'static str = "attempted to divide by zero";
+pub const DIV_OVERFLOW_MSG: &'static str = "attempted to divide with overflow";
+pub const REM_ZERO_MSG: &'static str = "attempted remainder with a divisor of zero";
+pub const REM_OVERFLOW_MSG: &'static str = "attempted remainder with overflow";
+
 struct Builder<'a, 'tcx: 'a> {
     hir: Cx<'a, 'tcx>,
     cfg: CFG<'tcx>,
     scopes: Vec<scope::Scope<'tcx>>,
+    scope_auxiliary: Vec<ScopeAuxiliary>,
     loop_scopes: Vec<scope::LoopScope>,
     unit_temp: Lvalue<'tcx>,
     var_decls: Vec<VarDecl<'tcx>>,
     var_indices: FnvHashMap<ast::NodeId, u32>,
     temp_decls: Vec<TempDecl<'tcx>>,
+    /// Whether this is the MIR of a constant expression rather than of a fn;
+    /// the temporaries of static initializers have no lifetime.
+    is_const: bool,
 }
 
 struct CFG<'tcx> {
@@ -79,35 +95,15 @@ macro_rules! unpack {
 ///////////////////////////////////////////////////////////////////////////
 // construct() -- the main entry point for building MIR for a function
 
-pub fn construct<'a,'tcx>(mut hir: Cx<'a,'tcx>,
+pub fn construct<'a,'tcx>(hir: Cx<'a,'tcx>,
                           _span: Span,
                           implicit_arguments: Vec<Ty<'tcx>>,
                           explicit_arguments: Vec<(Ty<'tcx>, &'tcx hir::Pat)>,
                           argument_extent: CodeExtent,
                           return_ty: FnOutput<'tcx>,
                           ast_block: &'tcx hir::Block)
-                          -> Mir<'tcx> {
-    let cfg = CFG { basic_blocks: vec![] };
-
-    // it's handy to have a temporary of type `()` sometimes, so make
-    // one from the start and keep it available
-    let temp_decls = vec![TempDecl::<'tcx> { ty: hir.unit_ty() }];
-    let unit_temp = Lvalue::Temp(0);
-
-    let mut builder = Builder {
-        hir: hir,
-        cfg: cfg,
-        scopes: vec![],
-        loop_scopes: vec![],
-        temp_decls: temp_decls,
-        var_decls: vec![],
-        var_indices: FnvHashMap(),
-        unit_temp: unit_temp,
-    };
-
-    assert_eq!(builder.cfg.start_new_block(), START_BLOCK);
-    assert_eq!(builder.cfg.start_new_block(), END_BLOCK);
-    assert_eq!(builder.cfg.start_new_block(), DIVERGE_BLOCK);
+                          -> (Mir<'tcx>, Vec<ScopeAuxiliary>) {
+    let mut builder = Builder::new(hir, false);
 
     let mut block = START_BLOCK;
     let arg_decls = unpack!(block = builder.args_and_body(block,
@@ -119,16 +115,81 @@ pub fn construct<'a,'tcx>(mut hir: Cx<'a,'tcx>,
     builder.cfg.terminate(block, Terminator::Goto { target: END_BLOCK });
     builder.cfg.terminate(END_BLOCK, Terminator::Return);
 
-    Mir {
+    let mir = Mir {
         basic_blocks: builder.cfg.basic_blocks,
         var_decls: builder.var_decls,
         arg_decls: arg_decls,
         temp_decls: builder.temp_decls,
         return_ty: return_ty,
+    };
+    (mir, builder.scope_auxiliary)
+}
+
+///////////////////////////////////////////////////////////////////////////
+// construct_const() -- builds MIR for a constant expression, such as the
+// initializer of a const or static or the length of an array
+
+pub fn construct_const<'a,'tcx>(hir: Cx<'a,'tcx>,
+                                temp_scope: Option<CodeExtent>,
+                                ast_expr: &'tcx hir::Expr)
+                                -> Mir<'tcx> {
+    let mut builder = Builder::new(hir, true);
+
+    let expr = builder.hir.mirror(ast_expr);
+    let return_ty = FnOutput::FnConverging(expr.ty);
+
+    // The temporaries of a static initializer live as long as the program
+    // does and are never dropped; those of an array length inside of a fn
+    // live until the end of the enclosing statement.
+    let mut block = START_BLOCK;
+    block = match temp_scope {
+        Some(extent) => unpack!(builder.in_scope(extent, block, |this| {
+            this.into(&Lvalue::ReturnPointer, block, expr)
+        })),
+        None => unpack!(builder.into(&Lvalue::ReturnPointer, block, expr)),
+    };
+
+    builder.cfg.terminate(block, Terminator::Goto { target: END_BLOCK });
+    builder.cfg.terminate(END_BLOCK, Terminator::Return);
+
+    Mir {
+        basic_blocks: builder.cfg.basic_blocks,
+        var_decls: builder.var_decls,
+        arg_decls: vec![],
+        temp_decls: builder.temp_decls,
+        return_ty: return_ty,
     }
 }
 
 impl<'a,'tcx> Builder<'a,'tcx> {
+    fn new(mut hir: Cx<'a,'tcx>, is_const: bool) -> Builder<'a,'tcx> {
+        let cfg = CFG { basic_blocks: vec![] };
+
+        // it's handy to have a temporary of type `()` sometimes, so make
+        // one from the start and keep it available
+        let temp_decls = vec![TempDecl::<'tcx> { ty: hir.unit_ty() }];
+        let unit_temp = Lvalue::Temp(0);
+
+        let mut builder = Builder {
+            hir: hir,
+            cfg: cfg,
+            scopes: vec![],
+            scope_auxiliary: vec![],
+            loop_scopes: vec![],
+            temp_decls: temp_decls,
+            var_decls: vec![],
+            var_indices: FnvHashMap(),
+            unit_temp: unit_temp,
+            is_const: is_const,
+        };
+
+        assert_eq!(builder.cfg.start_new_block(), START_BLOCK);
+        assert_eq!(builder.cfg.start_new_block(), END_BLOCK);
+        assert_eq!(builder.cfg.start_new_block(), DIVERGE_BLOCK);
+
+        builder
+    }
+
     fn args_and_body(&mut self,
                      mut block: BasicBlock,
                      implicit_arguments: Vec<Ty<'tcx>>,
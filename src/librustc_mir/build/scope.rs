@@ -72,9 +72,9 @@ also add all drops.
 
 Panics are handled in a similar fashion, except that a panic always
 returns out to the `DIVERGE_BLOCK`. To trigger a panic, simply call
-`panic(p)` with the current point `p`. Or else you can call
+`panic(p, msg, span)` with the current point `p`. Or else you can call
 `diverge_cleanup`, which will produce a block that you can branch to
-which does the appropriate cleanup and then diverges. `panic(p)`
+which does the appropriate cleanup and then diverges. `panic(p, ..)`
 simply calls `diverge_cleanup()` and adds an edge from `p` to the
 result.
 
@@ -91,13 +91,35 @@ use rustc::middle::region::CodeExtent;
 use rustc::middle::ty::Ty;
 use rustc::mir::repr::*;
 use syntax::codemap::Span;
+use syntax::parse::token::InternedString;
 
 pub struct Scope<'tcx> {
     extent: CodeExtent,
+    /// The index of the `ScopeAuxiliary` recording this scope.
+    auxiliary: usize,
     drops: Vec<(DropKind, Span, Lvalue<'tcx>)>,
     cached_block: Option<BasicBlock>,
 }
 
+/// A point in the MIR: before statement `statement_index` of `block`, or
+/// before its terminator if that is the number of statements.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub block: BasicBlock,
+    pub statement_index: usize,
+}
+
+/// Where a scope was entered and exited along the normal path, recorded for
+/// debugging output. The scopes themselves do not survive building.
+#[derive(Clone, Debug)]
+pub struct ScopeAuxiliary {
+    pub extent: CodeExtent,
+    pub entry: Location,
+    /// The points after the drops on each exit from the scope: the
+    /// fallthrough and every `break`, `continue` or `return` out of it.
+    pub exits: Vec<Location>,
+}
+
 #[derive(Clone, Debug)]
 pub struct LoopScope {
     pub extent: CodeExtent, // extent of the loop
@@ -147,9 +169,17 @@ impl<'a,'tcx> Builder<'a,'tcx> {
     pub fn push_scope(&mut self, extent: CodeExtent, block: BasicBlock) {
         debug!("push_scope({:?}, {:?})", extent, block);
 
+        let entry = self.cfg.current_location(block);
+        self.scope_auxiliary.push(ScopeAuxiliary {
+            extent: extent,
+            entry: entry,
+            exits: vec![],
+        });
+
         // push scope, execute `f`, then pop scope again
         self.scopes.push(Scope {
             extent: extent.clone(),
+            auxiliary: self.scope_auxiliary.len() - 1,
             drops: vec![],
             cached_block: None,
         });
@@ -170,6 +200,8 @@ impl<'a,'tcx> Builder<'a,'tcx> {
         for (kind, span, lvalue) in scope.drops {
             self.cfg.push_drop(block, span, kind, &lvalue);
         }
+        let exit = self.cfg.current_location(block);
+        self.scope_auxiliary[scope.auxiliary].exits.push(exit);
     }
 
 
@@ -222,6 +254,8 @@ impl<'a,'tcx> Builder<'a,'tcx> {
             for &(kind, drop_span, ref lvalue) in &scope.drops {
                 self.cfg.push_drop(block, drop_span, kind, lvalue);
             }
+            let exit = self.cfg.current_location(block);
+            self.scope_auxiliary[scope.auxiliary].exits.push(exit);
         }
 
         self.cfg.terminate(block, Terminator::Goto { target: target });
@@ -234,10 +268,32 @@ impl<'a,'tcx> Builder<'a,'tcx> {
         diverge_cleanup_helper(&mut self.cfg, &mut self.scopes)
     }
 
-    /// Create diverge cleanup and branch to it from `block`.
-    pub fn panic(&mut self, block: BasicBlock) {
+    /// Create diverge cleanup and branch to it from `block`, reporting
+    /// `msg` at `span`.
+    pub fn panic(&mut self, block: BasicBlock, msg: PanicMessage<'tcx>, span: Span) {
         let cleanup = self.diverge_cleanup();
-        self.cfg.terminate(block, Terminator::Panic { target: cleanup });
+        self.cfg.terminate(block, Terminator::Panic {
+            msg: msg,
+            span: span,
+            target: cleanup,
+        });
+    }
+
+    /// Panic with `msg` at `span` from `block` if `cond` is true. Returns
+    /// the block to continue in otherwise.
+    pub fn panic_if(&mut self,
+                    block: BasicBlock,
+                    cond: Operand<'tcx>,
+                    msg: &'static str,
+                    span: Span)
+                    -> BasicBlock {
+        let (success, failure) = (self.cfg.start_new_block(), self.cfg.start_new_block());
+        self.cfg.terminate(block, Terminator::If {
+            cond: cond,
+            targets: (failure, success),
+        });
+        self.panic(failure, PanicMessage::Message(InternedString::new(msg)), span);
+        success
     }
 
     /// Indicates that `lvalue` should be dropped on exit from
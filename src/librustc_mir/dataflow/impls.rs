@@ -0,0 +1,359 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The initialization and liveness analyses of locals.
+
+use rustc::middle::ty;
+use rustc::mir::repr::*;
+use rustc::mir::visit::{LvalueContext, Visitor};
+use rustc_data_structures::bitvec::BitVector;
+use syntax::codemap::{Span, DUMMY_SP};
+
+use super::{BitDenotation, BlockSets, Direction, Locals};
+
+/// How a statement or terminator accesses a local.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Access {
+    /// The whole local is overwritten.
+    Def,
+    /// A field or element of the local is overwritten.
+    PartialDef,
+    /// The value of the local, or of part of it, is read without being
+    /// moved: it is copied, inspected, or a pointer stored in it is followed.
+    Use,
+    /// The local, or part of it if `partial`, is moved out of or dropped.
+    Move { partial: bool },
+    /// The local, or part of it, is borrowed.
+    Borrow,
+}
+
+/// Finds the accesses to locals made by statements and terminators.
+pub struct LocalAccesses<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    param_env: &'a ty::ParameterEnvironment<'a, 'tcx>,
+    mir: &'a Mir<'tcx>,
+    locals: Locals,
+}
+
+impl<'a, 'tcx> LocalAccesses<'a, 'tcx> {
+    pub fn new(tcx: &'a ty::ctxt<'tcx>,
+               param_env: &'a ty::ParameterEnvironment<'a, 'tcx>,
+               mir: &'a Mir<'tcx>)
+               -> LocalAccesses<'a, 'tcx> {
+        LocalAccesses {
+            tcx: tcx,
+            param_env: param_env,
+            mir: mir,
+            locals: Locals::new(mir),
+        }
+    }
+
+    pub fn locals(&self) -> Locals {
+        self.locals
+    }
+
+    /// The accesses made by `statement`, reads before writes.
+    pub fn of_statement(&self, statement: &Statement<'tcx>) -> Vec<(usize, Access)> {
+        let mut collector = self.collector(statement.span);
+        collector.visit_statement(START_BLOCK, statement);
+        collector.finish()
+    }
+
+    /// The accesses made by `terminator`, apart from the write to the
+    /// destination of a call.
+    pub fn of_terminator(&self, terminator: &Terminator<'tcx>) -> Vec<(usize, Access)> {
+        let mut collector = self.collector(DUMMY_SP);
+        match *terminator {
+            Terminator::Call { ref data, .. } => {
+                collector.visit_operand(&data.func);
+                for arg in &data.args {
+                    collector.visit_operand(arg);
+                }
+            }
+            Terminator::Return => {
+                collector.visit_lvalue(&Lvalue::ReturnPointer, LvalueContext::Inspect);
+            }
+            _ => collector.visit_terminator(START_BLOCK, terminator),
+        }
+        collector.finish()
+    }
+
+    /// The accesses made by a call returning into `destination`.
+    pub fn of_call_return(&self, destination: &Lvalue<'tcx>) -> Vec<(usize, Access)> {
+        let mut collector = self.collector(DUMMY_SP);
+        collector.visit_lvalue(destination, LvalueContext::Store);
+        collector.finish()
+    }
+
+    fn collector<'b>(&'b self, span: Span) -> AccessCollector<'b, 'a, 'tcx> {
+        AccessCollector {
+            cx: self,
+            span: span,
+            reads: vec![],
+            writes: vec![],
+        }
+    }
+
+    fn moves(&self, lvalue: &Lvalue<'tcx>, span: Span) -> bool {
+        let ty = self.mir.lvalue_ty(self.tcx, lvalue).to_ty(self.tcx);
+        ty.moves_by_default(self.param_env, span)
+    }
+}
+
+struct AccessCollector<'b, 'a: 'b, 'tcx: 'a> {
+    cx: &'b LocalAccesses<'a, 'tcx>,
+    span: Span,
+    reads: Vec<(usize, Access)>,
+    writes: Vec<(usize, Access)>,
+}
+
+impl<'b, 'a, 'tcx> AccessCollector<'b, 'a, 'tcx> {
+    fn finish(mut self) -> Vec<(usize, Access)> {
+        self.reads.extend(self.writes);
+        self.reads
+    }
+}
+
+impl<'b, 'a, 'tcx> Visitor<'tcx> for AccessCollector<'b, 'a, 'tcx> {
+    fn visit_lvalue(&mut self, lvalue: &Lvalue<'tcx>, context: LvalueContext) {
+        let mut base = lvalue;
+        let mut through_deref = false;
+        while let Lvalue::Projection(ref proj) = *base {
+            match proj.elem {
+                ProjectionElem::Deref => through_deref = true,
+                ProjectionElem::Index(ref index) => self.visit_operand(index),
+                _ => {}
+            }
+            base = &proj.base;
+        }
+        let local = match self.cx.locals.index(base) {
+            Some(local) => local,
+            None => return,
+        };
+        let whole = match *lvalue {
+            Lvalue::Projection(_) => false,
+            _ => true,
+        };
+
+        let access = match context {
+            LvalueContext::Store if through_deref => Access::Use,
+            LvalueContext::Store if whole => Access::Def,
+            LvalueContext::Store => Access::PartialDef,
+            LvalueContext::Consume => {
+                // Moving out through a pointer is only allowed for boxes,
+                // which are left partially moved.
+                if self.cx.moves(lvalue, self.span) {
+                    Access::Move { partial: !whole }
+                } else {
+                    Access::Use
+                }
+            }
+            LvalueContext::Drop if through_deref => Access::Use,
+            LvalueContext::Drop => Access::Move { partial: !whole },
+            LvalueContext::Borrow { .. } |
+            LvalueContext::Slice { .. } if !through_deref => Access::Borrow,
+            LvalueContext::Borrow { .. } |
+            LvalueContext::Slice { .. } |
+            LvalueContext::Inspect |
+            LvalueContext::Projection => Access::Use,
+        };
+        match access {
+            Access::Def | Access::PartialDef => self.writes.push((local, access)),
+            _ => self.reads.push((local, access)),
+        }
+    }
+}
+
+/// The locals which may be initialized, in whole or in part, at each point.
+pub struct MaybeInitializedLocals<'a, 'tcx: 'a> {
+    accesses: LocalAccesses<'a, 'tcx>,
+}
+
+impl<'a, 'tcx> MaybeInitializedLocals<'a, 'tcx> {
+    pub fn new(accesses: LocalAccesses<'a, 'tcx>) -> MaybeInitializedLocals<'a, 'tcx> {
+        MaybeInitializedLocals { accesses: accesses }
+    }
+
+    fn effect(&self, sets: &mut BlockSets, accesses: Vec<(usize, Access)>) {
+        for (local, access) in accesses {
+            match access {
+                Access::Def => sets.gen(local),
+                Access::Move { partial: false } => sets.kill(local),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<'a, 'tcx> BitDenotation<'tcx> for MaybeInitializedLocals<'a, 'tcx> {
+    fn name(&self) -> &'static str {
+        "maybe_initialized_locals"
+    }
+
+    fn direction(&self) -> Direction {
+        Direction::Forward
+    }
+
+    fn bits_per_block(&self) -> usize {
+        self.accesses.locals.len()
+    }
+
+    fn boundary_effect(&self, state: &mut BitVector) {
+        for arg in self.accesses.locals.args() {
+            state.insert(arg);
+        }
+    }
+
+    fn statement_effect(&self, sets: &mut BlockSets, statement: &Statement<'tcx>) {
+        self.effect(sets, self.accesses.of_statement(statement));
+    }
+
+    fn terminator_effect(&self, sets: &mut BlockSets, terminator: &Terminator<'tcx>) {
+        self.effect(sets, self.accesses.of_terminator(terminator));
+    }
+
+    fn call_return_effect(&self, sets: &mut BlockSets, destination: &Lvalue<'tcx>) {
+        self.effect(sets, self.accesses.of_call_return(destination));
+    }
+}
+
+/// The locals which may be uninitialized, in whole or in part, at each
+/// point. Unlike `MaybeInitializedLocals`, this tells apart locals which
+/// are partially moved out of from those which are fully initialized.
+pub struct MaybeUninitializedLocals<'a, 'tcx: 'a> {
+    accesses: LocalAccesses<'a, 'tcx>,
+}
+
+impl<'a, 'tcx> MaybeUninitializedLocals<'a, 'tcx> {
+    pub fn new(accesses: LocalAccesses<'a, 'tcx>) -> MaybeUninitializedLocals<'a, 'tcx> {
+        MaybeUninitializedLocals { accesses: accesses }
+    }
+
+    fn effect(&self, sets: &mut BlockSets, accesses: Vec<(usize, Access)>) {
+        for (local, access) in accesses {
+            match access {
+                Access::Def => sets.kill(local),
+                Access::Move { .. } => sets.gen(local),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<'a, 'tcx> BitDenotation<'tcx> for MaybeUninitializedLocals<'a, 'tcx> {
+    fn name(&self) -> &'static str {
+        "maybe_uninitialized_locals"
+    }
+
+    fn direction(&self) -> Direction {
+        Direction::Forward
+    }
+
+    fn bits_per_block(&self) -> usize {
+        self.accesses.locals.len()
+    }
+
+    fn boundary_effect(&self, state: &mut BitVector) {
+        let args = self.accesses.locals.args();
+        for local in args.end..self.accesses.locals.len() {
+            state.insert(local);
+        }
+    }
+
+    fn statement_effect(&self, sets: &mut BlockSets, statement: &Statement<'tcx>) {
+        self.effect(sets, self.accesses.of_statement(statement));
+    }
+
+    fn terminator_effect(&self, sets: &mut BlockSets, terminator: &Terminator<'tcx>) {
+        self.effect(sets, self.accesses.of_terminator(terminator));
+    }
+
+    fn call_return_effect(&self, sets: &mut BlockSets, destination: &Lvalue<'tcx>) {
+        self.effect(sets, self.accesses.of_call_return(destination));
+    }
+}
+
+/// The locals whose current value may be read later on.
+///
+/// A local which is borrowed anywhere in the fn may be read through the
+/// reference at any time, so it is considered live at every point from
+/// which the end of the fn can be reached.
+pub struct LiveLocals<'a, 'tcx: 'a> {
+    accesses: LocalAccesses<'a, 'tcx>,
+    borrowed: BitVector,
+}
+
+impl<'a, 'tcx> LiveLocals<'a, 'tcx> {
+    pub fn new(accesses: LocalAccesses<'a, 'tcx>) -> LiveLocals<'a, 'tcx> {
+        let mut borrowed = BitVector::new(accesses.locals.len());
+        for bb in accesses.mir.all_basic_blocks() {
+            for statement in &accesses.mir.basic_block_data(bb).statements {
+                for (local, access) in accesses.of_statement(statement) {
+                    if access == Access::Borrow {
+                        borrowed.insert(local);
+                    }
+                }
+            }
+        }
+        LiveLocals {
+            accesses: accesses,
+            borrowed: borrowed,
+        }
+    }
+
+    pub fn accesses(&self) -> &LocalAccesses<'a, 'tcx> {
+        &self.accesses
+    }
+
+    /// Applies `accesses` backwards: writes first, then reads.
+    fn effect(&self, sets: &mut BlockSets, accesses: Vec<(usize, Access)>) {
+        for &(local, access) in accesses.iter().rev() {
+            match access {
+                Access::Def => {
+                    if !self.borrowed.contains(local) {
+                        sets.kill(local);
+                    }
+                }
+                Access::PartialDef => {}
+                Access::Use | Access::Move { .. } | Access::Borrow => sets.gen(local),
+            }
+        }
+    }
+}
+
+impl<'a, 'tcx> BitDenotation<'tcx> for LiveLocals<'a, 'tcx> {
+    fn name(&self) -> &'static str {
+        "live_locals"
+    }
+
+    fn direction(&self) -> Direction {
+        Direction::Backward
+    }
+
+    fn bits_per_block(&self) -> usize {
+        self.accesses.locals.len()
+    }
+
+    fn boundary_effect(&self, state: &mut BitVector) {
+        state.insert_all(&self.borrowed);
+    }
+
+    fn statement_effect(&self, sets: &mut BlockSets, statement: &Statement<'tcx>) {
+        self.effect(sets, self.accesses.of_statement(statement));
+    }
+
+    fn terminator_effect(&self, sets: &mut BlockSets, terminator: &Terminator<'tcx>) {
+        self.effect(sets, self.accesses.of_terminator(terminator));
+    }
+
+    fn call_return_effect(&self, sets: &mut BlockSets, destination: &Lvalue<'tcx>) {
+        self.effect(sets, self.accesses.of_call_return(destination));
+    }
+}
@@ -0,0 +1,443 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Dataflow analyses over the basic blocks of the MIR.
+//!
+//! An analysis is described by a `BitDenotation`: the number of bits it
+//! tracks, and the bits each statement and terminator sets ("gen") or
+//! clears ("kill"). `do_dataflow` summarises every basic block as a single
+//! gen set and kill set, then propagates the states along the edges of the
+//! control flow graph, forwards or backwards, until they stop changing.
+//! States are joined by union, so the analyses compute what *may* hold.
+//!
+//! The effect of a call on its destination only happens if the call
+//! returns, so it is applied on the edge to the return target rather than
+//! as part of the block which ends with the call.
+//!
+//! The analyses in `impls` track whole locals, numbered as in `Locals`.
+
+use rustc::mir::repr::*;
+use rustc_data_structures::bitvec::BitVector;
+use std::ops::Range;
+
+pub use self::impls::{Access, LocalAccesses};
+pub use self::impls::{MaybeInitializedLocals, MaybeUninitializedLocals, LiveLocals};
+
+mod impls;
+
+/// The locals of a fn: its arguments, variables, temporaries and return
+/// pointer, which the analyses number in that order.
+#[derive(Copy, Clone, Debug)]
+pub struct Locals {
+    args: usize,
+    vars: usize,
+    temps: usize,
+}
+
+impl Locals {
+    pub fn new(mir: &Mir) -> Locals {
+        Locals {
+            args: mir.arg_decls.len(),
+            vars: mir.var_decls.len(),
+            temps: mir.temp_decls.len(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.args + self.vars + self.temps + 1
+    }
+
+    pub fn args(&self) -> Range<usize> {
+        0..self.args
+    }
+
+    /// The number of `lvalue`, if it is a local (and not a projection out of
+    /// one, or a static).
+    pub fn index(&self, lvalue: &Lvalue) -> Option<usize> {
+        match *lvalue {
+            Lvalue::Arg(index) => Some(index as usize),
+            Lvalue::Var(index) => Some(self.args + index as usize),
+            Lvalue::Temp(index) => Some(self.args + self.vars + index as usize),
+            Lvalue::ReturnPointer => Some(self.args + self.vars + self.temps),
+            Lvalue::Static(_) |
+            Lvalue::Projection(_) => None,
+        }
+    }
+
+    /// The local numbered `index`.
+    pub fn lvalue<'tcx>(&self, index: usize) -> Lvalue<'tcx> {
+        if index < self.args {
+            Lvalue::Arg(index as u32)
+        } else if index < self.args + self.vars {
+            Lvalue::Var((index - self.args) as u32)
+        } else if index < self.args + self.vars + self.temps {
+            Lvalue::Temp((index - self.args - self.vars) as u32)
+        } else {
+            assert!(index == self.args + self.vars + self.temps);
+            Lvalue::ReturnPointer
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Backward,
+}
+
+/// The combined effect of a sequence of statements, built up by applying
+/// the effect of each of them in the order in which the analysis flows.
+pub struct BlockSets<'a> {
+    gen: &'a mut BitVector,
+    kill: &'a mut BitVector,
+}
+
+impl<'a> BlockSets<'a> {
+    pub fn gen(&mut self, bit: usize) {
+        self.gen.insert(bit);
+        self.kill.remove(bit);
+    }
+
+    pub fn kill(&mut self, bit: usize) {
+        self.kill.insert(bit);
+        self.gen.remove(bit);
+    }
+
+    fn apply_to(&self, state: &mut BitVector) {
+        state.subtract(self.kill);
+        state.insert_all(self.gen);
+    }
+}
+
+pub trait BitDenotation<'tcx> {
+    /// A name for the analysis, for debugging output.
+    fn name(&self) -> &'static str;
+
+    fn direction(&self) -> Direction;
+
+    fn bits_per_block(&self) -> usize;
+
+    /// Sets the bits which hold where the analysis starts: on entry to the
+    /// `START_BLOCK` for forward analyses, and at the end of every block
+    /// without successors for backward ones.
+    fn boundary_effect(&self, state: &mut BitVector);
+
+    fn statement_effect(&self, sets: &mut BlockSets, statement: &Statement<'tcx>);
+
+    /// The effect of `terminator`, except on the destination of a call.
+    fn terminator_effect(&self, sets: &mut BlockSets, terminator: &Terminator<'tcx>);
+
+    /// The effect of a call returning into `destination`.
+    fn call_return_effect(&self, sets: &mut BlockSets, destination: &Lvalue<'tcx>);
+}
+
+pub struct DataflowResults<D> {
+    denotation: D,
+    /// The state on entry to each block in the direction of the analysis:
+    /// at the start of the block for forward analyses, at its end for
+    /// backward ones.
+    on_entry: Vec<BitVector>,
+    gen: Vec<BitVector>,
+    kill: Vec<BitVector>,
+}
+
+/// Runs the analysis `denotation` over `mir` to a fixed point.
+pub fn do_dataflow<'tcx, D>(mir: &Mir<'tcx>, denotation: D) -> DataflowResults<D>
+    where D: BitDenotation<'tcx>
+{
+    let bits = denotation.bits_per_block();
+    let empty = || mir.basic_blocks.iter().map(|_| BitVector::new(bits)).collect::<Vec<_>>();
+    let mut results = DataflowResults {
+        denotation: denotation,
+        on_entry: empty(),
+        gen: empty(),
+        kill: empty(),
+    };
+
+    for bb in mir.all_basic_blocks() {
+        let mut sets = BlockSets {
+            gen: &mut results.gen[bb.index()],
+            kill: &mut results.kill[bb.index()],
+        };
+        block_effect(&results.denotation, &mut sets, mir.basic_block_data(bb));
+    }
+
+    let direction = results.denotation.direction();
+    if direction == Direction::Forward {
+        results.denotation.boundary_effect(&mut results.on_entry[START_BLOCK.index()]);
+    }
+
+    let mut blocks = mir.all_basic_blocks();
+    if direction == Direction::Backward {
+        blocks.reverse();
+    }
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &bb in &blocks {
+            changed |= match direction {
+                Direction::Forward => results.propagate_forward(mir, bb),
+                Direction::Backward => results.propagate_backward(mir, bb),
+            };
+        }
+    }
+    debug!("do_dataflow({}): converged", results.denotation.name());
+    results
+}
+
+/// Applies the effects of the statements and terminator of `data`, in the
+/// order in which `denotation` flows.
+fn block_effect<'tcx, D>(denotation: &D, sets: &mut BlockSets, data: &BasicBlockData<'tcx>)
+    where D: BitDenotation<'tcx>
+{
+    match denotation.direction() {
+        Direction::Forward => {
+            for statement in &data.statements {
+                denotation.statement_effect(sets, statement);
+            }
+            denotation.terminator_effect(sets, &data.terminator);
+        }
+        Direction::Backward => {
+            denotation.terminator_effect(sets, &data.terminator);
+            for statement in data.statements.iter().rev() {
+                denotation.statement_effect(sets, statement);
+            }
+        }
+    }
+}
+
+impl<D> DataflowResults<D> {
+    pub fn denotation(&self) -> &D {
+        &self.denotation
+    }
+
+    /// The state at the start of `bb` for forward analyses, or at its end
+    /// for backward ones.
+    pub fn on_entry(&self, bb: BasicBlock) -> &BitVector {
+        &self.on_entry[bb.index()]
+    }
+
+    /// The state at the end of `bb` for forward analyses, or at its start
+    /// for backward ones.
+    pub fn on_exit(&self, bb: BasicBlock) -> BitVector {
+        let mut state = self.on_entry[bb.index()].clone();
+        state.subtract(&self.kill[bb.index()]);
+        state.insert_all(&self.gen[bb.index()]);
+        state
+    }
+}
+
+impl<'tcx, D: BitDenotation<'tcx>> DataflowResults<D> {
+    /// The state just before statement `index` of `bb` is executed, or
+    /// before its terminator if `index` is the number of statements.
+    pub fn state_before(&self, mir: &Mir<'tcx>, bb: BasicBlock, index: usize) -> BitVector {
+        let data = mir.basic_block_data(bb);
+        let bits = self.denotation.bits_per_block();
+        let (mut gen, mut kill) = (BitVector::new(bits), BitVector::new(bits));
+        let mut state = self.on_entry[bb.index()].clone();
+        {
+            let mut sets = BlockSets { gen: &mut gen, kill: &mut kill };
+            match self.denotation.direction() {
+                Direction::Forward => {
+                    for statement in &data.statements[..index] {
+                        self.denotation.statement_effect(&mut sets, statement);
+                    }
+                }
+                Direction::Backward => {
+                    self.denotation.terminator_effect(&mut sets, &data.terminator);
+                    for statement in data.statements[index..].iter().rev() {
+                        self.denotation.statement_effect(&mut sets, statement);
+                    }
+                }
+            }
+            sets.apply_to(&mut state);
+        }
+        state
+    }
+
+    /// Propagates the state at the end of `bb` to its successors, returning
+    /// whether any of them changed.
+    fn propagate_forward(&mut self, mir: &Mir<'tcx>, bb: BasicBlock) -> bool {
+        let exit = self.on_exit(bb);
+        let terminator = &mir.basic_block_data(bb).terminator;
+        let mut changed = false;
+        for (i, &succ) in terminator.successors().iter().enumerate() {
+            let mut state = exit.clone();
+            self.call_return_edge(terminator, i, &mut state);
+            changed |= self.on_entry[succ.index()].insert_all(&state);
+        }
+        changed
+    }
+
+    /// Recomputes the state at the end of `bb` from the states at the start
+    /// of its successors, returning whether it changed.
+    fn propagate_backward(&mut self, mir: &Mir<'tcx>, bb: BasicBlock) -> bool {
+        let terminator = &mir.basic_block_data(bb).terminator;
+        let mut end = BitVector::new(self.denotation.bits_per_block());
+        if terminator.successors().is_empty() {
+            self.denotation.boundary_effect(&mut end);
+        }
+        for (i, &succ) in terminator.successors().iter().enumerate() {
+            let mut state = self.on_exit(succ);
+            self.call_return_edge(terminator, i, &mut state);
+            end.insert_all(&state);
+        }
+        self.on_entry[bb.index()].insert_all(&end)
+    }
+
+    /// Applies the effect of the call returning, if `terminator` is a call
+    /// and its successor `index` is the return target.
+    fn call_return_edge(&self, terminator: &Terminator<'tcx>, index: usize, state: &mut BitVector) {
+        if let Terminator::Call { ref data, .. } = *terminator {
+            if index == 0 {
+                let bits = self.denotation.bits_per_block();
+                let (mut gen, mut kill) = (BitVector::new(bits), BitVector::new(bits));
+                let mut sets = BlockSets { gen: &mut gen, kill: &mut kill };
+                self.denotation.call_return_effect(&mut sets, &data.destination);
+                sets.apply_to(state);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rustc::middle::ty::FnOutput;
+    use rustc::mir::repr::*;
+    use rustc_data_structures::bitvec::BitVector;
+    use syntax::codemap::DUMMY_SP;
+    use super::{do_dataflow, BitDenotation, BlockSets, Direction};
+
+    /// The variables which may have been assigned and not dropped since.
+    struct AssignedVars(usize);
+
+    impl<'tcx> BitDenotation<'tcx> for AssignedVars {
+        fn name(&self) -> &'static str {
+            "assigned_vars"
+        }
+
+        fn direction(&self) -> Direction {
+            Direction::Forward
+        }
+
+        fn bits_per_block(&self) -> usize {
+            self.0
+        }
+
+        fn boundary_effect(&self, _: &mut BitVector) {}
+
+        fn statement_effect(&self, sets: &mut BlockSets, statement: &Statement<'tcx>) {
+            match statement.kind {
+                StatementKind::Assign(Lvalue::Var(index), _) => sets.gen(index as usize),
+                StatementKind::Drop(_, Lvalue::Var(index)) => sets.kill(index as usize),
+                _ => {}
+            }
+        }
+
+        fn terminator_effect(&self, _: &mut BlockSets, _: &Terminator<'tcx>) {}
+
+        fn call_return_effect(&self, _: &mut BlockSets, _: &Lvalue<'tcx>) {}
+    }
+
+    fn assign_var<'tcx>(var: u32) -> Statement<'tcx> {
+        Statement {
+            span: DUMMY_SP,
+            kind: StatementKind::Assign(Lvalue::Var(var),
+                                        Rvalue::Use(Operand::Consume(Lvalue::Arg(0)))),
+        }
+    }
+
+    fn drop_var<'tcx>(var: u32) -> Statement<'tcx> {
+        Statement {
+            span: DUMMY_SP,
+            kind: StatementKind::Drop(DropKind::Deep, Lvalue::Var(var)),
+        }
+    }
+
+    fn block<'tcx>(statements: Vec<Statement<'tcx>>, terminator: Terminator<'tcx>)
+                   -> BasicBlockData<'tcx> {
+        BasicBlockData { statements: statements, terminator: terminator }
+    }
+
+    /// bb0: x0 = a0; goto bb3
+    /// bb3: if a0 { bb4 } else { bb5 }
+    /// bb4: drop x0; x1 = a0; goto bb3
+    /// bb5: goto END_BLOCK
+    fn looping_mir<'tcx>() -> Mir<'tcx> {
+        let bb = BasicBlock::new;
+        Mir {
+            basic_blocks: vec![
+                block(vec![assign_var(0)], Terminator::Goto { target: bb(3) }),
+                block(vec![], Terminator::Return),
+                block(vec![], Terminator::Diverge),
+                block(vec![], Terminator::If {
+                    cond: Operand::Consume(Lvalue::Arg(0)),
+                    targets: (bb(4), bb(5)),
+                }),
+                block(vec![drop_var(0), assign_var(1)], Terminator::Goto { target: bb(3) }),
+                block(vec![], Terminator::Goto { target: END_BLOCK }),
+            ],
+            return_ty: FnOutput::FnDiverging,
+            var_decls: vec![],
+            arg_decls: vec![],
+            temp_decls: vec![],
+        }
+    }
+
+    fn bits(vector: &BitVector) -> Vec<usize> {
+        vector.iter().collect()
+    }
+
+    #[test]
+    fn test_block_sets_last_effect_wins() {
+        let (mut gen, mut kill) = (BitVector::new(4), BitVector::new(4));
+        {
+            let mut sets = BlockSets { gen: &mut gen, kill: &mut kill };
+            sets.gen(0);
+            sets.kill(0);
+            sets.kill(1);
+            sets.gen(1);
+            sets.kill(2);
+        }
+        assert_eq!(bits(&gen), vec![1]);
+        assert_eq!(bits(&kill), vec![0, 2]);
+
+        let mut state = BitVector::new(4);
+        state.insert(2);
+        state.insert(3);
+        BlockSets { gen: &mut gen, kill: &mut kill }.apply_to(&mut state);
+        assert_eq!(bits(&state), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_block_transfer_function() {
+        let mir = looping_mir();
+        let results = do_dataflow(&mir, AssignedVars(2));
+        let bb4 = BasicBlock::new(4);
+        assert_eq!(bits(results.on_entry(bb4)), vec![0, 1]);
+        assert_eq!(bits(&results.on_exit(bb4)), vec![1]);
+        assert_eq!(bits(&results.state_before(&mir, bb4, 0)), vec![0, 1]);
+        assert_eq!(bits(&results.state_before(&mir, bb4, 1)), vec![1]);
+        assert_eq!(bits(&results.state_before(&mir, bb4, 2)), vec![1]);
+    }
+
+    #[test]
+    fn test_fixpoint_through_loop() {
+        let mir = looping_mir();
+        let results = do_dataflow(&mir, AssignedVars(2));
+        // x1 only reaches the loop header along the back edge, so it is
+        // missing until the second pass over the blocks.
+        assert_eq!(bits(results.on_entry(START_BLOCK)), Vec::<usize>::new());
+        assert_eq!(bits(results.on_entry(BasicBlock::new(3))), vec![0, 1]);
+        assert_eq!(bits(results.on_entry(BasicBlock::new(5))), vec![0, 1]);
+        assert_eq!(bits(results.on_entry(END_BLOCK)), vec![0, 1]);
+        assert_eq!(bits(results.on_entry(DIVERGE_BLOCK)), Vec::<usize>::new());
+    }
+}
@@ -8,9 +8,239 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+//! Renders the MIR of a fn as a graphviz graph of its basic blocks, for
+//! `#[rustc_mir(graphviz="file.gv")]`. The graph shows the MIR as built,
+//! before any pass has run, whatever the annotations.
+//!
+//! Each block can be annotated, as requested by `#[rustc_mir(annotate="...")]`
+//! (a comma separated list), with:
+//!
+//! - `temps`: the types of the temps the block mentions;
+//! - `scopes`: the scopes of `build::scope` active at its start, and where
+//!   they are entered and exited;
+//! - `init`, `uninit`, `live`: the locals which may be initialized, may be
+//!   uninitialized, or are live at the start and at the end of the block,
+//!   as computed by the analyses of `dataflow`.
+
+use build::{Location, ScopeAuxiliary};
+use dataflow::{self, BitDenotation, DataflowResults, Direction, LocalAccesses, Locals};
+use dataflow::{LiveLocals, MaybeInitializedLocals, MaybeUninitializedLocals};
 use dot;
+use rustc::middle::ty;
 use rustc::mir::repr::*;
+use rustc::mir::visit::{LvalueContext, Visitor};
+use rustc_data_structures::bitvec::BitVector;
 use std::borrow::IntoCow;
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use syntax::ast::NodeId;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Analysis {
+    Initialized,
+    Uninitialized,
+    Live,
+}
+
+impl Analysis {
+    fn name(self) -> &'static str {
+        match self {
+            Analysis::Initialized => "maybe initialized",
+            Analysis::Uninitialized => "maybe uninitialized",
+            Analysis::Live => "live",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Annotations {
+    pub temp_types: bool,
+    pub scopes: bool,
+    pub dataflow: Vec<Analysis>,
+}
+
+impl Annotations {
+    /// Parses the value of `#[rustc_mir(annotate="...")]`, returning the
+    /// unknown annotation on failure.
+    pub fn parse(value: &str) -> Result<Annotations, String> {
+        let mut annotations = Annotations::default();
+        for name in value.split(',').map(|name| name.trim()).filter(|name| !name.is_empty()) {
+            match name {
+                "temps" => annotations.temp_types = true,
+                "scopes" => annotations.scopes = true,
+                "init" => annotations.dataflow.push(Analysis::Initialized),
+                "uninit" => annotations.dataflow.push(Analysis::Uninitialized),
+                "live" => annotations.dataflow.push(Analysis::Live),
+                _ => return Err(name.to_string()),
+            }
+        }
+        Ok(annotations)
+    }
+}
+
+/// Writes the graph of `mir`, the MIR of the fn `id`, annotated as asked
+/// for. `scopes` must be given for the `scopes` annotation.
+pub fn write_mir_graphviz<'tcx, W>(tcx: &ty::ctxt<'tcx>,
+                                   id: NodeId,
+                                   mir: &Mir<'tcx>,
+                                   annotations: &Annotations,
+                                   scopes: Option<&[ScopeAuxiliary]>,
+                                   w: &mut W)
+                                   -> io::Result<()>
+    where W: Write
+{
+    let locals = Locals::new(mir);
+    let param_env = ty::ParameterEnvironment::for_item(tcx, id);
+    let dataflow = annotations.dataflow.iter().map(|&analysis| {
+        let accesses = LocalAccesses::new(tcx, &param_env, mir);
+        let (start, end) = match analysis {
+            Analysis::Initialized => {
+                let initialized = MaybeInitializedLocals::new(accesses);
+                block_states(mir, &dataflow::do_dataflow(mir, initialized))
+            }
+            Analysis::Uninitialized => {
+                let uninitialized = MaybeUninitializedLocals::new(accesses);
+                block_states(mir, &dataflow::do_dataflow(mir, uninitialized))
+            }
+            Analysis::Live => {
+                block_states(mir, &dataflow::do_dataflow(mir, LiveLocals::new(accesses)))
+            }
+        };
+        AnalysisStates { analysis: analysis, start: start, end: end }
+    }).collect();
+
+    let graph = MirGraph {
+        mir: mir,
+        locals: locals,
+        temp_types: annotations.temp_types,
+        scopes: match scopes {
+            Some(scopes) if annotations.scopes => Some(ScopeStates::new(mir, scopes)),
+            _ => None,
+        },
+        dataflow: dataflow,
+    };
+    dot::render(&graph, w)
+}
+
+/// The states of `results` at the start and at the end of each block.
+fn block_states<'tcx, D>(mir: &Mir<'tcx>, results: &DataflowResults<D>)
+                         -> (Vec<BitVector>, Vec<BitVector>)
+    where D: BitDenotation<'tcx>
+{
+    let blocks = mir.all_basic_blocks();
+    let on_entry = blocks.iter().map(|&bb| results.on_entry(bb).clone()).collect();
+    let on_exit = blocks.iter().map(|&bb| results.on_exit(bb)).collect();
+    match results.denotation().direction() {
+        Direction::Forward => (on_entry, on_exit),
+        Direction::Backward => (on_exit, on_entry),
+    }
+}
+
+struct AnalysisStates {
+    analysis: Analysis,
+    start: Vec<BitVector>,
+    end: Vec<BitVector>,
+}
+
+/// The scopes which may be active at the start of each block, and where
+/// scopes are entered and exited.
+struct ScopeStates<'a> {
+    scopes: &'a [ScopeAuxiliary],
+    start: Vec<BitVector>,
+}
+
+impl<'a> ScopeStates<'a> {
+    fn new(mir: &Mir, scopes: &'a [ScopeAuxiliary]) -> ScopeStates<'a> {
+        let mut states = ScopeStates {
+            scopes: scopes,
+            start: mir.all_basic_blocks().iter().map(|_| BitVector::new(scopes.len())).collect(),
+        };
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for bb in mir.all_basic_blocks() {
+                let data = mir.basic_block_data(bb);
+                let mut state = states.start[bb.index()].clone();
+                for index in 0..data.statements.len() + 1 {
+                    states.apply_events(bb, index, &mut state);
+                }
+                for &succ in data.terminator.successors() {
+                    changed |= states.start[succ.index()].insert_all(&state);
+                }
+            }
+        }
+        states
+    }
+
+    /// The scopes exited and entered at `location`, in that order. A scope
+    /// exited where it is entered is empty, and ignored.
+    fn events(&self, location: Location) -> (Vec<usize>, Vec<usize>) {
+        let mut exits = vec![];
+        let mut entries = vec![];
+        for (index, scope) in self.scopes.iter().enumerate() {
+            if scope.entry == location {
+                if !scope.exits.contains(&location) {
+                    entries.push(index);
+                }
+            } else if scope.exits.contains(&location) {
+                exits.push(index);
+            }
+        }
+        (exits, entries)
+    }
+
+    fn apply_events(&self, block: BasicBlock, statement_index: usize, state: &mut BitVector) {
+        let (exits, entries) = self.events(Location {
+            block: block,
+            statement_index: statement_index,
+        });
+        for scope in exits {
+            state.remove(scope);
+        }
+        for scope in entries {
+            state.insert(scope);
+        }
+    }
+
+    fn describe(&self, state: &BitVector) -> String {
+        let extents: Vec<_> = (0..self.scopes.len()).filter(|&scope| state.contains(scope))
+                                                    .map(|scope| self.scopes[scope].extent)
+                                                    .collect();
+        format!("{:?}", extents)
+    }
+}
+
+struct MirGraph<'a, 'tcx: 'a> {
+    mir: &'a Mir<'tcx>,
+    locals: Locals,
+    temp_types: bool,
+    scopes: Option<ScopeStates<'a>>,
+    dataflow: Vec<AnalysisStates>,
+}
+
+impl<'a, 'tcx> MirGraph<'a, 'tcx> {
+    fn describe_locals(&self, state: &BitVector) -> String {
+        let locals: Vec<_> = (0..self.locals.len()).filter(|&local| state.contains(local))
+                                                   .map(|local| self.locals.lvalue(local))
+                                                   .collect();
+        format!("{:?}", locals)
+    }
+
+    fn push_scope_events(&self, buffer: &mut String, bb: BasicBlock, statement_index: usize) {
+        if let Some(ref scopes) = self.scopes {
+            let (exits, entries) = scopes.events(Location {
+                block: bb,
+                statement_index: statement_index,
+            });
+            for scope in exits {
+                push_row(buffer, format!("exit {:?}", scopes.scopes[scope].extent));
+            }
+            for scope in entries {
+                push_row(buffer, format!("enter {:?}", scopes.scopes[scope].extent));
+            }
+        }
+    }
+}
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub struct EdgeIndex {
@@ -19,7 +249,7 @@ pub struct EdgeIndex {
     index: usize,
 }
 
-impl<'a,'tcx> dot::Labeller<'a, BasicBlock, EdgeIndex> for Mir<'tcx> {
+impl<'a, 'b, 'tcx> dot::Labeller<'a, BasicBlock, EdgeIndex> for MirGraph<'b, 'tcx> {
     fn graph_id(&'a self) -> dot::Id<'a> {
         dot::Id::new("Mir").unwrap()
     }
@@ -40,16 +270,40 @@ impl<'a,'tcx> dot::Labeller<'a, BasicBlock, EdgeIndex> for Mir<'tcx> {
         buffer.push_str(&format!("{:?}", n));
         buffer.push_str("</TD></TR>");
 
-        let data = self.basic_block_data(n);
-        for statement in &data.statements {
-            buffer.push_str("<TR><TD>");
-            buffer.push_str(&escape(format!("{:?}", statement)));
-            buffer.push_str("</TD></TR>");
+        let data = self.mir.basic_block_data(n);
+
+        if self.temp_types {
+            let mut temps = TempCollector { temps: BTreeSet::new() };
+            temps.visit_basic_block_data(n, data);
+            for temp in temps.temps {
+                push_row(&mut buffer, format!("{:?}: {}",
+                                              Lvalue::Temp(temp),
+                                              self.mir.temp_decls[temp as usize].ty));
+            }
+        }
+        if let Some(ref scopes) = self.scopes {
+            push_row(&mut buffer,
+                     format!("scopes: {}", scopes.describe(&scopes.start[n.index()])));
+        }
+        for states in &self.dataflow {
+            push_row(&mut buffer, format!("{} on entry: {}",
+                                          states.analysis.name(),
+                                          self.describe_locals(&states.start[n.index()])));
         }
 
-        buffer.push_str("<TR><TD>");
-        buffer.push_str(&escape(format!("{:?}", &data.terminator)));
-        buffer.push_str("</TD></TR>");
+        for (index, statement) in data.statements.iter().enumerate() {
+            self.push_scope_events(&mut buffer, n, index);
+            push_row(&mut buffer, format!("{:?}", statement));
+        }
+        self.push_scope_events(&mut buffer, n, data.statements.len());
+
+        push_row(&mut buffer, format!("{:?}", &data.terminator));
+
+        for states in &self.dataflow {
+            push_row(&mut buffer, format!("{} on exit: {}",
+                                          states.analysis.name(),
+                                          self.describe_locals(&states.end[n.index()])));
+        }
 
         buffer.push_str("</TABLE>");
 
@@ -61,30 +315,31 @@ impl<'a,'tcx> dot::Labeller<'a, BasicBlock, EdgeIndex> for Mir<'tcx> {
     }
 }
 
-impl<'a,'tcx> dot::GraphWalk<'a, BasicBlock, EdgeIndex> for Mir<'tcx> {
+impl<'a, 'b, 'tcx> dot::GraphWalk<'a, BasicBlock, EdgeIndex> for MirGraph<'b, 'tcx> {
     fn nodes(&'a self) -> dot::Nodes<'a, BasicBlock> {
-        self.all_basic_blocks().into_cow()
+        self.mir.all_basic_blocks().into_cow()
     }
 
     fn edges(&'a self) -> dot::Edges<'a, EdgeIndex> {
-        self.all_basic_blocks()
-            .into_iter()
-            .flat_map(|source| {
-                self.basic_block_data(source)
-                    .terminator
-                    .successors()
-                    .iter()
-                    .enumerate()
-                    .map(move |(index, &target)| {
-                        EdgeIndex {
-                            source: source,
-                            target: target,
-                            index: index,
-                        }
-                    })
-            })
-            .collect::<Vec<_>>()
-            .into_cow()
+        let mir = self.mir;
+        mir.all_basic_blocks()
+           .into_iter()
+           .flat_map(|source| {
+               mir.basic_block_data(source)
+                  .terminator
+                  .successors()
+                  .iter()
+                  .enumerate()
+                  .map(move |(index, &target)| {
+                      EdgeIndex {
+                          source: source,
+                          target: target,
+                          index: index,
+                      }
+                  })
+           })
+           .collect::<Vec<_>>()
+           .into_cow()
     }
 
     fn source(&'a self, edge: &EdgeIndex) -> BasicBlock {
@@ -96,6 +351,26 @@ impl<'a,'tcx> dot::GraphWalk<'a, BasicBlock, EdgeIndex> for Mir<'tcx> {
     }
 }
 
+fn push_row(buffer: &mut String, text: String) {
+    buffer.push_str("<TR><TD>");
+    buffer.push_str(&escape(text));
+    buffer.push_str("</TD></TR>");
+}
+
+/// Collects the temps mentioned by a block.
+struct TempCollector {
+    temps: BTreeSet<u32>,
+}
+
+impl<'tcx> Visitor<'tcx> for TempCollector {
+    fn visit_lvalue(&mut self, lvalue: &Lvalue<'tcx>, context: LvalueContext) {
+        if let Lvalue::Temp(index) = *lvalue {
+            self.temps.insert(index);
+        }
+        self.super_lvalue(lvalue, context);
+    }
+}
+
 fn escape(text: String) -> String {
     let text = dot::escape_html(&text);
     let text = all_to_subscript("Temp", text);
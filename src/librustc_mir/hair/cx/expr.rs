@@ -92,7 +92,6 @@ impl<'tcx> Mirror<'tcx> for &'tcx hir::Expr {
                     overloaded_operator(cx, self, ty::MethodCall::expr(self.id),
                                         pass_args, lhs.to_ref(), vec![rhs])
                 } else {
-                    // FIXME overflow
                     match op.node {
                         hir::BinOp_::BiAnd => {
                             ExprKind::LogicalOp {
@@ -146,7 +145,6 @@ impl<'tcx> Mirror<'tcx> for &'tcx hir::Expr {
                     overloaded_operator(cx, self, ty::MethodCall::expr(self.id),
                                         PassArgs::ByValue, arg.to_ref(), vec![])
                 } else {
-                    // FIXME overflow
                     let op = match op {
                         hir::UnOp::UnNot => UnOp::Not,
                         hir::UnOp::UnNeg => UnOp::Neg,
@@ -23,6 +23,8 @@ use rustc::middle::def_id::DefId;
 use rustc::middle::infer::InferCtxt;
 use rustc::middle::subst::{Subst, Substs};
 use rustc::middle::ty::{self, Ty};
+use std::i64;
+use syntax::ast;
 use syntax::codemap::Span;
 use syntax::parse::token;
 use rustc_front::hir;
@@ -31,13 +33,15 @@ use rustc_front::hir;
 pub struct Cx<'a, 'tcx: 'a> {
     tcx: &'a ty::ctxt<'tcx>,
     infcx: &'a InferCtxt<'a, 'tcx>,
+    check_overflow: bool,
 }
 
 impl<'a,'tcx> Cx<'a,'tcx> {
-    pub fn new(infcx: &'a InferCtxt<'a, 'tcx>) -> Cx<'a, 'tcx> {
+    pub fn new(infcx: &'a InferCtxt<'a, 'tcx>, check_overflow: bool) -> Cx<'a, 'tcx> {
         Cx {
             tcx: infcx.tcx,
             infcx: infcx,
+            check_overflow: check_overflow,
         }
     }
 }
@@ -72,6 +76,41 @@ impl<'a,'tcx:'a> Cx<'a, 'tcx> {
         Literal::Value { value: ConstVal::Bool(false) }
     }
 
+    /// The literal `value` of the integer type `ty`.
+    pub fn int_literal(&mut self, ty: Ty<'tcx>, value: i64) -> Literal<'tcx> {
+        let value = if ty.is_signed() {
+            ConstVal::Int(value)
+        } else {
+            ConstVal::Uint(value as u64)
+        };
+        Literal::Value { value: value }
+    }
+
+    /// The minimum value of the signed integer type `ty`.
+    pub fn int_min_literal(&mut self, ty: Ty<'tcx>) -> Literal<'tcx> {
+        let bits = self.int_bits(ty);
+        Literal::Value { value: ConstVal::Int(i64::MIN >> (64 - bits)) }
+    }
+
+    pub fn int_bits(&mut self, ty: Ty<'tcx>) -> u64 {
+        let bits = match ty.sty {
+            ty::TyInt(ast::TyIs) => self.tcx.sess.target.int_type.bit_width(),
+            ty::TyInt(t) => t.bit_width(),
+            ty::TyUint(ast::TyUs) => self.tcx.sess.target.uint_type.bit_width(),
+            ty::TyUint(t) => t.bit_width(),
+            _ => None,
+        };
+        match bits {
+            Some(bits) => bits as u64,
+            None => self.tcx.sess.bug(&format!("int_bits: not an integer type: {:?}", ty)),
+        }
+    }
+
+    /// Whether arithmetic in the fn being built panics on overflow.
+    pub fn check_overflow(&self) -> bool {
+        self.check_overflow
+    }
+
     pub fn const_eval_literal(&mut self, e: &hir::Expr) -> Literal<'tcx> {
         Literal::Value { value: const_eval::eval_const_expr(self.tcx, e) }
     }
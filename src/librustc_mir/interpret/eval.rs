@@ -0,0 +1,882 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use build;
+use mir_map::{self, MirMap};
+use rustc::front::map as ast_map;
+use rustc::middle::const_eval::{self, ConstVal, EvalHint, IntTy, UintTy};
+use rustc::middle::def_id::DefId;
+use rustc::middle::subst::{Subst, Substs};
+use rustc::middle::ty::{self, Ty};
+use rustc::mir::repr::*;
+use rustc::util::nodemap::DefIdMap;
+use rustc_front::hir;
+use std::ops::Deref;
+use std::rc::Rc;
+use syntax::abi::Abi;
+use syntax::ast;
+use syntax::codemap::{Span, DUMMY_SP};
+
+use super::{EvalError, EvalErrorKind};
+use super::memory::{Memory, Pointer, Value};
+
+/// The number of statements and terminators after which evaluation is
+/// abandoned, so that infinite loops do not hang the compiler.
+pub const STEP_LIMIT: usize = 1_000_000;
+
+/// The maximal depth of the call stack.
+const STACK_LIMIT: usize = 100;
+
+/// Arrays longer than this are not created; every element takes up a
+/// `Value`.
+const ARRAY_LIMIT: u64 = 1 << 20;
+
+type EvalResult<T> = Result<T, EvalErrorKind>;
+
+/// Where an lvalue lives.
+#[derive(Clone, Debug)]
+enum Place {
+    Ptr(Pointer),
+    /// The elements `start..start + len` of the array behind `array`; the
+    /// target of a slice.
+    Slice { array: Pointer, start: usize, len: usize },
+}
+
+#[derive(Clone)]
+enum FrameMir<'a, 'tcx: 'a> {
+    Borrowed(&'a Mir<'tcx>),
+    Owned(Rc<Mir<'tcx>>),
+}
+
+impl<'a, 'tcx> Deref for FrameMir<'a, 'tcx> {
+    type Target = Mir<'tcx>;
+
+    fn deref(&self) -> &Mir<'tcx> {
+        match *self {
+            FrameMir::Borrowed(mir) => mir,
+            FrameMir::Owned(ref mir) => mir,
+        }
+    }
+}
+
+struct Frame<'a, 'tcx: 'a> {
+    mir: FrameMir<'a, 'tcx>,
+    /// The fn being executed and the span of its call, for backtraces.
+    /// `None` for a constant expression.
+    call: Option<(Span, DefId)>,
+    substs: &'tcx Substs<'tcx>,
+    return_place: Place,
+    args: Vec<Pointer>,
+    vars: Vec<Pointer>,
+    temps: Vec<Pointer>,
+    block: BasicBlock,
+    statement: usize,
+}
+
+pub struct Interpreter<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    mir_map: &'a MirMap<'tcx>,
+    memory: Memory<'tcx>,
+    stack: Vec<Frame<'a, 'tcx>>,
+    consts: DefIdMap<Value<'tcx>>,
+    statics: DefIdMap<Pointer>,
+    steps: usize,
+    /// The span of the statement being evaluated.
+    span: Span,
+}
+
+impl<'a, 'tcx> Interpreter<'a, 'tcx> {
+    pub fn new(tcx: &'a ty::ctxt<'tcx>, mir_map: &'a MirMap<'tcx>) -> Interpreter<'a, 'tcx> {
+        Interpreter {
+            tcx: tcx,
+            mir_map: mir_map,
+            memory: Memory::new(),
+            stack: vec![],
+            consts: DefIdMap(),
+            statics: DefIdMap(),
+            steps: 0,
+            span: DUMMY_SP,
+        }
+    }
+
+    /// Evaluates the constant expression `expr`.
+    pub fn eval_expr(&mut self, expr: &'tcx hir::Expr) -> Result<Value<'tcx>, EvalError> {
+        self.span = expr.span;
+        let mir = Rc::new(mir_map::build_const_mir(self.tcx, expr));
+        let result = self.eval_nested(FrameMir::Owned(mir));
+        result.map_err(|kind| {
+            EvalError {
+                kind: kind,
+                span: self.span,
+                backtrace: self.stack.iter().rev().filter_map(|frame| frame.call).collect(),
+            }
+        })
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    // Frames
+
+    /// Evaluates `mir`, which takes no arguments, in a frame of its own.
+    fn eval_nested(&mut self, mir: FrameMir<'a, 'tcx>) -> EvalResult<Value<'tcx>> {
+        let depth = self.stack.len();
+        let return_place = Place::Ptr(self.memory.allocate(Value::Undef, true));
+        let substs = self.tcx.mk_substs(Substs::empty());
+        try!(self.push_frame(mir, None, substs, vec![], return_place.clone()));
+        try!(self.run(depth));
+        let value = try!(self.read_place(&return_place));
+        if let Place::Ptr(ref ptr) = return_place {
+            self.memory.deallocate(ptr.alloc);
+        }
+        Ok(value)
+    }
+
+    fn push_frame(&mut self,
+                  mir: FrameMir<'a, 'tcx>,
+                  call: Option<(Span, DefId)>,
+                  substs: &'tcx Substs<'tcx>,
+                  args: Vec<Value<'tcx>>,
+                  return_place: Place)
+                  -> EvalResult<()> {
+        if self.stack.len() >= STACK_LIMIT {
+            return Err(EvalErrorKind::StackOverflow);
+        }
+        let memory = &mut self.memory;
+        let args = args.into_iter().map(|arg| memory.allocate(arg, true)).collect();
+        let vars = mir.var_decls.iter().map(|_| memory.allocate(Value::Undef, true)).collect();
+        let temps = mir.temp_decls.iter().map(|_| memory.allocate(Value::Undef, true)).collect();
+        self.stack.push(Frame {
+            mir: mir,
+            call: call,
+            substs: substs,
+            return_place: return_place,
+            args: args,
+            vars: vars,
+            temps: temps,
+            block: START_BLOCK,
+            statement: 0,
+        });
+        Ok(())
+    }
+
+    fn pop_frame(&mut self) {
+        let frame = self.stack.pop().unwrap();
+        for ptr in frame.args.iter().chain(&frame.vars).chain(&frame.temps) {
+            self.memory.deallocate(ptr.alloc);
+        }
+    }
+
+    fn frame(&self) -> &Frame<'a, 'tcx> {
+        self.stack.last().unwrap()
+    }
+
+    fn frame_mut(&mut self) -> &mut Frame<'a, 'tcx> {
+        self.stack.last_mut().unwrap()
+    }
+
+    fn goto(&mut self, target: BasicBlock) {
+        let frame = self.frame_mut();
+        frame.block = target;
+        frame.statement = 0;
+    }
+
+    /// Runs until the stack shrinks to `depth` frames.
+    fn run(&mut self, depth: usize) -> EvalResult<()> {
+        while self.stack.len() > depth {
+            self.steps += 1;
+            if self.steps > STEP_LIMIT {
+                return Err(EvalErrorKind::StepLimitReached);
+            }
+
+            let (mir, block, index) = {
+                let frame = self.frame();
+                (frame.mir.clone(), frame.block, frame.statement)
+            };
+            let data = mir.basic_block_data(block);
+            match data.statements.get(index) {
+                Some(statement) => {
+                    self.span = statement.span;
+                    try!(self.eval_statement(statement));
+                    self.frame_mut().statement += 1;
+                }
+                None => try!(self.eval_terminator(&data.terminator)),
+            }
+        }
+        Ok(())
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    // Statements and terminators
+
+    fn eval_statement(&mut self, statement: &Statement<'tcx>) -> EvalResult<()> {
+        match statement.kind {
+            StatementKind::Assign(ref lvalue, ref rvalue) => {
+                let value = try!(self.eval_rvalue(rvalue));
+                let place = try!(self.eval_lvalue(lvalue));
+                self.write_place(&place, value)
+            }
+            // Constant expressions cannot run destructors, and memory is
+            // reclaimed when frames are popped.
+            StatementKind::Drop(..) => Ok(()),
+        }
+    }
+
+    fn eval_terminator(&mut self, terminator: &Terminator<'tcx>) -> EvalResult<()> {
+        match *terminator {
+            Terminator::Goto { target } => self.goto(target),
+            Terminator::Panic { ref msg, span, .. } => {
+                self.span = span;
+                return Err(panic_error(msg));
+            }
+            Terminator::Diverge => return Err(EvalErrorKind::Diverge),
+            Terminator::If { ref cond, targets: (then_target, else_target) } => {
+                match try!(self.eval_operand(cond)) {
+                    Value::Bool(true) => self.goto(then_target),
+                    Value::Bool(false) => self.goto(else_target),
+                    _ => return Err(EvalErrorKind::InvalidMemoryAccess),
+                }
+            }
+            Terminator::Switch { ref discr, ref targets, .. } => {
+                let place = try!(self.eval_lvalue(discr));
+                match try!(self.read_place(&place)) {
+                    Value::Adt { variant, .. } => self.goto(targets[variant]),
+                    _ => return Err(EvalErrorKind::InvalidMemoryAccess),
+                }
+            }
+            Terminator::SwitchInt { ref discr, switch_ty, ref values, ref targets } => {
+                let place = try!(self.eval_lvalue(discr));
+                let value = try!(self.read_place(&place));
+                let switch_ty = self.monomorphize(switch_ty);
+                let mut target = *targets.last().unwrap();
+                for (case, &case_target) in values.iter().zip(targets) {
+                    if try!(self.equals_const_val(&value, case, switch_ty)) {
+                        target = case_target;
+                        break;
+                    }
+                }
+                self.goto(target);
+            }
+            Terminator::Return => self.pop_frame(),
+            Terminator::Call { ref data, targets: (success, _) } => {
+                try!(self.eval_call(data, success));
+            }
+        }
+        Ok(())
+    }
+
+    fn eval_call(&mut self, data: &CallData<'tcx>, success: BasicBlock) -> EvalResult<()> {
+        let (def_id, substs) = match try!(self.eval_operand(&data.func)) {
+            Value::Fn(def_id, substs) => (def_id, substs),
+            _ => return Err(EvalErrorKind::InvalidMemoryAccess),
+        };
+        let mut args = Vec::with_capacity(data.args.len());
+        for arg in &data.args {
+            args.push(try!(self.eval_operand(arg)));
+        }
+        let destination = try!(self.eval_lvalue(&data.destination));
+
+        // Tuple structs and variants are called like fns.
+        let dest_ty = self.lvalue_ty(&data.destination);
+        if let ty::TyEnum(adt_def, _) | ty::TyStruct(adt_def, _) = dest_ty.sty {
+            if let Some(variant) = adt_def.variants.iter().position(|v| v.did == def_id) {
+                try!(self.write_place(&destination, Value::Adt {
+                    variant: variant,
+                    fields: args,
+                }));
+                self.goto(success);
+                return Ok(());
+            }
+        }
+
+        let func_ty = self.operand_ty(&data.func);
+        match func_ty.sty {
+            ty::TyBareFn(_, ref fn_ty) if fn_ty.abi == Abi::Rust => {}
+            _ => return Err(EvalErrorKind::Unsupported("calls to intrinsics and foreign fns")),
+        }
+        if const_eval::lookup_const_fn_by_id(self.tcx, def_id).is_none() {
+            return Err(EvalErrorKind::NonConstFn(def_id));
+        }
+        let mir = match self.tcx.map.as_local_node_id(def_id) {
+            Some(id) => self.mir_map.get(&id).map(FrameMir::Borrowed),
+            None => self.tcx.lookup_extern_mir(def_id).map(FrameMir::Owned),
+        };
+        let mir = match mir {
+            Some(mir) => mir,
+            None => return Err(EvalErrorKind::NoMir(def_id)),
+        };
+
+        // The caller resumes at the success target once the callee returns.
+        self.goto(success);
+        let call = Some((self.span, def_id));
+        self.push_frame(mir, call, substs, args, destination)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    // Lvalues
+
+    fn eval_lvalue(&mut self, lvalue: &Lvalue<'tcx>) -> EvalResult<Place> {
+        let place = match *lvalue {
+            Lvalue::Var(index) => Place::Ptr(self.frame().vars[index as usize].clone()),
+            Lvalue::Temp(index) => Place::Ptr(self.frame().temps[index as usize].clone()),
+            Lvalue::Arg(index) => Place::Ptr(self.frame().args[index as usize].clone()),
+            Lvalue::ReturnPointer => self.frame().return_place.clone(),
+            Lvalue::Static(def_id) => Place::Ptr(try!(self.static_ptr(def_id))),
+            Lvalue::Projection(ref proj) => {
+                let base = try!(self.eval_lvalue(&proj.base));
+                try!(self.eval_projection(base, &proj.elem))
+            }
+        };
+        Ok(place)
+    }
+
+    fn eval_projection(&mut self, base: Place, elem: &LvalueElem<'tcx>) -> EvalResult<Place> {
+        let place = match *elem {
+            ProjectionElem::Deref => {
+                match try!(self.read_place(&base)) {
+                    Value::Ptr(ptr) => Place::Ptr(ptr),
+                    Value::Slice { array, start, len } => {
+                        Place::Slice { array: array, start: start, len: len }
+                    }
+                    Value::Undef => return Err(EvalErrorKind::UseOfUndef),
+                    _ => return Err(EvalErrorKind::InvalidMemoryAccess),
+                }
+            }
+            ProjectionElem::Field(field) => match base {
+                Place::Ptr(ptr) => Place::Ptr(ptr.project(field.index())),
+                Place::Slice { .. } => return Err(EvalErrorKind::InvalidMemoryAccess),
+            },
+            ProjectionElem::Downcast(_, variant) => {
+                match try!(self.read_place(&base)) {
+                    Value::Adt { variant: actual, .. } if actual == variant => base,
+                    _ => return Err(EvalErrorKind::InvalidDowncast),
+                }
+            }
+            ProjectionElem::Index(ref index) => {
+                let index = match try!(self.eval_operand(index)) {
+                    Value::Uint(index) => index as usize,
+                    _ => return Err(EvalErrorKind::InvalidMemoryAccess),
+                };
+                try!(self.index_place(base, index))
+            }
+            ProjectionElem::ConstantIndex { offset, from_end, .. } => {
+                let len = try!(self.place_len(&base));
+                let index = if from_end { len - offset as usize } else { offset as usize };
+                try!(self.index_place(base, index))
+            }
+        };
+        Ok(place)
+    }
+
+    fn index_place(&self, base: Place, index: usize) -> EvalResult<Place> {
+        if index >= try!(self.place_len(&base)) {
+            return Err(EvalErrorKind::IndexOutOfBounds);
+        }
+        Ok(match base {
+            Place::Ptr(ptr) => Place::Ptr(ptr.project(index)),
+            Place::Slice { array, start, .. } => Place::Ptr(array.project(start + index)),
+        })
+    }
+
+    fn place_len(&self, place: &Place) -> EvalResult<usize> {
+        match *place {
+            Place::Ptr(ref ptr) => match *try!(self.memory.read(ptr)) {
+                Value::Array(ref elems) => Ok(elems.len()),
+                _ => Err(EvalErrorKind::InvalidMemoryAccess),
+            },
+            Place::Slice { len, .. } => Ok(len),
+        }
+    }
+
+    fn read_place(&self, place: &Place) -> EvalResult<Value<'tcx>> {
+        match *place {
+            Place::Ptr(ref ptr) => self.memory.read(ptr).map(|value| value.clone()),
+            Place::Slice { .. } => Err(EvalErrorKind::Unsupported("unsized values")),
+        }
+    }
+
+    fn write_place(&mut self, place: &Place, value: Value<'tcx>) -> EvalResult<()> {
+        match *place {
+            Place::Ptr(ref ptr) => self.memory.write(ptr, value),
+            Place::Slice { .. } => Err(EvalErrorKind::Unsupported("unsized values")),
+        }
+    }
+
+    /// The allocation holding the value of the static `def_id`, evaluating
+    /// its initializer the first time it is used.
+    fn static_ptr(&mut self, def_id: DefId) -> EvalResult<Pointer> {
+        if let Some(ptr) = self.statics.get(&def_id) {
+            return Ok(ptr.clone());
+        }
+        let node = self.tcx.map.as_local_node_id(def_id).and_then(|id| self.tcx.map.find(id));
+        let expr = match node {
+            Some(ast_map::NodeItem(&hir::Item { node: hir::ItemStatic(_, _, ref expr), .. })) => {
+                expr
+            }
+            _ => return Err(EvalErrorKind::Unsupported("statics of other crates")),
+        };
+        let mir = Rc::new(mir_map::build_const_mir(self.tcx, expr));
+        let value = try!(self.eval_nested(FrameMir::Owned(mir)));
+        let ptr = self.memory.allocate(value, false);
+        self.statics.insert(def_id, ptr.clone());
+        Ok(ptr)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    // Operands and rvalues
+
+    fn eval_operand(&mut self, operand: &Operand<'tcx>) -> EvalResult<Value<'tcx>> {
+        let value = match *operand {
+            Operand::Consume(ref lvalue) => {
+                let place = try!(self.eval_lvalue(lvalue));
+                try!(self.read_place(&place))
+            }
+            Operand::Constant(ref constant) => try!(self.eval_constant(constant)),
+        };
+        match value {
+            Value::Undef => Err(EvalErrorKind::UseOfUndef),
+            value => Ok(value),
+        }
+    }
+
+    fn eval_constant(&mut self, constant: &Constant<'tcx>) -> EvalResult<Value<'tcx>> {
+        let ty = self.monomorphize(constant.ty);
+        match constant.literal {
+            Literal::Value { ref value } => self.from_const_val(value, ty),
+            Literal::Item { def_id, substs } => {
+                match ty.sty {
+                    ty::TyBareFn(..) => {
+                        let substs = substs.subst(self.tcx, self.frame().substs);
+                        let substs = self.tcx.mk_substs(substs);
+                        return Ok(Value::Fn(def_id, substs));
+                    }
+                    // Unit structs and variants.
+                    ty::TyEnum(adt_def, _) | ty::TyStruct(adt_def, _) => {
+                        let variant = adt_def.variants.iter().position(|v| v.did == def_id);
+                        if let Some(variant) = variant {
+                            return Ok(Value::Adt { variant: variant, fields: vec![] });
+                        }
+                    }
+                    _ => {}
+                }
+                self.const_value(def_id, ty)
+            }
+        }
+    }
+
+    /// The value of the const `def_id`. Consts of the local crate are
+    /// interpreted; those of other crates are only available through
+    /// `const_eval`.
+    fn const_value(&mut self, def_id: DefId, ty: Ty<'tcx>) -> EvalResult<Value<'tcx>> {
+        if let Some(value) = self.consts.get(&def_id) {
+            return Ok(value.clone());
+        }
+        let expr = match const_eval::lookup_const_by_id(self.tcx, def_id, None) {
+            Some(expr) => expr,
+            None => return Err(EvalErrorKind::Unsupported("associated consts")),
+        };
+        let value = if def_id.is_local() {
+            let mir = Rc::new(mir_map::build_const_mir(self.tcx, expr));
+            try!(self.eval_nested(FrameMir::Owned(mir)))
+        } else {
+            match const_eval::eval_const_expr_partial(self.tcx, expr,
+                                                      EvalHint::ExprTypeChecked, None) {
+                Ok(const_val) => try!(self.from_const_val(&const_val, ty)),
+                Err(_) => return Err(EvalErrorKind::Unsupported("consts of other crates")),
+            }
+        };
+        self.consts.insert(def_id, value.clone());
+        Ok(value)
+    }
+
+    fn from_const_val(&mut self, const_val: &ConstVal, ty: Ty<'tcx>) -> EvalResult<Value<'tcx>> {
+        let value = match *const_val {
+            ConstVal::Int(n) if is_unsigned(ty) => Value::Uint(n as u64),
+            ConstVal::Uint(n) if is_signed(ty) => Value::Int(n as i64),
+            ConstVal::Int(n) => Value::Int(n),
+            ConstVal::Uint(n) => Value::Uint(n),
+            ConstVal::Float(f) => Value::Float(f),
+            ConstVal::Bool(b) => Value::Bool(b),
+            ConstVal::Str(ref s) => {
+                let bytes = s.as_bytes().iter().map(|&b| Value::Uint(b as u64)).collect();
+                let array = self.memory.allocate(Value::Array(bytes), false);
+                Value::Slice { array: array, start: 0, len: s.len() }
+            }
+            ConstVal::ByteStr(ref data) => {
+                let bytes = data.iter().map(|&b| Value::Uint(b as u64)).collect();
+                Value::Ptr(self.memory.allocate(Value::Array(bytes), false))
+            }
+            ConstVal::Function(def_id) => {
+                Value::Fn(def_id, self.tcx.mk_substs(Substs::empty()))
+            }
+            ConstVal::Struct(_) | ConstVal::Tuple(_) |
+            ConstVal::Array(..) | ConstVal::Repeat(..) => {
+                return Err(EvalErrorKind::Unsupported("aggregate constants"));
+            }
+        };
+        Ok(value)
+    }
+
+    /// Whether `value` is the constant `const_val` of type `ty`. Strings and
+    /// byte strings are compared by their contents, without allocating them.
+    fn equals_const_val(&mut self,
+                        value: &Value<'tcx>,
+                        const_val: &ConstVal,
+                        ty: Ty<'tcx>)
+                        -> EvalResult<bool> {
+        match (const_val, value) {
+            (&ConstVal::Str(ref s), &Value::Slice { ref array, start, len }) => {
+                match *try!(self.memory.read(array)) {
+                    Value::Array(ref elems) if start + len <= elems.len() => {
+                        Ok(bytes_equal(&elems[start..start + len], s.as_bytes()))
+                    }
+                    _ => Err(EvalErrorKind::InvalidMemoryAccess),
+                }
+            }
+            (&ConstVal::ByteStr(ref data), &Value::Ptr(ref ptr)) => {
+                match *try!(self.memory.read(ptr)) {
+                    Value::Array(ref elems) => Ok(bytes_equal(elems, data)),
+                    _ => Err(EvalErrorKind::InvalidMemoryAccess),
+                }
+            }
+            (&ConstVal::Str(_), _) | (&ConstVal::ByteStr(_), _) => {
+                Err(EvalErrorKind::InvalidMemoryAccess)
+            }
+            _ => Ok(try!(self.from_const_val(const_val, ty)) == *value),
+        }
+    }
+
+    fn eval_rvalue(&mut self, rvalue: &Rvalue<'tcx>) -> EvalResult<Value<'tcx>> {
+        match *rvalue {
+            Rvalue::Use(ref operand) => self.eval_operand(operand),
+            Rvalue::Repeat(ref operand, ref count) => {
+                let elem = try!(self.eval_operand(operand));
+                let count = match try!(self.eval_constant(count)) {
+                    Value::Uint(count) if count <= ARRAY_LIMIT => count as usize,
+                    Value::Uint(_) => return Err(EvalErrorKind::Unsupported("huge arrays")),
+                    _ => return Err(EvalErrorKind::InvalidMemoryAccess),
+                };
+                Ok(Value::Array(vec![elem; count]))
+            }
+            Rvalue::Ref(_, _, ref lvalue) => {
+                Ok(match try!(self.eval_lvalue(lvalue)) {
+                    Place::Ptr(ptr) => Value::Ptr(ptr),
+                    Place::Slice { array, start, len } => {
+                        Value::Slice { array: array, start: start, len: len }
+                    }
+                })
+            }
+            Rvalue::Len(ref lvalue) => {
+                let place = try!(self.eval_lvalue(lvalue));
+                Ok(Value::Uint(try!(self.place_len(&place)) as u64))
+            }
+            Rvalue::Cast(ref kind, ref operand, cast_ty) => {
+                let value = try!(self.eval_operand(operand));
+                let from_ty = self.operand_ty(operand);
+                let cast_ty = self.monomorphize(cast_ty);
+                self.eval_cast(kind, value, from_ty, cast_ty)
+            }
+            Rvalue::BinaryOp(op, ref left, ref right) => {
+                let ty = self.operand_ty(left);
+                let left = try!(self.eval_operand(left));
+                let right = try!(self.eval_operand(right));
+                self.eval_binop(op, left, right, ty)
+            }
+            Rvalue::UnaryOp(op, ref operand) => {
+                let ty = self.operand_ty(operand);
+                let value = try!(self.eval_operand(operand));
+                self.eval_unop(op, value, ty)
+            }
+            Rvalue::Box(_) => Ok(Value::Ptr(self.memory.allocate(Value::Undef, true))),
+            Rvalue::Aggregate(ref kind, ref operands) => {
+                let mut fields = Vec::with_capacity(operands.len());
+                for operand in operands {
+                    fields.push(try!(self.eval_operand(operand)));
+                }
+                Ok(match *kind {
+                    AggregateKind::Vec => Value::Array(fields),
+                    AggregateKind::Tuple |
+                    AggregateKind::Closure(..) => Value::Adt { variant: 0, fields: fields },
+                    AggregateKind::Adt(_, variant, _) => {
+                        Value::Adt { variant: variant, fields: fields }
+                    }
+                })
+            }
+            Rvalue::Slice { ref input, from_start, from_end } => {
+                let place = try!(self.eval_lvalue(input));
+                let len = try!(self.place_len(&place));
+                if from_start + from_end > len {
+                    return Err(EvalErrorKind::IndexOutOfBounds);
+                }
+                Ok(match place {
+                    Place::Ptr(array) => Value::Slice {
+                        array: array,
+                        start: from_start,
+                        len: len - from_start - from_end,
+                    },
+                    Place::Slice { array, start, len } => Value::Slice {
+                        array: array,
+                        start: start + from_start,
+                        len: len - from_start - from_end,
+                    },
+                })
+            }
+            Rvalue::InlineAsm(_) => Err(EvalErrorKind::Unsupported("inline assembly")),
+        }
+    }
+
+    fn eval_cast(&self, kind: &CastKind, value: Value<'tcx>, from_ty: Ty<'tcx>, to_ty: Ty<'tcx>)
+                 -> EvalResult<Value<'tcx>> {
+        match *kind {
+            CastKind::ReifyFnPointer | CastKind::UnsafeFnPointer => Ok(value),
+            CastKind::Unsize => match value {
+                Value::Ptr(ptr) => {
+                    let len = try!(self.place_len(&Place::Ptr(ptr.clone())));
+                    Ok(Value::Slice { array: ptr, start: 0, len: len })
+                }
+                _ => Err(EvalErrorKind::Unsupported("casts to trait objects")),
+            },
+            CastKind::Misc => {
+                // Enums are cast through their discriminant.
+                let value = match (value, &from_ty.sty) {
+                    (Value::Adt { variant, .. }, &ty::TyEnum(adt_def, _)) => {
+                        Value::Uint(adt_def.variants[variant].disr_val)
+                    }
+                    (value, _) => value,
+                };
+                let (bits, as_i64, as_f64) = match value {
+                    Value::Int(n) => (n as u64, n, n as f64),
+                    Value::Uint(n) => (n, n as i64, n as f64),
+                    Value::Bool(b) => (b as u64, b as i64, b as u8 as f64),
+                    Value::Float(f) => (f as u64, f as i64, f),
+                    _ => return Err(EvalErrorKind::Unsupported("pointer casts")),
+                };
+                let from_signed = is_signed(from_ty);
+                match to_ty.sty {
+                    ty::TyInt(_) => {
+                        let n = if from_signed { as_i64 } else { bits as i64 };
+                        Ok(Value::Int(self.truncate_signed(n, to_ty)))
+                    }
+                    ty::TyUint(_) | ty::TyChar => {
+                        let n = if from_signed { as_i64 as u64 } else { bits };
+                        Ok(Value::Uint(self.truncate_unsigned(n, to_ty)))
+                    }
+                    ty::TyFloat(ast::TyF32) => Ok(Value::Float(as_f64 as f32 as f64)),
+                    ty::TyFloat(ast::TyF64) => Ok(Value::Float(as_f64)),
+                    _ => Err(EvalErrorKind::Unsupported("pointer casts")),
+                }
+            }
+        }
+    }
+
+    fn eval_binop(&self, op: BinOp, left: Value<'tcx>, right: Value<'tcx>, ty: Ty<'tcx>)
+                  -> EvalResult<Value<'tcx>> {
+        use rustc::mir::repr::BinOp::*;
+
+        let bits = self.int_bits(ty);
+        if op == Shl || op == Shr {
+            let amount = try!(shift_amount(op, &right, bits));
+            return match left {
+                Value::Int(l) => {
+                    let n = if op == Shl { l << amount } else { l >> amount };
+                    Ok(Value::Int(self.truncate_signed(n, ty)))
+                }
+                Value::Uint(l) => {
+                    let n = if op == Shl { l << amount } else { l >> amount };
+                    Ok(Value::Uint(self.truncate_unsigned(n, ty)))
+                }
+                _ => Err(EvalErrorKind::InvalidMemoryAccess),
+            };
+        }
+        let value = match (left, right) {
+            (Value::Int(l), Value::Int(r)) => {
+                let min = -1i64 << (bits - 1);
+                let max = !min;
+                let result = match op {
+                    Add => l.checked_add(r),
+                    Sub => l.checked_sub(r),
+                    Mul => l.checked_mul(r),
+                    Div if r == 0 => return Err(EvalErrorKind::DivisionByZero),
+                    Rem if r == 0 => return Err(EvalErrorKind::RemainderByZero),
+                    Div | Rem if l == min && r == -1 => None,
+                    Div => Some(l / r),
+                    Rem => Some(l % r),
+                    BitXor => Some(l ^ r),
+                    BitAnd => Some(l & r),
+                    BitOr => Some(l | r),
+                    _ => return Ok(Value::Bool(compare(op, &l, &r))),
+                };
+                match result {
+                    Some(n) if min <= n && n <= max => Value::Int(n),
+                    _ => return Err(EvalErrorKind::Overflow(op)),
+                }
+            }
+            (Value::Uint(l), Value::Uint(r)) => {
+                let max = if bits == 64 { !0 } else { (1 << bits) - 1 };
+                let result = match op {
+                    Add => l.checked_add(r),
+                    Sub => l.checked_sub(r),
+                    Mul => l.checked_mul(r),
+                    Div if r == 0 => return Err(EvalErrorKind::DivisionByZero),
+                    Rem if r == 0 => return Err(EvalErrorKind::RemainderByZero),
+                    Div => Some(l / r),
+                    Rem => Some(l % r),
+                    BitXor => Some(l ^ r),
+                    BitAnd => Some(l & r),
+                    BitOr => Some(l | r),
+                    _ => return Ok(Value::Bool(compare(op, &l, &r))),
+                };
+                match result {
+                    Some(n) if n <= max => Value::Uint(n),
+                    _ => return Err(EvalErrorKind::Overflow(op)),
+                }
+            }
+            (Value::Float(l), Value::Float(r)) => match op {
+                Add => Value::Float(l + r),
+                Sub => Value::Float(l - r),
+                Mul => Value::Float(l * r),
+                Div => Value::Float(l / r),
+                Rem => Value::Float(l % r),
+                _ => Value::Bool(compare(op, &l, &r)),
+            },
+            (Value::Bool(l), Value::Bool(r)) => match op {
+                BitXor => Value::Bool(l ^ r),
+                BitAnd => Value::Bool(l & r),
+                BitOr => Value::Bool(l | r),
+                _ => Value::Bool(compare(op, &l, &r)),
+            },
+            (left, right) => match op {
+                Eq => Value::Bool(left == right),
+                Ne => Value::Bool(left != right),
+                _ => return Err(EvalErrorKind::Unsupported("comparison of pointers")),
+            },
+        };
+        Ok(value)
+    }
+
+    fn eval_unop(&self, op: UnOp, value: Value<'tcx>, ty: Ty<'tcx>) -> EvalResult<Value<'tcx>> {
+        match (op, value) {
+            (UnOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+            (UnOp::Not, Value::Int(n)) => Ok(Value::Int(!n)),
+            (UnOp::Not, Value::Uint(n)) => Ok(Value::Uint(self.truncate_unsigned(!n, ty))),
+            (UnOp::Neg, Value::Int(n)) => {
+                if n == -1i64 << (self.int_bits(ty) - 1) {
+                    Err(EvalErrorKind::NegationOverflow)
+                } else {
+                    Ok(Value::Int(-n))
+                }
+            }
+            (UnOp::Neg, Value::Float(f)) => Ok(Value::Float(-f)),
+            _ => Err(EvalErrorKind::InvalidMemoryAccess),
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    // Types
+
+    fn monomorphize(&self, ty: Ty<'tcx>) -> Ty<'tcx> {
+        ty.subst(self.tcx, self.frame().substs)
+    }
+
+    fn operand_ty(&self, operand: &Operand<'tcx>) -> Ty<'tcx> {
+        let ty = self.frame().mir.operand_ty(self.tcx, operand);
+        self.monomorphize(ty)
+    }
+
+    fn lvalue_ty(&self, lvalue: &Lvalue<'tcx>) -> Ty<'tcx> {
+        let ty = self.frame().mir.lvalue_ty(self.tcx, lvalue).to_ty(self.tcx);
+        self.monomorphize(ty)
+    }
+
+    /// The width of the integer type `ty`, or 64 for anything else.
+    fn int_bits(&self, ty: Ty<'tcx>) -> u32 {
+        match ty.sty {
+            ty::TyInt(t) => match IntTy::from(self.tcx, t) {
+                IntTy::I8 => 8,
+                IntTy::I16 => 16,
+                IntTy::I32 => 32,
+                IntTy::I64 => 64,
+            },
+            ty::TyUint(t) => match UintTy::from(self.tcx, t) {
+                UintTy::U8 => 8,
+                UintTy::U16 => 16,
+                UintTy::U32 => 32,
+                UintTy::U64 => 64,
+            },
+            ty::TyChar => 32,
+            _ => 64,
+        }
+    }
+
+    fn truncate_signed(&self, n: i64, ty: Ty<'tcx>) -> i64 {
+        let shift = 64 - self.int_bits(ty);
+        (n << shift) >> shift
+    }
+
+    fn truncate_unsigned(&self, n: u64, ty: Ty<'tcx>) -> u64 {
+        let shift = 64 - self.int_bits(ty);
+        (n << shift) >> shift
+    }
+}
+
+fn shift_amount(op: BinOp, amount: &Value, bits: u32) -> EvalResult<u32> {
+    match *amount {
+        Value::Int(n) if 0 <= n && n < bits as i64 => Ok(n as u32),
+        Value::Uint(n) if n < bits as u64 => Ok(n as u32),
+        _ => Err(EvalErrorKind::Overflow(op)),
+    }
+}
+
+/// The error reported for a `Panic` terminator with `msg`, i.e. for a check
+/// emitted by the MIR builder which failed.
+fn panic_error(msg: &PanicMessage) -> EvalErrorKind {
+    let msg = match *msg {
+        PanicMessage::BoundsCheck { .. } => return EvalErrorKind::IndexOutOfBounds,
+        PanicMessage::Message(ref msg) => msg,
+    };
+    match &msg[..] {
+        build::DIV_ZERO_MSG => EvalErrorKind::DivisionByZero,
+        build::REM_ZERO_MSG => EvalErrorKind::RemainderByZero,
+        build::DIV_OVERFLOW_MSG => EvalErrorKind::Overflow(BinOp::Div),
+        build::REM_OVERFLOW_MSG => EvalErrorKind::Overflow(BinOp::Rem),
+        build::NEG_OVERFLOW_MSG => EvalErrorKind::NegationOverflow,
+        _ => EvalErrorKind::Panic(msg.clone()),
+    }
+}
+
+/// Whether `elems` are the bytes `bytes`.
+fn bytes_equal(elems: &[Value], bytes: &[u8]) -> bool {
+    elems.len() == bytes.len() &&
+        elems.iter().zip(bytes).all(|(elem, &byte)| *elem == Value::Uint(byte as u64))
+}
+
+fn compare<T: PartialOrd>(op: BinOp, l: &T, r: &T) -> bool {
+    match op {
+        BinOp::Eq => l == r,
+        BinOp::Ne => l != r,
+        BinOp::Lt => l < r,
+        BinOp::Le => l <= r,
+        BinOp::Gt => l > r,
+        BinOp::Ge => l >= r,
+        _ => unreachable!(),
+    }
+}
+
+fn is_signed(ty: Ty) -> bool {
+    match ty.sty {
+        ty::TyInt(_) => true,
+        _ => false,
+    }
+}
+
+fn is_unsigned(ty: Ty) -> bool {
+    match ty.sty {
+        ty::TyUint(_) | ty::TyChar => true,
+        _ => false,
+    }
+}
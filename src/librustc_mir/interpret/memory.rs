@@ -0,0 +1,142 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The memory of the MIR interpreter.
+//!
+//! Memory is a set of allocations: one for every local of every frame, one
+//! for every `box`, and one for the data of every string literal and static.
+//! An allocation does not hold bytes but a single structured `Value`, a tree
+//! of scalars, aggregates and arrays. A pointer names an allocation and the
+//! path of field and element indices leading from its value to the pointee.
+//! That is all safe code can observe, and it keeps the interpreter
+//! independent of the data layout of the target.
+
+use rustc::middle::def_id::DefId;
+use rustc::middle::subst::Substs;
+
+use super::EvalErrorKind;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AllocId(usize);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pointer {
+    pub alloc: AllocId,
+    /// The indices of the fields and elements leading from the value of the
+    /// allocation to the pointee.
+    pub path: Vec<usize>,
+}
+
+impl Pointer {
+    /// A pointer to the field or element `index` of the pointee.
+    pub fn project(&self, index: usize) -> Pointer {
+        let mut path = self.path.clone();
+        path.push(index);
+        Pointer { alloc: self.alloc, path: path }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value<'tcx> {
+    /// Memory that was never initialized.
+    Undef,
+    Bool(bool),
+    /// A value of a signed integer type.
+    Int(i64),
+    /// A value of an unsigned integer type, or a `char`.
+    Uint(u64),
+    Float(f64),
+    /// A fn item, or a fn pointer to it.
+    Fn(DefId, &'tcx Substs<'tcx>),
+    /// A thin pointer, reference or box.
+    Ptr(Pointer),
+    /// A pointer to the elements `start..start + len` of the array behind
+    /// `array`, i.e. a slice or a `str`.
+    Slice { array: Pointer, start: usize, len: usize },
+    /// A struct, tuple, closure or enum. `variant` is only ever non-zero for
+    /// enums.
+    Adt { variant: usize, fields: Vec<Value<'tcx>> },
+    /// An array; the bytes of a `str` are an array of `Uint`s.
+    Array(Vec<Value<'tcx>>),
+}
+
+struct Allocation<'tcx> {
+    value: Value<'tcx>,
+    mutable: bool,
+    live: bool,
+}
+
+pub struct Memory<'tcx> {
+    allocs: Vec<Allocation<'tcx>>,
+}
+
+impl<'tcx> Memory<'tcx> {
+    pub fn new() -> Memory<'tcx> {
+        Memory { allocs: vec![] }
+    }
+
+    pub fn allocate(&mut self, value: Value<'tcx>, mutable: bool) -> Pointer {
+        let alloc = AllocId(self.allocs.len());
+        self.allocs.push(Allocation {
+            value: value,
+            mutable: mutable,
+            live: true,
+        });
+        Pointer { alloc: alloc, path: vec![] }
+    }
+
+    /// Frees `alloc`. Any later access through a pointer into it is an
+    /// error.
+    pub fn deallocate(&mut self, alloc: AllocId) {
+        let allocation = &mut self.allocs[alloc.0];
+        allocation.live = false;
+        allocation.value = Value::Undef;
+    }
+
+    pub fn read(&self, ptr: &Pointer) -> Result<&Value<'tcx>, EvalErrorKind> {
+        let allocation = &self.allocs[ptr.alloc.0];
+        if !allocation.live {
+            return Err(EvalErrorKind::DanglingPointer);
+        }
+        let mut value = &allocation.value;
+        for &index in &ptr.path {
+            value = match *value {
+                Value::Adt { ref fields, .. } => &fields[index],
+                Value::Array(ref elems) => &elems[index],
+                Value::Undef => return Err(EvalErrorKind::UseOfUndef),
+                _ => return Err(EvalErrorKind::InvalidMemoryAccess),
+            };
+        }
+        Ok(value)
+    }
+
+    pub fn write(&mut self, ptr: &Pointer, new_value: Value<'tcx>) -> Result<(), EvalErrorKind> {
+        let allocation = &mut self.allocs[ptr.alloc.0];
+        if !allocation.live {
+            return Err(EvalErrorKind::DanglingPointer);
+        }
+        if !allocation.mutable {
+            return Err(EvalErrorKind::ModifiedConstant);
+        }
+        let mut value = &mut allocation.value;
+        for &index in &ptr.path {
+            // Rebind to move the borrow into the loop body.
+            let current = value;
+            value = match *current {
+                Value::Adt { ref mut fields, .. } => &mut fields[index],
+                Value::Array(ref mut elems) => &mut elems[index],
+                Value::Undef => return Err(EvalErrorKind::Unsupported("partial initialization")),
+                _ => return Err(EvalErrorKind::InvalidMemoryAccess),
+            };
+        }
+        *value = new_value;
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An interpreter for MIR, used by the MIR const checker (`-Z mir-const-check`)
+//! to find the errors in constant expressions. The values of constants are
+//! still computed by `middle::const_eval`; the interpreter's results are only
+//! used for the diagnostics.
+//!
+//! Unlike `middle::const_eval`, which folds the HIR of an expression, the
+//! interpreter executes the MIR of the expression and of every `const fn` it
+//! calls, one statement at a time, against a virtual memory (see `memory`).
+//! It can therefore run arbitrary control flow and work with aggregates,
+//! references and slices, and points at the precise statement, and the
+//! chain of calls leading to it, when evaluation fails.
+//!
+//! The MIR of constant expressions is built on demand, since the `MirMap`
+//! only contains the MIR of fns. Callees are looked up in the `MirMap` or in
+//! the metadata of the crate defining them.
+
+mod eval;
+mod memory;
+
+use mir_map::MirMap;
+use rustc::middle::def_id::DefId;
+use rustc::middle::ty;
+use rustc::mir::repr::BinOp;
+use rustc_front::hir;
+use rustc_front::intravisit::{self, Visitor};
+use syntax::codemap::Span;
+use syntax::parse::token::InternedString;
+
+use self::eval::Interpreter;
+
+#[derive(Clone, Debug)]
+pub enum EvalErrorKind {
+    Overflow(BinOp),
+    NegationOverflow,
+    DivisionByZero,
+    RemainderByZero,
+    IndexOutOfBounds,
+    /// A `Panic` terminator with a message that has no more specific error
+    /// kind was reached, e.g. a shift overflowed.
+    Panic(InternedString),
+    /// A `Diverge` terminator was reached, i.e. the evaluated code started
+    /// unwinding.
+    Diverge,
+    UseOfUndef,
+    DanglingPointer,
+    InvalidMemoryAccess,
+    InvalidDowncast,
+    ModifiedConstant,
+    StackOverflow,
+    StepLimitReached,
+    NonConstFn(DefId),
+    NoMir(DefId),
+    Unsupported(&'static str),
+}
+
+impl EvalErrorKind {
+    /// Whether the error is certain to be a bug in the evaluated code, rather
+    /// than possibly a limitation of the interpreter.
+    pub fn is_definite(&self) -> bool {
+        match *self {
+            EvalErrorKind::NonConstFn(_) |
+            EvalErrorKind::NoMir(_) |
+            EvalErrorKind::Unsupported(_) => false,
+            _ => true,
+        }
+    }
+
+    pub fn description(&self, tcx: &ty::ctxt) -> String {
+        match *self {
+            EvalErrorKind::Overflow(op) => {
+                let what = match op {
+                    BinOp::Add => "add",
+                    BinOp::Sub => "subtract",
+                    BinOp::Mul => "multiply",
+                    BinOp::Div => "divide",
+                    BinOp::Rem => "calculate the remainder",
+                    BinOp::Shl => "shift left",
+                    BinOp::Shr => "shift right",
+                    _ => "compute",
+                };
+                format!("attempted to {} with overflow", what)
+            }
+            EvalErrorKind::NegationOverflow => format!("attempted to negate with overflow"),
+            EvalErrorKind::DivisionByZero => format!("attempted to divide by zero"),
+            EvalErrorKind::RemainderByZero => {
+                format!("attempted to calculate the remainder with a divisor of zero")
+            }
+            EvalErrorKind::IndexOutOfBounds => format!("index out of bounds"),
+            EvalErrorKind::Panic(ref msg) => format!("panicked: {}", msg),
+            EvalErrorKind::Diverge => format!("reached the unwinding path"),
+            EvalErrorKind::UseOfUndef => format!("use of uninitialized memory"),
+            EvalErrorKind::DanglingPointer => format!("use of a dangling pointer"),
+            EvalErrorKind::InvalidMemoryAccess => format!("invalid memory access"),
+            EvalErrorKind::InvalidDowncast => format!("access to an inactive enum variant"),
+            EvalErrorKind::ModifiedConstant => format!("attempted to modify constant memory"),
+            EvalErrorKind::StackOverflow => format!("reached the recursion limit"),
+            EvalErrorKind::StepLimitReached => {
+                format!("evaluation did not finish in {} steps", eval::STEP_LIMIT)
+            }
+            EvalErrorKind::NonConstFn(def_id) => {
+                format!("call to non-const fn `{}`", tcx.item_path_str(def_id))
+            }
+            EvalErrorKind::NoMir(def_id) => {
+                format!("no MIR available for `{}`", tcx.item_path_str(def_id))
+            }
+            EvalErrorKind::Unsupported(what) => format!("unsupported: {}", what),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EvalError {
+    pub kind: EvalErrorKind,
+    /// The statement that was being evaluated.
+    pub span: Span,
+    /// The calls leading up to that statement, innermost first.
+    pub backtrace: Vec<(Span, DefId)>,
+}
+
+impl EvalError {
+    pub fn report(&self, tcx: &ty::ctxt) {
+        tcx.sess.span_err(self.span,
+                          &format!("constant evaluation error: {}",
+                                   self.kind.description(tcx)));
+        for &(span, def_id) in &self.backtrace {
+            tcx.sess.span_note(span,
+                               &format!("inside call to `{}`", tcx.item_path_str(def_id)));
+        }
+    }
+}
+
+/// Checks the initializers of all consts and statics and all array lengths
+/// of the crate by running them in the interpreter, and reports the errors
+/// found in them. Enabled by `-Z mir-const-check`.
+pub fn check_crate<'tcx>(tcx: &ty::ctxt<'tcx>, mir_map: &MirMap<'tcx>) {
+    let mut checker = ConstChecker {
+        tcx: tcx,
+        mir_map: mir_map,
+    };
+    tcx.map.krate().visit_all_items(&mut checker);
+}
+
+struct ConstChecker<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    mir_map: &'a MirMap<'tcx>,
+}
+
+impl<'a, 'tcx> ConstChecker<'a, 'tcx> {
+    fn check(&self, expr: &'tcx hir::Expr) {
+        // Only errors during evaluation are of interest; the values
+        // themselves are still computed by `middle::const_eval`.
+        match Interpreter::new(self.tcx, self.mir_map).eval_expr(expr) {
+            Ok(_) => {}
+            Err(ref err) if err.kind.is_definite() => err.report(self.tcx),
+            Err(err) => {
+                debug!("check_crate: cannot evaluate {:?}: {}",
+                       expr, err.kind.description(self.tcx));
+            }
+        }
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for ConstChecker<'a, 'tcx> {
+    fn visit_item(&mut self, item: &'tcx hir::Item) {
+        match item.node {
+            hir::ItemConst(_, ref expr) | hir::ItemStatic(_, _, ref expr) => self.check(expr),
+            _ => {}
+        }
+        intravisit::walk_item(self, item);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr) {
+        if let hir::ExprRepeat(_, ref count) = expr.node {
+            self.check(count);
+        }
+        intravisit::walk_expr(self, expr);
+    }
+
+    fn visit_ty(&mut self, ty: &'tcx hir::Ty) {
+        if let hir::TyFixedLengthVec(_, ref len) = ty.node {
+            self.check(len);
+        }
+        intravisit::walk_ty(self, ty);
+    }
+}
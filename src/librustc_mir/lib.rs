@@ -30,8 +30,11 @@ extern crate rustc_back;
 extern crate syntax;
 
 pub mod build;
+pub mod dataflow;
+pub mod interpret;
 pub mod mir_map;
 mod hair;
 mod graphviz;
+pub mod pretty;
 pub mod transform;
 
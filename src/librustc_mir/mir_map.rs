@@ -14,19 +14,23 @@
 //! The attribute formats that are currently accepted are:
 //!
 //! - `#[rustc_mir(graphviz="file.gv")]`
+//! - `#[rustc_mir(graphviz="file.gv", annotate="temps,scopes,live")]` (see
+//!   the `graphviz` module for the annotations)
 //! - `#[rustc_mir(pretty="file.mir")]`
 
 extern crate syntax;
 extern crate rustc;
 extern crate rustc_front;
 
-use build;
-use dot;
+use build::{self, ScopeAuxiliary};
+use graphviz::{self, Annotations};
 use transform::*;
 use rustc::mir::repr::Mir;
 use hair::cx::Cx;
 use std::fs::File;
 
+use self::rustc::dep_graph::DepNode;
+use self::rustc::lint;
 use self::rustc::middle::infer;
 use self::rustc::middle::region::CodeExtentData;
 use self::rustc::middle::ty::{self, Ty};
@@ -81,6 +85,11 @@ impl<'a, 'tcx> OuterDump<'a, 'tcx> {
 
 impl<'a, 'tcx> Visitor<'tcx> for OuterDump<'a, 'tcx> {
     fn visit_item(&mut self, item: &'tcx hir::Item) {
+        let tcx = self.tcx;
+        let def_id = tcx.map.local_def_id(item.id);
+        let _task = tcx.dep_graph.in_task(DepNode::Mir(def_id));
+        tcx.dep_graph.read(DepNode::Hir(def_id));
+
         self.visit_mir(&item.attrs, |c| intravisit::walk_item(c, item));
         intravisit::walk_item(self, item);
     }
@@ -143,21 +152,55 @@ impl<'a, 'm, 'tcx> Visitor<'tcx> for InnerDump<'a,'m,'tcx> {
 
         let infcx = infer::new_infer_ctxt(self.tcx, &self.tcx.tables, Some(param_env), true);
 
-        match build_mir(Cx::new(&infcx), implicit_arg_tys, id, span, decl, body) {
-            Ok(mut mir) => {
-                simplify_cfg::SimplifyCfg::new().run_on_mir(&mut mir);
+        let cx = Cx::new(&infcx, self.tcx.check_overflow(id));
+        match build_mir(cx, implicit_arg_tys, id, span, decl, body) {
+            Ok((mut mir, scope_auxiliary)) => {
+                if self.tcx.sess.opts.debugging_opts.validate_mir {
+                    validate::validate(self.tcx, "building it", id, &mir);
+                }
+
+                // The MIR lints see the MIR as built, before any transformation.
+                lint::check_mir(self.tcx, id, &mir);
+
+                let meta_item_list: Vec<_> = self.attr
+                                                 .iter()
+                                                 .flat_map(|a| a.meta_item_list())
+                                                 .flat_map(|l| l.iter())
+                                                 .collect();
+
+                let mut annotations = Annotations::default();
+                for item in meta_item_list.iter().filter(|item| item.check_name("annotate")) {
+                    match item.value_str().map(|s| Annotations::parse(&s)) {
+                        Some(Ok(a)) => annotations = a,
+                        Some(Err(name)) => {
+                            self.tcx.sess.span_err(
+                                item.span,
+                                &format!("unknown MIR annotation `{}`", name));
+                        }
+                        None => {
+                            self.tcx.sess.span_err(
+                                item.span,
+                                "annotate attribute requires a list of annotations");
+                        }
+                    }
+                }
 
-                let meta_item_list = self.attr
-                                         .iter()
-                                         .flat_map(|a| a.meta_item_list())
-                                         .flat_map(|l| l.iter());
+                // The graphs show the MIR as built, before any pass, as the
+                // scopes are only known for it.
                 for item in meta_item_list {
                     if item.check_name("graphviz") {
                         match item.value_str() {
                             Some(s) => {
                                 match
                                     File::create(format!("{}{}", prefix, s))
-                                    .and_then(|ref mut output| dot::render(&mir, output))
+                                    .and_then(|ref mut output| {
+                                        graphviz::write_mir_graphviz(self.tcx,
+                                                                     id,
+                                                                     &mir,
+                                                                     &annotations,
+                                                                     Some(&scope_auxiliary),
+                                                                     output)
+                                    })
                                 {
                                     Ok(()) => { }
                                     Err(e) => {
@@ -178,6 +221,14 @@ impl<'a, 'm, 'tcx> Visitor<'tcx> for InnerDump<'a,'m,'tcx> {
                     }
                 }
 
+                run_pass(self.tcx, &mut simplify_cfg::SimplifyCfg::new(), id, &mut mir);
+                run_pass(self.tcx,
+                         &mut elaborate_drops::ElaborateDrops::new(self.tcx,
+                                                                   &infcx.parameter_environment),
+                         id,
+                         &mut mir);
+                run_optimization_passes(self.tcx, id, &mut mir);
+
                 let previous = self.map.insert(id, mir);
                 assert!(previous.is_none());
             }
@@ -194,7 +245,7 @@ fn build_mir<'a,'tcx:'a>(cx: Cx<'a,'tcx>,
                          span: Span,
                          decl: &'tcx hir::FnDecl,
                          body: &'tcx hir::Block)
-                         -> Result<Mir<'tcx>, ErrorReported> {
+                         -> Result<(Mir<'tcx>, Vec<ScopeAuxiliary>), ErrorReported> {
     // fetch the fully liberated fn signature (that is, all bound
     // types/lifetimes replaced)
     let fn_sig = match cx.tcx().tables.borrow().liberated_fn_sigs.get(&fn_id) {
@@ -226,6 +277,18 @@ fn build_mir<'a,'tcx:'a>(cx: Cx<'a,'tcx>,
                         body))
 }
 
+/// Builds the MIR of the constant expression `expr`, e.g. the initializer of
+/// a const or static or the length of an array. Unlike the MIR of fns, this
+/// is not kept in the `MirMap`; the MIR interpreter builds it on demand.
+pub fn build_const_mir<'tcx>(tcx: &ty::ctxt<'tcx>, expr: &'tcx hir::Expr) -> Mir<'tcx> {
+    let infcx = infer::new_infer_ctxt(tcx, &tcx.tables, None, true);
+    let temp_scope = tcx.region_maps.temporary_scope(expr.id);
+    // the interpreter reports overflow in constants itself
+    let mut mir = build::construct_const(Cx::new(&infcx, false), temp_scope, expr);
+    simplify_cfg::SimplifyCfg::new().run_on_mir(&mut mir);
+    mir
+}
+
 fn closure_self_ty<'a, 'tcx>(tcx: &ty::ctxt<'tcx>,
                              closure_expr_id: ast::NodeId,
                              body_id: ast::NodeId)
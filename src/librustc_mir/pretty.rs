@@ -0,0 +1,118 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A textual form of the MIR, for reading what the MIR passes do.
+//!
+//! `-Z dump-mir=<filter>` writes the MIR of every fn whose path contains
+//! `<filter>` (or of every fn, for `all`) before and after each pass, to
+//! `rustc.<path>.<pass>.<before|after>.mir` in the directory given by `-Z
+//! dump-mir-dir`, or the current directory. Several filters can be given,
+//! separated by `|`.
+//!
+//! The output names variables, temporaries and basic blocks the same way
+//! the `Debug` impls of the MIR do, and does not include node ids, so that
+//! it can be compared between compilations.
+
+use rustc::middle::ty::{self, FnOutput};
+use rustc::mir::repr::*;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use syntax::ast;
+
+const INDENT: &'static str = "    ";
+
+/// Writes the MIR of the fn `id` to its dump file if `-Z dump-mir` selects
+/// it. `when` says where it is in the sequence of passes, e.g. `"before"`.
+pub fn dump_mir<'tcx>(tcx: &ty::ctxt<'tcx>,
+                      pass_name: &str,
+                      when: &str,
+                      id: ast::NodeId,
+                      mir: &Mir<'tcx>) {
+    let filters = match tcx.sess.opts.debugging_opts.dump_mir {
+        Some(ref filters) => filters,
+        None => return,
+    };
+    let path = tcx.item_path_str(tcx.map.local_def_id(id));
+    if filters != "all" && !filters.split('|').any(|filter| path.contains(filter)) {
+        return;
+    }
+
+    let mut file_path = PathBuf::new();
+    if let Some(ref dir) = tcx.sess.opts.debugging_opts.dump_mir_dir {
+        file_path.push(dir);
+    }
+    let file_name = format!("rustc.{}.{}.{}.mir", file_name_part(&path), pass_name, when);
+    file_path.push(file_name);
+
+    let result = file_path.parent()
+                          .map_or(Ok(()), |dir| fs::create_dir_all(dir))
+                          .and_then(|()| File::create(&file_path))
+                          .and_then(|mut file| {
+                              try!(writeln!(file, "// MIR for `{}`", path));
+                              try!(writeln!(file, "// pass: {} ({})", pass_name, when));
+                              try!(writeln!(file, ""));
+                              write_mir_fn(&path, mir, &mut file)
+                          });
+    if let Err(e) = result {
+        tcx.sess.warn(&format!("could not write MIR to `{}`: {}", file_path.display(), e));
+    }
+}
+
+/// Replaces the characters of a path which are awkward in file names.
+/// Closures are told apart by the `{{closure}}` component of their path.
+fn file_name_part(path: &str) -> String {
+    path.chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// Writes `mir`, the MIR of the fn `name`, as text.
+pub fn write_mir_fn<'tcx>(name: &str, mir: &Mir<'tcx>, w: &mut Write) -> io::Result<()> {
+    try!(write!(w, "fn {}(", name));
+    for (i, arg) in mir.arg_decls.iter().enumerate() {
+        if i > 0 {
+            try!(write!(w, ", "));
+        }
+        try!(write!(w, "{:?}: {}", Lvalue::Arg(i as u32), arg.ty));
+    }
+    match mir.return_ty {
+        FnOutput::FnConverging(ty) => try!(writeln!(w, ") -> {} {{", ty)),
+        FnOutput::FnDiverging => try!(writeln!(w, ") -> ! {{")),
+    }
+
+    for (i, var) in mir.var_decls.iter().enumerate() {
+        let mutability = match var.mutability {
+            Mutability::Mut => "mut ",
+            Mutability::Not => "",
+        };
+        try!(writeln!(w, "{}let {}{:?}: {}; // {}",
+                      INDENT, mutability, Lvalue::Var(i as u32), var.ty, var.name));
+    }
+    for (i, temp) in mir.temp_decls.iter().enumerate() {
+        try!(writeln!(w, "{}let mut {:?}: {};", INDENT, Lvalue::Temp(i as u32), temp.ty));
+    }
+
+    for bb in mir.all_basic_blocks() {
+        try!(writeln!(w, ""));
+        try!(write_basic_block(bb, mir.basic_block_data(bb), w));
+    }
+
+    writeln!(w, "}}")
+}
+
+fn write_basic_block(bb: BasicBlock, data: &BasicBlockData, w: &mut Write) -> io::Result<()> {
+    try!(writeln!(w, "{}{:?}: {{", INDENT, bb));
+    for statement in &data.statements {
+        try!(writeln!(w, "{0}{0}{1:?};", INDENT, statement));
+    }
+    try!(writeln!(w, "{0}{0}{1:?};", INDENT, data.terminator));
+    writeln!(w, "{}}}", INDENT)
+}
@@ -0,0 +1,277 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Propagates constants through temps, and folds unary and binary
+//! operations on integer and boolean constants.
+//!
+//! A temp which is assigned exactly once, from a constant, holds that
+//! constant wherever it is used, since the MIR never reads a temp before
+//! assigning it. Its uses are replaced by the constant, which may make more
+//! operations constant; the assignment itself is left for `RemoveDeadTemps`.
+//! Temps which are borrowed are left alone, since they could be modified
+//! through the reference.
+//!
+//! Operations which would overflow or divide by zero are not folded, so
+//! that they still fail at runtime.
+
+use rustc::middle::const_eval::ConstVal;
+use rustc::middle::ty::{self, Ty};
+use rustc::mir::repr::*;
+use rustc::mir::visit::MutVisitor;
+use std::cmp::Ordering;
+use std::{u8, u16, u32, u64};
+use syntax::ast;
+use syntax::codemap::Span;
+use transform::MirPass;
+use transform::util;
+
+macro_rules! try_opt {
+    ($e:expr) => (match $e { Some(e) => e, None => return None })
+}
+
+pub struct ConstPropagation<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+}
+
+impl<'a, 'tcx> ConstPropagation<'a, 'tcx> {
+    pub fn new(tcx: &'a ty::ctxt<'tcx>) -> ConstPropagation<'a, 'tcx> {
+        ConstPropagation { tcx: tcx }
+    }
+}
+
+impl<'a, 'tcx> MirPass<'tcx> for ConstPropagation<'a, 'tcx> {
+    fn name(&self) -> &'static str {
+        "const_prop"
+    }
+
+    fn run_on_mir(&mut self, mir: &mut Mir<'tcx>) {
+        let mut changed = true;
+        while changed {
+            let mut propagator = Propagator {
+                tcx: self.tcx,
+                constants: constant_temps(mir),
+                changed: false,
+            };
+            for bb in mir.all_basic_blocks() {
+                let data = mir.basic_block_data_mut(bb);
+                for statement in &mut data.statements {
+                    propagator.visit_statement(bb, statement);
+                    if let StatementKind::Assign(_, ref mut rvalue) = statement.kind {
+                        if let Some(constant) = propagator.fold(rvalue, statement.span) {
+                            *rvalue = Rvalue::Use(Operand::Constant(constant));
+                            propagator.changed = true;
+                        }
+                    }
+                }
+                propagator.visit_terminator(bb, &mut data.terminator);
+            }
+            changed = propagator.changed;
+        }
+    }
+}
+
+/// The constant held by each temp, if it is assigned exactly once, from an
+/// integer, float, boolean or string constant, and never borrowed.
+pub fn constant_temps<'tcx>(mir: &Mir<'tcx>) -> Vec<Option<Constant<'tcx>>> {
+    let usage = util::temp_usage(mir);
+    let borrowed = util::borrowed_locals(mir);
+    let mut constants = vec![None; mir.temp_decls.len()];
+    for bb in mir.all_basic_blocks() {
+        for statement in &mir.basic_block_data(bb).statements {
+            if let StatementKind::Assign(Lvalue::Temp(index),
+                                         Rvalue::Use(Operand::Constant(ref constant))) =
+                    statement.kind {
+                if usage[index as usize].defs == 1 &&
+                   !borrowed.contains(&Lvalue::Temp(index)) &&
+                   is_propagatable(constant) {
+                    constants[index as usize] = Some(constant.clone());
+                }
+            }
+        }
+    }
+    constants
+}
+
+fn is_propagatable(constant: &Constant) -> bool {
+    match constant.literal {
+        Literal::Value { value: ConstVal::Int(_) } |
+        Literal::Value { value: ConstVal::Uint(_) } |
+        Literal::Value { value: ConstVal::Float(_) } |
+        Literal::Value { value: ConstVal::Bool(_) } |
+        Literal::Value { value: ConstVal::Str(_) } => true,
+        _ => false,
+    }
+}
+
+struct Propagator<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    constants: Vec<Option<Constant<'tcx>>>,
+    changed: bool,
+}
+
+impl<'a, 'tcx> MutVisitor<'tcx> for Propagator<'a, 'tcx> {
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>) {
+        let constant = match *operand {
+            Operand::Consume(Lvalue::Temp(index)) => self.constants[index as usize].clone(),
+            _ => None,
+        };
+        match constant {
+            Some(constant) => {
+                *operand = Operand::Constant(constant);
+                self.changed = true;
+            }
+            None => self.super_operand(operand),
+        }
+    }
+}
+
+impl<'a, 'tcx> Propagator<'a, 'tcx> {
+    /// The value of `rvalue`, if it is an operation on constants which can
+    /// be evaluated.
+    fn fold(&self, rvalue: &Rvalue<'tcx>, span: Span) -> Option<Constant<'tcx>> {
+        let (value, ty) = match *rvalue {
+            Rvalue::BinaryOp(op, Operand::Constant(ref lhs), Operand::Constant(ref rhs)) => {
+                match (&lhs.literal, &rhs.literal) {
+                    (&Literal::Value { value: ref a }, &Literal::Value { value: ref b }) => {
+                        let ty = if is_comparison(op) { self.tcx.types.bool } else { lhs.ty };
+                        (try_opt!(self.fold_binop(op, a, b, ty)), ty)
+                    }
+                    _ => return None,
+                }
+            }
+            Rvalue::UnaryOp(op, Operand::Constant(ref operand)) => {
+                match operand.literal {
+                    Literal::Value { ref value } => {
+                        (try_opt!(self.fold_unop(op, value, operand.ty)), operand.ty)
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        Some(Constant {
+            span: span,
+            ty: ty,
+            literal: Literal::Value { value: value },
+        })
+    }
+
+    fn fold_binop(&self, op: BinOp, a: &ConstVal, b: &ConstVal, ty: Ty<'tcx>)
+                  -> Option<ConstVal> {
+        use rustc::middle::const_eval::ConstVal::*;
+
+        if is_comparison(op) {
+            let ordering = match (a, b) {
+                (&Int(a), &Int(b)) => a.cmp(&b),
+                (&Uint(a), &Uint(b)) => a.cmp(&b),
+                (&Bool(a), &Bool(b)) => a.cmp(&b),
+                _ => return None,
+            };
+            return Some(Bool(match op {
+                BinOp::Eq => ordering == Ordering::Equal,
+                BinOp::Ne => ordering != Ordering::Equal,
+                BinOp::Lt => ordering == Ordering::Less,
+                BinOp::Le => ordering != Ordering::Greater,
+                BinOp::Gt => ordering == Ordering::Greater,
+                BinOp::Ge => ordering != Ordering::Less,
+                _ => unreachable!(),
+            }));
+        }
+
+        let value = match (a, b) {
+            (&Int(a), &Int(b)) => Int(try_opt!(match op {
+                BinOp::Add => a.checked_add(b),
+                BinOp::Sub => a.checked_sub(b),
+                BinOp::Mul => a.checked_mul(b),
+                BinOp::Div => a.checked_div(b),
+                BinOp::Rem => a.checked_rem(b),
+                BinOp::BitXor => Some(a ^ b),
+                BinOp::BitAnd => Some(a & b),
+                BinOp::BitOr => Some(a | b),
+                _ => None,
+            })),
+            (&Uint(a), &Uint(b)) => Uint(try_opt!(match op {
+                BinOp::Add => a.checked_add(b),
+                BinOp::Sub => a.checked_sub(b),
+                BinOp::Mul => a.checked_mul(b),
+                BinOp::Div => a.checked_div(b),
+                BinOp::Rem => a.checked_rem(b),
+                BinOp::BitXor => Some(a ^ b),
+                BinOp::BitAnd => Some(a & b),
+                BinOp::BitOr => Some(a | b),
+                _ => None,
+            })),
+            (&Bool(a), &Bool(b)) => Bool(match op {
+                BinOp::BitXor => a ^ b,
+                BinOp::BitAnd => a & b,
+                BinOp::BitOr => a | b,
+                _ => return None,
+            }),
+            _ => return None,
+        };
+        self.in_range(value, ty)
+    }
+
+    fn fold_unop(&self, op: UnOp, value: &ConstVal, ty: Ty<'tcx>) -> Option<ConstVal> {
+        use rustc::middle::const_eval::ConstVal::*;
+
+        let value = match (op, value) {
+            (UnOp::Not, &Bool(a)) => Bool(!a),
+            (UnOp::Not, &Int(a)) => Int(!a),
+            (UnOp::Not, &Uint(a)) => Uint(!a & try_opt!(self.uint_max(ty))),
+            (UnOp::Neg, &Int(a)) => Int(try_opt!(a.checked_neg())),
+            _ => return None,
+        };
+        self.in_range(value, ty)
+    }
+
+    /// `value`, if it can be represented in `ty`.
+    fn in_range(&self, value: ConstVal, ty: Ty<'tcx>) -> Option<ConstVal> {
+        let fits = match (&value, &ty.sty) {
+            (&ConstVal::Int(a), &ty::TyInt(int_ty)) => {
+                let int_ty = match int_ty {
+                    ast::TyIs => self.tcx.sess.target.int_type,
+                    int_ty => int_ty,
+                };
+                match int_ty {
+                    ast::TyI8 => a as i8 as i64 == a,
+                    ast::TyI16 => a as i16 as i64 == a,
+                    ast::TyI32 => a as i32 as i64 == a,
+                    ast::TyI64 | ast::TyIs => true,
+                }
+            }
+            (&ConstVal::Uint(a), &ty::TyUint(_)) => a <= try_opt!(self.uint_max(ty)),
+            (&ConstVal::Bool(_), &ty::TyBool) => true,
+            _ => false,
+        };
+        if fits { Some(value) } else { None }
+    }
+
+    fn uint_max(&self, ty: Ty<'tcx>) -> Option<u64> {
+        let uint_ty = match ty.sty {
+            ty::TyUint(ast::TyUs) => self.tcx.sess.target.uint_type,
+            ty::TyUint(uint_ty) => uint_ty,
+            _ => return None,
+        };
+        Some(match uint_ty {
+            ast::TyU8 => u8::MAX as u64,
+            ast::TyU16 => u16::MAX as u64,
+            ast::TyU32 => u32::MAX as u64,
+            ast::TyU64 | ast::TyUs => u64::MAX,
+        })
+    }
+}
+
+fn is_comparison(op: BinOp) -> bool {
+    match op {
+        BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Removes temps which only carry a copy (or move) of a local to a single
+//! use later in the same basic block:
+//!
+//! ```text
+//! tmp0 = var0;          // removed
+//! ...                   // nothing mentions var0 or tmp0
+//! var1 = Add(tmp0, 1);  // becomes Add(var0, 1)
+//! ```
+//!
+//! Locals which are borrowed anywhere in the fn are never propagated, since
+//! they could be modified through the reference in between.
+
+use rustc::mir::repr::*;
+use rustc::mir::visit::MutVisitor;
+use transform::MirPass;
+use transform::util::{self, TempUsage};
+
+pub struct CopyPropagation;
+
+impl CopyPropagation {
+    pub fn new() -> CopyPropagation {
+        CopyPropagation
+    }
+}
+
+impl<'tcx> MirPass<'tcx> for CopyPropagation {
+    fn name(&self) -> &'static str {
+        "copy_prop"
+    }
+
+    fn run_on_mir(&mut self, mir: &mut Mir<'tcx>) {
+        let usage = util::temp_usage(mir);
+        let borrowed = util::borrowed_locals(mir);
+
+        for bb in mir.all_basic_blocks() {
+            let data = mir.basic_block_data_mut(bb);
+            let mut index = 0;
+            while index < data.statements.len() {
+                if propagate_copy(data, index, &usage, &borrowed) {
+                    data.statements.remove(index);
+                } else {
+                    index += 1;
+                }
+            }
+        }
+    }
+}
+
+/// If statement `index` of `data` is a copy which can be propagated to its
+/// use, replaces the use by the source of the copy and returns true.
+fn propagate_copy<'tcx>(data: &mut BasicBlockData<'tcx>,
+                        index: usize,
+                        usage: &[TempUsage],
+                        borrowed: &[Lvalue<'tcx>])
+                        -> bool {
+    let (temp, source) = match data.statements[index].kind {
+        StatementKind::Assign(ref temp @ Lvalue::Temp(_),
+                              Rvalue::Use(Operand::Consume(ref source))) => {
+            (temp.clone(), source.clone())
+        }
+        _ => return false,
+    };
+    match temp {
+        Lvalue::Temp(i) if usage[i as usize].defs == 1 && usage[i as usize].uses == 1 => {}
+        _ => return false,
+    }
+    match source {
+        Lvalue::Var(_) | Lvalue::Arg(_) | Lvalue::Temp(_) => {}
+        _ => return false,
+    }
+    if borrowed.contains(&source) {
+        return false;
+    }
+
+    let mut replacer = Replacer { temp: &temp, source: &source, replaced: false };
+    for statement in &mut data.statements[index + 1..] {
+        if util::statement_mentions(statement, &temp) {
+            replacer.visit_statement(START_BLOCK, statement);
+            return replacer.replaced;
+        }
+        if util::statement_mentions(statement, &source) {
+            return false;
+        }
+    }
+    if util::terminator_mentions(&data.terminator, &temp) {
+        replacer.visit_terminator(START_BLOCK, &mut data.terminator);
+        return replacer.replaced;
+    }
+    false
+}
+
+/// Replaces an operand consuming the whole of `temp` by one consuming
+/// `source`.
+struct Replacer<'a, 'tcx: 'a> {
+    temp: &'a Lvalue<'tcx>,
+    source: &'a Lvalue<'tcx>,
+    replaced: bool,
+}
+
+impl<'a, 'tcx> MutVisitor<'tcx> for Replacer<'a, 'tcx> {
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>) {
+        match *operand {
+            Operand::Consume(ref mut lvalue) if lvalue == self.temp => {
+                *lvalue = self.source.clone();
+                self.replaced = true;
+            }
+            _ => self.super_operand(operand),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Removes assignments to temps whose value is never read, then the temps
+//! which are no longer mentioned at all.
+//!
+//! An assignment is only removed if evaluating its rvalue has no effect
+//! other than producing the value: it must not move out of anything (that
+//! would change what gets dropped) and must not be inline assembly.
+
+use dataflow::{self, Access, LiveLocals, LocalAccesses};
+use rustc::middle::ty;
+use rustc::mir::repr::*;
+use transform::MirPass;
+use transform::util;
+
+pub struct RemoveDeadTemps<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    param_env: &'a ty::ParameterEnvironment<'a, 'tcx>,
+}
+
+impl<'a, 'tcx> RemoveDeadTemps<'a, 'tcx> {
+    pub fn new(tcx: &'a ty::ctxt<'tcx>,
+               param_env: &'a ty::ParameterEnvironment<'a, 'tcx>)
+               -> RemoveDeadTemps<'a, 'tcx> {
+        RemoveDeadTemps {
+            tcx: tcx,
+            param_env: param_env,
+        }
+    }
+
+    /// The statements which assign a dead temp, by block, in decreasing
+    /// order.
+    fn dead_assignments(&self, mir: &Mir<'tcx>) -> Vec<Vec<usize>> {
+        let accesses = LocalAccesses::new(self.tcx, self.param_env, mir);
+        let locals = accesses.locals();
+        let liveness = dataflow::do_dataflow(mir, LiveLocals::new(accesses));
+        let accesses = liveness.denotation().accesses();
+
+        mir.all_basic_blocks().into_iter().map(|bb| {
+            let statements = &mir.basic_block_data(bb).statements;
+            let mut dead = vec![];
+            for (index, statement) in statements.iter().enumerate().rev() {
+                let (lvalue, rvalue) = match statement.kind {
+                    StatementKind::Assign(ref lvalue, ref rvalue) => (lvalue, rvalue),
+                    _ => continue,
+                };
+                let temp = match assigned_temp(lvalue) {
+                    Some(temp) => temp,
+                    None => continue,
+                };
+                if let Rvalue::InlineAsm(_) = *rvalue {
+                    continue;
+                }
+                let moves = accesses.of_statement(statement).iter().any(|&(_, access)| {
+                    match access {
+                        Access::Move { .. } => true,
+                        _ => false,
+                    }
+                });
+                let live = liveness.state_before(mir, bb, index + 1)
+                                   .contains(locals.index(temp).unwrap());
+                if !moves && !live {
+                    dead.push(index);
+                }
+            }
+            dead
+        }).collect()
+    }
+}
+
+/// The temp `lvalue` writes to, if it is a temp or a field or element of
+/// one (but not something a temp points to).
+fn assigned_temp<'a, 'tcx>(lvalue: &'a Lvalue<'tcx>) -> Option<&'a Lvalue<'tcx>> {
+    match *lvalue {
+        Lvalue::Temp(_) => Some(lvalue),
+        Lvalue::Projection(ref proj) => match proj.elem {
+            ProjectionElem::Deref => None,
+            _ => assigned_temp(&proj.base),
+        },
+        _ => None,
+    }
+}
+
+impl<'a, 'tcx> MirPass<'tcx> for RemoveDeadTemps<'a, 'tcx> {
+    fn name(&self) -> &'static str {
+        "dead_temps"
+    }
+
+    fn run_on_mir(&mut self, mir: &mut Mir<'tcx>) {
+        let dead = self.dead_assignments(mir);
+        for (bb, dead) in mir.all_basic_blocks().into_iter().zip(dead) {
+            let statements = &mut mir.basic_block_data_mut(bb).statements;
+            for index in dead {
+                statements.remove(index);
+            }
+        }
+        util::remove_unused_temps(mir);
+    }
+}
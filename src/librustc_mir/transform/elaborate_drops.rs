@@ -0,0 +1,301 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Drop elaboration: decides statically whether each drop of a local has
+//! anything to drop, instead of leaving it to a drop flag in the value.
+//!
+//! The builder drops every local with a destructor on every exit from its
+//! scope, whether or not it has been moved out of. Using the initialization
+//! analyses of `dataflow`, each such drop becomes:
+//!
+//! - nothing, if the local is definitely uninitialized there;
+//! - an unconditional drop, if it is definitely initialized;
+//! - otherwise, a drop guarded by a drop flag: a `bool` temp which is set
+//!   whenever the local is assigned and cleared whenever it is moved out of
+//!   or dropped.
+//!
+//! Drops of locals which are ever partially moved out of or partially
+//! assigned are left alone, as are drops of anything but whole locals.
+//!
+//! With `-Z orbit` every fn of the crate is translated from its elaborated
+//! MIR, so the types of the crate are laid out without the drop-flag fields
+//! of `trans::adt`. Crates compiled without `-Z orbit` rely on those fields
+//! and are not allowed to use such a crate.
+
+use dataflow::{self, Access, LocalAccesses, Locals};
+use dataflow::{MaybeInitializedLocals, MaybeUninitializedLocals};
+use rustc::middle::const_eval::ConstVal;
+use rustc::middle::ty;
+use rustc::mir::repr::*;
+use rustc_data_structures::bitvec::BitVector;
+use std::mem;
+use syntax::codemap::{Span, DUMMY_SP};
+use transform::MirPass;
+
+pub struct ElaborateDrops<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    param_env: &'a ty::ParameterEnvironment<'a, 'tcx>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Elaboration {
+    Remove,
+    Keep,
+    Guard,
+}
+
+/// What the elaboration does to a fn, worked out before changing it, as
+/// adding the drop flags renumbers the locals.
+struct Plan {
+    locals: Locals,
+    /// The locals which need a drop flag.
+    flagged: BitVector,
+    /// The elaboration of each drop, by block and statement.
+    drops: Vec<Vec<Option<Elaboration>>>,
+    /// The drop flags to update after each statement, by block and statement.
+    after_statement: Vec<Vec<Vec<(usize, bool)>>>,
+    /// The drop flags to update before each terminator.
+    before_terminator: Vec<Vec<(usize, bool)>>,
+    /// The flagged local a call returns into, for each block ending in one.
+    call_destination: Vec<Option<usize>>,
+}
+
+impl<'a, 'tcx> ElaborateDrops<'a, 'tcx> {
+    pub fn new(tcx: &'a ty::ctxt<'tcx>,
+               param_env: &'a ty::ParameterEnvironment<'a, 'tcx>)
+               -> ElaborateDrops<'a, 'tcx> {
+        ElaborateDrops {
+            tcx: tcx,
+            param_env: param_env,
+        }
+    }
+
+    fn plan(&self, mir: &Mir<'tcx>) -> Plan {
+        let accesses = LocalAccesses::new(self.tcx, self.param_env, mir);
+        let locals = accesses.locals();
+        let blocks = mir.all_basic_blocks();
+
+        // The locals whose drops cannot be elaborated.
+        let mut partial = BitVector::new(locals.len());
+        for &bb in &blocks {
+            let data = mir.basic_block_data(bb);
+            let mut all_accesses = accesses.of_terminator(&data.terminator);
+            for statement in &data.statements {
+                if let StatementKind::Drop(DropKind::Free, ref lvalue) = statement.kind {
+                    if let Some(local) = locals.index(lvalue) {
+                        partial.insert(local);
+                    }
+                }
+                all_accesses.extend(accesses.of_statement(statement));
+            }
+            for (local, access) in all_accesses {
+                match access {
+                    Access::PartialDef | Access::Move { partial: true } => {
+                        partial.insert(local);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let initialized = dataflow::do_dataflow(mir, MaybeInitializedLocals::new(
+            LocalAccesses::new(self.tcx, self.param_env, mir)));
+        let uninitialized = dataflow::do_dataflow(mir, MaybeUninitializedLocals::new(
+            LocalAccesses::new(self.tcx, self.param_env, mir)));
+
+        let mut flagged = BitVector::new(locals.len());
+        let drops: Vec<Vec<_>> = blocks.iter().map(|&bb| {
+            let statements = &mir.basic_block_data(bb).statements;
+            statements.iter().enumerate().map(|(index, statement)| {
+                let local = match statement.kind {
+                    StatementKind::Drop(DropKind::Deep, ref lvalue) => locals.index(lvalue),
+                    _ => None,
+                };
+                let local = match local {
+                    Some(local) if !partial.contains(local) => local,
+                    _ => return None,
+                };
+                let maybe_initialized = initialized.state_before(mir, bb, index)
+                                                   .contains(local);
+                let maybe_uninitialized = uninitialized.state_before(mir, bb, index)
+                                                       .contains(local);
+                Some(if !maybe_initialized {
+                    Elaboration::Remove
+                } else if !maybe_uninitialized {
+                    Elaboration::Keep
+                } else {
+                    flagged.insert(local);
+                    Elaboration::Guard
+                })
+            }).collect()
+        }).collect();
+
+        let (after_statement, before_terminator, call_destination) = {
+            let flag_updates = |accesses: Vec<(usize, Access)>| -> Vec<(usize, bool)> {
+                accesses.into_iter().filter_map(|(local, access)| {
+                    match access {
+                        _ if !flagged.contains(local) => None,
+                        Access::Def => Some((local, true)),
+                        Access::Move { partial: false } => Some((local, false)),
+                        _ => None,
+                    }
+                }).collect()
+            };
+            let after_statement = blocks.iter().map(|&bb| {
+                mir.basic_block_data(bb).statements.iter().map(|statement| {
+                    flag_updates(accesses.of_statement(statement))
+                }).collect()
+            }).collect();
+            let before_terminator = blocks.iter().map(|&bb| {
+                flag_updates(accesses.of_terminator(&mir.basic_block_data(bb).terminator))
+            }).collect();
+            let call_destination = blocks.iter().map(|&bb| {
+                match mir.basic_block_data(bb).terminator {
+                    Terminator::Call { ref data, .. } => {
+                        flag_updates(accesses.of_call_return(&data.destination))
+                            .into_iter()
+                            .map(|(local, _)| local)
+                            .next()
+                    }
+                    _ => None,
+                }
+            }).collect();
+            (after_statement, before_terminator, call_destination)
+        };
+
+        Plan {
+            locals: locals,
+            flagged: flagged,
+            drops: drops,
+            after_statement: after_statement,
+            before_terminator: before_terminator,
+            call_destination: call_destination,
+        }
+    }
+
+    fn set_flag(&self, flag: &Lvalue<'tcx>, value: bool, span: Span) -> Statement<'tcx> {
+        let constant = Constant {
+            span: span,
+            ty: self.tcx.types.bool,
+            literal: Literal::Value { value: ConstVal::Bool(value) },
+        };
+        Statement {
+            span: span,
+            kind: StatementKind::Assign(flag.clone(), Rvalue::Use(Operand::Constant(constant))),
+        }
+    }
+}
+
+impl<'a, 'tcx> MirPass<'tcx> for ElaborateDrops<'a, 'tcx> {
+    fn name(&self) -> &'static str {
+        "elaborate_drops"
+    }
+
+    fn run_on_mir(&mut self, mir: &mut Mir<'tcx>) {
+        let plan = self.plan(mir);
+        let changes = plan.drops.iter().any(|drops| {
+            drops.iter().any(|&drop| drop == Some(Elaboration::Remove) ||
+                                     drop == Some(Elaboration::Guard))
+        });
+        if !changes {
+            return;
+        }
+
+        // Args start out initialized, everything else uninitialized.
+        let mut flags = vec![None; plan.locals.len()];
+        let mut flag_inits = vec![];
+        for local in 0..plan.locals.len() {
+            if plan.flagged.contains(local) {
+                let flag = Lvalue::Temp(mir.temp_decls.len() as u32);
+                mir.temp_decls.push(TempDecl { ty: self.tcx.types.bool });
+                let initialized = plan.locals.args().start <= local &&
+                                  local < plan.locals.args().end;
+                flag_inits.push(self.set_flag(&flag, initialized, DUMMY_SP));
+                flags[local] = Some(flag);
+            }
+        }
+        let flag = |local: usize| flags[local].as_ref().unwrap();
+
+        for bb in 0..plan.drops.len() {
+            let data = mem::replace(&mut mir.basic_blocks[bb],
+                                    BasicBlockData::new(Terminator::Diverge));
+            let mut current = BasicBlock::new(bb);
+            let mut statements = if bb == START_BLOCK.index() {
+                flag_inits.clone()
+            } else {
+                vec![]
+            };
+
+            for (index, statement) in data.statements.into_iter().enumerate() {
+                let span = statement.span;
+                match plan.drops[bb][index] {
+                    Some(Elaboration::Remove) => continue,
+                    Some(Elaboration::Guard) => {
+                        // Split the block, and only run the drop if the flag
+                        // is set.
+                        let local = match statement.kind {
+                            StatementKind::Drop(_, ref lvalue) => plan.locals.index(lvalue),
+                            _ => None,
+                        }.unwrap();
+                        let rest = new_block(mir, vec![], Terminator::Diverge);
+                        let drop_statements = vec![statement,
+                                                   self.set_flag(flag(local), false, span)];
+                        let drop_block = new_block(mir,
+                                                   drop_statements,
+                                                   Terminator::Goto { target: rest });
+                        mir.basic_blocks[current.index()] = BasicBlockData {
+                            statements: mem::replace(&mut statements, vec![]),
+                            terminator: Terminator::If {
+                                cond: Operand::Consume(flag(local).clone()),
+                                targets: (drop_block, rest),
+                            },
+                        };
+                        current = rest;
+                        continue;
+                    }
+                    Some(Elaboration::Keep) | None => statements.push(statement),
+                }
+                for &(local, value) in &plan.after_statement[bb][index] {
+                    statements.push(self.set_flag(flag(local), value, span));
+                }
+            }
+
+            for &(local, value) in &plan.before_terminator[bb] {
+                statements.push(self.set_flag(flag(local), value, DUMMY_SP));
+            }
+
+            // A call assigns its destination only if it returns, so its flag
+            // is set on the way to the return target.
+            let mut terminator = data.terminator;
+            if let Some(local) = plan.call_destination[bb] {
+                if let Terminator::Call { targets: (ref mut success, _), .. } = terminator {
+                    let set_flag = vec![self.set_flag(flag(local), true, DUMMY_SP)];
+                    *success = new_block(mir, set_flag, Terminator::Goto { target: *success });
+                }
+            }
+
+            mir.basic_blocks[current.index()] = BasicBlockData {
+                statements: statements,
+                terminator: terminator,
+            };
+        }
+    }
+}
+
+fn new_block<'tcx>(mir: &mut Mir<'tcx>,
+                   statements: Vec<Statement<'tcx>>,
+                   terminator: Terminator<'tcx>)
+                   -> BasicBlock {
+    mir.basic_blocks.push(BasicBlockData {
+        statements: statements,
+        terminator: terminator,
+    });
+    BasicBlock::new(mir.basic_blocks.len() - 1)
+}
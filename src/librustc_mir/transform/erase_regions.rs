@@ -14,14 +14,14 @@
 
 use rustc::middle::ty;
 use rustc::mir::repr::*;
-use transform::MirPass;
+use transform::{self, MirPass};
 use mir_map::MirMap;
 
 pub fn erase_regions<'tcx>(tcx: &ty::ctxt<'tcx>, mir_map: &mut MirMap<'tcx>) {
     let mut eraser = EraseRegions::new(tcx);
 
-    for mir in mir_map.iter_mut().map(|(_, v)| v) {
-        eraser.run_on_mir(mir);
+    for (&id, mir) in mir_map.iter_mut() {
+        transform::run_pass(tcx, &mut eraser, id, mir);
     }
 }
 
@@ -31,6 +31,10 @@ pub struct EraseRegions<'a, 'tcx: 'a> {
 
 impl<'a, 'tcx> MirPass<'tcx> for EraseRegions<'a, 'tcx> {
 
+    fn name(&self) -> &'static str {
+        "erase_regions"
+    }
+
     fn run_on_mir(&mut self, mir: &mut Mir<'tcx>) {
 
         for basic_block in &mut mir.basic_blocks {
@@ -81,9 +85,16 @@ impl<'a, 'tcx> EraseRegions<'a, 'tcx> {
             Terminator::Goto { .. } |
             Terminator::Diverge |
             Terminator::Return |
-            Terminator::Panic { .. } => {
+            Terminator::Panic { msg: PanicMessage::Message(_), .. } => {
                 /* nothing to do */
             }
+            Terminator::Panic {
+                msg: PanicMessage::BoundsCheck { ref mut len, ref mut index },
+                ..
+            } => {
+                self.erase_regions_operand(len);
+                self.erase_regions_operand(index);
+            }
             Terminator::If { ref mut cond, .. } => {
                 self.erase_regions_operand(cond);
             }
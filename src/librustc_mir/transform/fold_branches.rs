@@ -0,0 +1,84 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Replaces `If` and `SwitchInt` terminators whose condition is a constant,
+//! or a temp holding one, by a `Goto` to the branch which is always taken,
+//! then removes the blocks which became unreachable.
+
+use rustc::middle::const_eval::ConstVal;
+use rustc::mir::repr::*;
+use transform::MirPass;
+use transform::const_prop::constant_temps;
+use transform::simplify_cfg::SimplifyCfg;
+
+pub struct FoldBranches;
+
+impl FoldBranches {
+    pub fn new() -> FoldBranches {
+        FoldBranches
+    }
+}
+
+impl<'tcx> MirPass<'tcx> for FoldBranches {
+    fn name(&self) -> &'static str {
+        "fold_branches"
+    }
+
+    fn run_on_mir(&mut self, mir: &mut Mir<'tcx>) {
+        let constants = constant_temps(mir);
+
+        let mut changed = false;
+        for bb in mir.all_basic_blocks() {
+            let target = match mir.basic_block_data(bb).terminator {
+                Terminator::If { cond: Operand::Constant(Constant {
+                    literal: Literal::Value { value: ConstVal::Bool(cond) }, ..
+                }), targets: (yes, no) } => {
+                    if cond { yes } else { no }
+                }
+                Terminator::If { cond: Operand::Consume(ref cond), targets: (yes, no) } => {
+                    match constant_value(&constants, cond) {
+                        Some(&ConstVal::Bool(true)) => yes,
+                        Some(&ConstVal::Bool(false)) => no,
+                        _ => continue,
+                    }
+                }
+                Terminator::SwitchInt { ref discr, ref values, ref targets, .. } => {
+                    match constant_value(&constants, discr) {
+                        Some(discr) => {
+                            let index = values.iter()
+                                              .position(|value| value == discr)
+                                              .unwrap_or(values.len());
+                            targets[index]
+                        }
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            };
+            mir.basic_block_data_mut(bb).terminator = Terminator::Goto { target: target };
+            changed = true;
+        }
+
+        if changed {
+            SimplifyCfg::new().run_on_mir(mir);
+        }
+    }
+}
+
+fn constant_value<'a, 'tcx>(constants: &'a [Option<Constant<'tcx>>], lvalue: &Lvalue<'tcx>)
+                            -> Option<&'a ConstVal> {
+    match *lvalue {
+        Lvalue::Temp(index) => match constants[index as usize] {
+            Some(Constant { literal: Literal::Value { ref value }, .. }) => Some(value),
+            _ => None,
+        },
+        _ => None,
+    }
+}
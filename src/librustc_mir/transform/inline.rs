@@ -0,0 +1,502 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Inlines calls to small, non-recursive fns into their callers.
+//!
+//! A call is inlined when its callee is statically known -- a fn item, an
+//! inherent method, or a trait method whose impl can be selected because the
+//! substitutions are fully concrete -- and the MIR of the callee is
+//! available. The callee is copied into the caller with its types
+//! substituted, its locals renumbered into fresh caller locals, and its
+//! `Return` and `Diverge` terminators turned into jumps to the targets of the
+//! call. The callee writes its return value into a var of its own, which is
+//! moved into the destination of the call on return. Only the call sites
+//! present in the caller before the pass runs are considered, so each fn is
+//! inlined at most one level deep.
+//!
+//! Whether a callee is worth inlining is decided by a simple cost model;
+//! `#[inline]` raises the threshold, `#[inline(always)]` lifts it and
+//! `#[inline(never)]` disables inlining of the fn entirely.
+//!
+//! Callees from other crates are inlined from the MIR exported in their crate
+//! metadata. Since the MIR of `#[inline]`, generic and `const` fns and of
+//! default trait methods is itself exported (see `ty::ctxt::exports_body`),
+//! only callees that are exported as well are inlined into such fns; the body
+//! of any other fn may refer to items that have no symbol in this crate.
+//!
+//! The pass only runs with `-Z orbit` or `-Z mir-inline`, but then at every
+//! optimization level, since it is most useful in debug builds, where LLVM
+//! does no inlining of its own. Fns into which any call was inlined are
+//! translated through the MIR even without `-Z orbit`, and so no longer refer
+//! to the instantiations of the callees they inlined.
+//!
+//! This pass runs after `erase_regions`, so neither the caller nor the callee
+//! contain any regions that would need to be substituted.
+
+use rustc::middle::def_id::DefId;
+use rustc::middle::infer;
+use rustc::middle::subst::{self, Subst, Substs, VecPerParamSpace};
+use rustc::middle::traits;
+use rustc::middle::ty::{self, HasTypeFlags, Ty};
+use rustc::mir::repr::*;
+use rustc::mir::visit::{LvalueContext, MutVisitor, Visitor};
+use rustc::util::nodemap::NodeSet;
+use std::borrow::Cow;
+use mir_map::MirMap;
+use syntax::abi::Abi;
+use syntax::ast;
+use syntax::attr::{self, InlineAttr};
+use syntax::codemap::DUMMY_SP;
+use syntax::parse::token;
+use transform::{self, MirPass};
+use transform::simplify_cfg::SimplifyCfg;
+
+/// The cost above which a callee without an `#[inline]` attribute is not
+/// inlined.
+const DEFAULT_THRESHOLD: usize = 25;
+
+/// The cost above which a callee marked `#[inline]` is not inlined.
+const HINT_THRESHOLD: usize = 100;
+
+/// Inlines calls throughout `mir_map`, returning the fns into which any
+/// call was inlined. Trans translates those through the MIR, since their
+/// AST no longer matches what should be generated.
+pub fn inline_calls<'tcx>(tcx: &ty::ctxt<'tcx>, mir_map: &mut MirMap<'tcx>) -> NodeSet {
+    let mut inlined = NodeSet();
+
+    // Visit the fns in a deterministic order, so that the output does not
+    // depend on the hashing of node ids.
+    let mut ids: Vec<ast::NodeId> = mir_map.keys().cloned().collect();
+    ids.sort();
+
+    for id in ids {
+        // Take the caller out of the map while inlining into it, so that the
+        // remaining MIR can be borrowed as the source of callee bodies.
+        let mut mir = mir_map.remove(&id).unwrap();
+        {
+            let mut inliner = Inliner::new(tcx, mir_map, tcx.map.local_def_id(id));
+            transform::run_pass(tcx, &mut inliner, id, &mut mir);
+            if inliner.changed {
+                transform::run_pass(tcx, &mut SimplifyCfg::new(), id, &mut mir);
+                inlined.insert(id);
+            }
+        }
+        mir_map.insert(id, mir);
+    }
+    inlined
+}
+
+pub struct Inliner<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    mir_map: &'a MirMap<'tcx>,
+    caller: DefId,
+    caller_is_exported: bool,
+    changed: bool,
+}
+
+impl<'a, 'tcx> MirPass<'tcx> for Inliner<'a, 'tcx> {
+    fn name(&self) -> &'static str {
+        "inline"
+    }
+
+    fn run_on_mir(&mut self, mir: &mut Mir<'tcx>) {
+        for bb in mir.all_basic_blocks() {
+            let callee = match mir.basic_block_data(bb).terminator {
+                Terminator::Call { ref data, .. } => self.inlinable_callee(data),
+                _ => None,
+            };
+            if let Some((callee_mir, substs)) = callee {
+                inline_call(self.tcx, mir, bb, &callee_mir, substs);
+                self.changed = true;
+            }
+        }
+    }
+}
+
+impl<'a, 'tcx> Inliner<'a, 'tcx> {
+    pub fn new(tcx: &'a ty::ctxt<'tcx>,
+               mir_map: &'a MirMap<'tcx>,
+               caller: DefId)
+               -> Inliner<'a, 'tcx> {
+        Inliner {
+            tcx: tcx,
+            mir_map: mir_map,
+            caller: caller,
+            caller_is_exported: tcx.exports_body(caller),
+            changed: false,
+        }
+    }
+
+    /// Returns the MIR of the fn called by `call` and the substitutions to
+    /// apply to it, if the call can and should be inlined.
+    fn inlinable_callee(&self, call: &CallData<'tcx>)
+                        -> Option<(Cow<'a, Mir<'tcx>>, &'tcx Substs<'tcx>)> {
+        let (def_id, substs) = match call.func {
+            Operand::Constant(Constant {
+                ty,
+                literal: Literal::Item { def_id, substs },
+                ..
+            }) => match ty.sty {
+                ty::TyBareFn(_, ref fn_ty) if fn_ty.abi == Abi::Rust => (def_id, substs),
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        let (def_id, substs) = match self.resolve(def_id, substs) {
+            Some(callee) => callee,
+            None => return None,
+        };
+        if def_id == self.caller {
+            return None;
+        }
+        if self.caller_is_exported && def_id.is_local() && !self.tcx.exports_body(def_id) {
+            return None;
+        }
+
+        let threshold = match attr::find_inline_attr(None, &self.tcx.get_attrs(def_id)) {
+            InlineAttr::Never => return None,
+            InlineAttr::None => DEFAULT_THRESHOLD,
+            InlineAttr::Hint => HINT_THRESHOLD,
+            InlineAttr::Always => usize::max_value(),
+        };
+
+        let callee_mir = match self.callee_mir(def_id) {
+            Some(callee_mir) => callee_mir,
+            None => return None,
+        };
+        if callee_mir.arg_decls.len() != call.args.len() {
+            return None;
+        }
+        if calls_any(&callee_mir, &[def_id, self.caller]) {
+            debug!("inline: not inlining recursive fn {:?}", def_id);
+            return None;
+        }
+
+        let cost = inline_cost(&callee_mir);
+        if cost > threshold {
+            debug!("inline: not inlining {:?}, cost {} exceeds {}", def_id, cost, threshold);
+            return None;
+        }
+
+        debug!("inline: inlining {:?} with cost {}", def_id, cost);
+        Some((callee_mir, substs))
+    }
+
+    /// Looks up the MIR of `def_id`, either in the map of the local crate or
+    /// in the metadata of the crate defining it.
+    fn callee_mir(&self, def_id: DefId) -> Option<Cow<'a, Mir<'tcx>>> {
+        if let Some(id) = self.tcx.map.as_local_node_id(def_id) {
+            return self.mir_map.get(&id).map(Cow::Borrowed);
+        }
+        match self.tcx.lookup_extern_mir(def_id) {
+            // The closures of an external fn can only be translated through
+            // its inlined AST, which the MIR does not refer to.
+            Some(ref mir) if mentions_closures(mir) => None,
+            Some(mir) => Some(Cow::Owned((*mir).clone())),
+            None => None,
+        }
+    }
+
+    /// Maps a reference to a trait method to the method of the impl that
+    /// would be called, if that impl is statically known. Other items are
+    /// returned unchanged.
+    fn resolve(&self, def_id: DefId, substs: &'tcx Substs<'tcx>)
+               -> Option<(DefId, &'tcx Substs<'tcx>)> {
+        let tcx = self.tcx;
+        let trait_id = match tcx.trait_of_item(def_id) {
+            Some(trait_id) => trait_id,
+            None => return Some((def_id, substs)),
+        };
+
+        // Inside of a generic fn, the impl may depend on the type
+        // parameters; leave such calls to trans.
+        if substs.types.needs_subst() {
+            return None;
+        }
+
+        // See `trans_static_method_callee_with_substs` for how the
+        // substitutions of the trait ref and the impl method are formed.
+        let subst::SeparateVecsPerParamSpace {
+            types: rcvr_type,
+            selfs: rcvr_self,
+            fns: rcvr_method
+        } = substs.types.clone().split();
+        let trait_substs = Substs::erased(VecPerParamSpace::new(rcvr_type,
+                                                                rcvr_self,
+                                                                Vec::new()));
+        let trait_ref = ty::Binder(ty::TraitRef::new(trait_id, tcx.mk_substs(trait_substs)));
+
+        let infcx = infer::normalizing_infer_ctxt(tcx, &tcx.tables);
+        let mut selcx = traits::SelectionContext::new(&infcx);
+        let obligation =
+            traits::Obligation::new(traits::ObligationCause::misc(DUMMY_SP, ast::DUMMY_NODE_ID),
+                                    trait_ref.to_poly_trait_predicate());
+        let selection = match selcx.select(&obligation) {
+            Ok(Some(selection)) => selection,
+            Ok(None) | Err(_) => return None,
+        };
+        let mut fulfill_cx = infcx.fulfillment_cx.borrow_mut();
+        let vtable = selection.map(|predicate| {
+            fulfill_cx.register_predicate_obligation(&infcx, predicate);
+        });
+        let vtable = match infer::drain_fulfillment_cx(&infcx, &mut fulfill_cx, &vtable) {
+            Ok(vtable) => vtable,
+            Err(_) => return None,
+        };
+
+        match vtable {
+            traits::VtableImpl(traits::VtableImplData { impl_def_id, substs: impl_substs, .. }) => {
+                let subst::SeparateVecsPerParamSpace {
+                    types: impl_type,
+                    selfs: impl_self,
+                    fns: _
+                } = impl_substs.types.split();
+                let callee_substs = Substs::erased(VecPerParamSpace::new(impl_type,
+                                                                         impl_self,
+                                                                         rcvr_method));
+                let mth = tcx.get_impl_method(impl_def_id, callee_substs, tcx.item_name(def_id));
+                Some((mth.method.def_id, tcx.mk_substs(mth.substs)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Estimates how much code inlining `mir` adds to a caller.
+fn inline_cost(mir: &Mir) -> usize {
+    mir.basic_blocks.iter().map(|data| {
+        let statements: usize = data.statements.iter().map(|statement| {
+            match statement.kind {
+                StatementKind::Assign(..) => 1,
+                // Drops may expand into calls to drop glue.
+                StatementKind::Drop(..) => 3,
+            }
+        }).sum();
+        let terminator = match data.terminator {
+            Terminator::Goto { .. } |
+            Terminator::Diverge |
+            Terminator::Return => 0,
+            Terminator::Call { .. } => 5,
+            Terminator::Panic { .. } |
+            Terminator::If { .. } |
+            Terminator::Switch { .. } |
+            Terminator::SwitchInt { .. } => 1,
+        };
+        statements + terminator
+    }).sum()
+}
+
+/// Whether `mir` mentions any of the fns in `def_ids` as a callee.
+fn calls_any(mir: &Mir, def_ids: &[DefId]) -> bool {
+    struct CallFinder<'d> {
+        def_ids: &'d [DefId],
+        found: bool,
+    }
+
+    impl<'d, 'tcx> Visitor<'tcx> for CallFinder<'d> {
+        fn visit_literal(&mut self, literal: &Literal<'tcx>) {
+            if let Literal::Item { def_id, .. } = *literal {
+                self.found |= self.def_ids.contains(&def_id);
+            }
+            self.super_literal(literal);
+        }
+    }
+
+    let mut finder = CallFinder { def_ids: def_ids, found: false };
+    finder.visit_mir(mir);
+    finder.found
+}
+
+/// Whether `mir` constructs any closures.
+fn mentions_closures(mir: &Mir) -> bool {
+    struct ClosureFinder {
+        found: bool,
+    }
+
+    impl<'tcx> Visitor<'tcx> for ClosureFinder {
+        fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>) {
+            if let Rvalue::Aggregate(AggregateKind::Closure(..), _) = *rvalue {
+                self.found = true;
+            }
+            self.super_rvalue(rvalue);
+        }
+    }
+
+    let mut finder = ClosureFinder { found: false };
+    finder.visit_mir(mir);
+    finder.found
+}
+
+/// Replaces the call terminating `bb` in `caller` by a copy of `callee`,
+/// whose type parameters are substituted with `substs`.
+fn inline_call<'tcx>(tcx: &ty::ctxt<'tcx>,
+                     caller: &mut Mir<'tcx>,
+                     bb: BasicBlock,
+                     callee: &Mir<'tcx>,
+                     substs: &'tcx Substs<'tcx>) {
+    let (call, (success, cleanup)) = match caller.basic_block_data(bb).terminator {
+        Terminator::Call { ref data, targets } => (data.clone(), targets),
+        _ => tcx.sess.bug(&format!("inline_call: {:?} does not end in a call", bb)),
+    };
+    let span = caller.basic_block_data(bb).statements.last()
+                     .map_or(DUMMY_SP, |statement| statement.span);
+
+    // The callee returns into a var of its own, which is moved into the
+    // destination of the call when it returns; the destination may be a
+    // temp, which must still be assigned only once.
+    let return_ty = match callee.return_ty {
+        ty::FnConverging(ty) => ty.subst(tcx, substs),
+        ty::FnDiverging => tcx.mk_nil(),
+    };
+    let return_var = caller.var_decls.len() as u32;
+    caller.var_decls.push(VarDecl {
+        mutability: Mutability::Mut,
+        name: token::intern("ret"),
+        ty: return_ty,
+    });
+
+    // The args of the callee become vars of the caller, since the callee is
+    // free to mutate and borrow them.
+    let arg_name = token::intern("arg");
+    let mut arg_vars = Vec::with_capacity(callee.arg_decls.len());
+    for (decl, arg) in callee.arg_decls.iter().zip(call.args) {
+        let index = caller.var_decls.len() as u32;
+        caller.var_decls.push(VarDecl {
+            mutability: Mutability::Mut,
+            name: arg_name,
+            ty: decl.ty.subst(tcx, substs),
+        });
+        caller.basic_block_data_mut(bb).statements.push(Statement {
+            span: span,
+            kind: StatementKind::Assign(Lvalue::Var(index), Rvalue::Use(arg)),
+        });
+        arg_vars.push(index);
+    }
+
+    let mut integrator = Integrator {
+        tcx: tcx,
+        substs: substs,
+        var_offset: caller.var_decls.len() as u32,
+        temp_offset: caller.temp_decls.len() as u32,
+        block_offset: caller.basic_blocks.len(),
+        arg_vars: arg_vars,
+        return_var: return_var,
+    };
+
+    caller.var_decls.extend(callee.var_decls.iter().map(|decl| {
+        VarDecl { ty: decl.ty.subst(tcx, substs), ..decl.clone() }
+    }));
+    caller.temp_decls.extend(callee.temp_decls.iter().map(|decl| {
+        TempDecl { ty: decl.ty.subst(tcx, substs) }
+    }));
+
+    for (index, data) in callee.basic_blocks.iter().enumerate() {
+        let mut data = data.clone();
+        integrator.visit_basic_block_data(BasicBlock::new(index), &mut data);
+        data.terminator = match data.terminator {
+            Terminator::Return => {
+                let ret = Operand::Consume(Lvalue::Var(return_var));
+                data.statements.push(Statement {
+                    span: span,
+                    kind: StatementKind::Assign(call.destination.clone(), Rvalue::Use(ret)),
+                });
+                Terminator::Goto { target: success }
+            }
+            Terminator::Diverge => Terminator::Goto { target: cleanup },
+            mut terminator => {
+                for target in terminator.successors_mut() {
+                    *target = BasicBlock::new(target.index() + integrator.block_offset);
+                }
+                terminator
+            }
+        };
+        caller.basic_blocks.push(data);
+    }
+
+    caller.basic_block_data_mut(bb).terminator = Terminator::Goto {
+        target: BasicBlock::new(START_BLOCK.index() + integrator.block_offset),
+    };
+}
+
+/// Moves the locals and types of a callee body into the caller's frame.
+/// Basic blocks are renumbered separately by `inline_call`.
+struct Integrator<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    substs: &'tcx Substs<'tcx>,
+    var_offset: u32,
+    temp_offset: u32,
+    block_offset: usize,
+    arg_vars: Vec<u32>,
+    return_var: u32,
+}
+
+impl<'a, 'tcx> Integrator<'a, 'tcx> {
+    fn subst_ty(&self, ty: Ty<'tcx>) -> Ty<'tcx> {
+        ty.subst(self.tcx, self.substs)
+    }
+
+    fn subst_substs(&self, substs: &'tcx Substs<'tcx>) -> &'tcx Substs<'tcx> {
+        self.tcx.mk_substs(substs.subst(self.tcx, self.substs))
+    }
+}
+
+impl<'a, 'tcx> MutVisitor<'tcx> for Integrator<'a, 'tcx> {
+    fn visit_lvalue(&mut self, lvalue: &mut Lvalue<'tcx>, _context: LvalueContext) {
+        match *lvalue {
+            Lvalue::Var(ref mut index) => *index += self.var_offset,
+            Lvalue::Temp(ref mut index) => *index += self.temp_offset,
+            Lvalue::Arg(index) => *lvalue = Lvalue::Var(self.arg_vars[index as usize]),
+            Lvalue::ReturnPointer => *lvalue = Lvalue::Var(self.return_var),
+            Lvalue::Static(_) => {}
+            Lvalue::Projection(ref mut proj) => {
+                self.visit_lvalue(&mut proj.base, LvalueContext::Projection);
+                if let ProjectionElem::Index(ref mut index) = proj.elem {
+                    self.visit_operand(index);
+                }
+            }
+        }
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &mut Rvalue<'tcx>) {
+        match *rvalue {
+            Rvalue::Cast(_, _, ref mut ty) |
+            Rvalue::Box(ref mut ty) => *ty = self.subst_ty(*ty),
+            Rvalue::Aggregate(AggregateKind::Adt(_, _, ref mut substs), _) => {
+                *substs = self.subst_substs(*substs);
+            }
+            Rvalue::Aggregate(AggregateKind::Closure(def_id, ref mut closure_substs), _) => {
+                let cloned = Box::new(closure_substs.clone());
+                let ty = self.tcx.mk_closure_from_closure_substs(def_id, cloned);
+                *closure_substs = match self.subst_ty(ty).sty {
+                    ty::TyClosure(_, ref closure_substs) => &*closure_substs,
+                    _ => unreachable!()
+                };
+            }
+            _ => {}
+        }
+        self.super_rvalue(rvalue);
+    }
+
+    fn visit_terminator(&mut self, block: BasicBlock, terminator: &mut Terminator<'tcx>) {
+        if let Terminator::SwitchInt { ref mut switch_ty, .. } = *terminator {
+            *switch_ty = self.subst_ty(*switch_ty);
+        }
+        self.super_terminator(block, terminator);
+    }
+
+    fn visit_constant(&mut self, constant: &mut Constant<'tcx>) {
+        constant.ty = self.subst_ty(constant.ty);
+        if let Literal::Item { ref mut substs, .. } = constant.literal {
+            *substs = self.subst_substs(*substs);
+        }
+        self.super_constant(constant);
+    }
+}
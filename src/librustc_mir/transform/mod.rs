@@ -10,10 +10,52 @@
 
 pub mod simplify_cfg;
 pub mod erase_regions;
+pub mod const_prop;
+pub mod copy_prop;
+pub mod dead_temps;
+pub mod elaborate_drops;
+pub mod fold_branches;
+pub mod inline;
+pub mod validate;
 mod util;
 
+use pretty;
+use rustc::middle::ty;
+use rustc::session::config::OptLevel;
 use rustc::mir::repr::Mir;
+use syntax::ast::NodeId;
 
 pub trait MirPass<'tcx> {
+    /// The name of the pass, as it appears in the files written by `-Z
+    /// dump-mir`.
+    fn name(&self) -> &'static str;
+
     fn run_on_mir(&mut self, mir: &mut Mir<'tcx>);
 }
+
+/// Runs `pass` on `mir`, the MIR of the fn `id`, dumping it before and after
+/// if `-Z dump-mir` asks for it, and validating it afterwards under `-Z
+/// validate-mir`.
+pub fn run_pass<'tcx, P>(tcx: &ty::ctxt<'tcx>, pass: &mut P, id: NodeId, mir: &mut Mir<'tcx>)
+    where P: MirPass<'tcx>
+{
+    pretty::dump_mir(tcx, pass.name(), "before", id, mir);
+    pass.run_on_mir(mir);
+    pretty::dump_mir(tcx, pass.name(), "after", id, mir);
+    if tcx.sess.opts.debugging_opts.validate_mir {
+        validate::validate(tcx, &format!("the `{}` pass", pass.name()), id, mir);
+    }
+}
+
+/// Runs the MIR optimizations on the MIR of the fn `id`, unless optimizations
+/// are disabled by `-C opt-level=0`.
+pub fn run_optimization_passes<'tcx>(tcx: &ty::ctxt<'tcx>, id: NodeId, mir: &mut Mir<'tcx>) {
+    if tcx.sess.opts.optimize == OptLevel::No {
+        return;
+    }
+    let param_env = ty::ParameterEnvironment::for_item(tcx, id);
+    run_pass(tcx, &mut const_prop::ConstPropagation::new(tcx), id, mir);
+    run_pass(tcx, &mut fold_branches::FoldBranches::new(), id, mir);
+    run_pass(tcx, &mut copy_prop::CopyPropagation::new(), id, mir);
+    run_pass(tcx, &mut dead_temps::RemoveDeadTemps::new(tcx, &param_env), id, mir);
+}
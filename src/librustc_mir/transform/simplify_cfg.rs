@@ -124,6 +124,10 @@ impl SimplifyCfg {
 }
 
 impl<'tcx> MirPass<'tcx> for SimplifyCfg {
+    fn name(&self) -> &'static str {
+        "simplify_cfg"
+    }
+
     fn run_on_mir(&mut self, mir: &mut Mir<'tcx>) {
         let mut changed = true;
         while changed {
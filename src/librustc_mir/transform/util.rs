@@ -9,6 +9,7 @@
 // except according to those terms.
 
 use rustc::mir::repr::*;
+use rustc::mir::visit::{LvalueContext, MutVisitor, Visitor};
 
 /// Update basic block ids in all terminators using the given replacements,
 /// useful e.g. after removal of several basic blocks to update all terminators
@@ -49,3 +50,148 @@ pub fn retain_basic_blocks(mir: &mut Mir, keep: &[bool]) {
 
     update_basic_block_ids(mir, &replacements);
 }
+
+/// How often a temp is assigned as a whole (including as the destination of
+/// a call), and how often it is mentioned in any other way.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TempUsage {
+    pub defs: usize,
+    pub uses: usize,
+}
+
+pub fn temp_usage(mir: &Mir) -> Vec<TempUsage> {
+    let mut visitor = TempUsageVisitor {
+        usage: vec![TempUsage::default(); mir.temp_decls.len()],
+    };
+    visitor.visit_mir(mir);
+    visitor.usage
+}
+
+struct TempUsageVisitor {
+    usage: Vec<TempUsage>,
+}
+
+impl<'tcx> Visitor<'tcx> for TempUsageVisitor {
+    fn visit_lvalue(&mut self, lvalue: &Lvalue<'tcx>, context: LvalueContext) {
+        match *lvalue {
+            Lvalue::Temp(index) => {
+                let usage = &mut self.usage[index as usize];
+                match context {
+                    LvalueContext::Store => usage.defs += 1,
+                    _ => usage.uses += 1,
+                }
+            }
+            Lvalue::Projection(ref proj) => {
+                if let ProjectionElem::Index(ref index) = proj.elem {
+                    self.visit_operand(index);
+                }
+            }
+            _ => {}
+        }
+        self.super_lvalue(lvalue, context);
+    }
+}
+
+/// The locals which are borrowed (or sliced) anywhere in `mir`, possibly
+/// through a projection, and so may be modified through a reference.
+pub fn borrowed_locals<'tcx>(mir: &Mir<'tcx>) -> Vec<Lvalue<'tcx>> {
+    let mut visitor = BorrowedLocals { lvalues: vec![] };
+    visitor.visit_mir(mir);
+    visitor.lvalues
+}
+
+struct BorrowedLocals<'tcx> {
+    lvalues: Vec<Lvalue<'tcx>>,
+}
+
+impl<'tcx> Visitor<'tcx> for BorrowedLocals<'tcx> {
+    fn visit_lvalue(&mut self, lvalue: &Lvalue<'tcx>, context: LvalueContext) {
+        match context {
+            LvalueContext::Borrow { .. } | LvalueContext::Slice { .. } => {
+                let mut base = lvalue;
+                while let Lvalue::Projection(ref proj) = *base {
+                    base = &proj.base;
+                }
+                self.lvalues.push(base.clone());
+            }
+            _ => {}
+        }
+        self.super_lvalue(lvalue, context);
+    }
+}
+
+/// Whether `lvalue` is mentioned anywhere in `statement`, including as the
+/// base of a projection.
+pub fn statement_mentions(statement: &Statement, lvalue: &Lvalue) -> bool {
+    let mut visitor = MentionVisitor { lvalue: lvalue, found: false };
+    visitor.visit_statement(START_BLOCK, statement);
+    visitor.found
+}
+
+/// Whether `lvalue` is mentioned anywhere in `terminator`.
+pub fn terminator_mentions(terminator: &Terminator, lvalue: &Lvalue) -> bool {
+    let mut visitor = MentionVisitor { lvalue: lvalue, found: false };
+    visitor.visit_terminator(START_BLOCK, terminator);
+    visitor.found
+}
+
+struct MentionVisitor<'a, 'tcx: 'a> {
+    lvalue: &'a Lvalue<'tcx>,
+    found: bool,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for MentionVisitor<'a, 'tcx> {
+    fn visit_lvalue(&mut self, lvalue: &Lvalue<'tcx>, context: LvalueContext) {
+        if lvalue == self.lvalue {
+            self.found = true;
+        }
+        if let Lvalue::Projection(ref proj) = *lvalue {
+            if let ProjectionElem::Index(ref index) = proj.elem {
+                self.visit_operand(index);
+            }
+        }
+        self.super_lvalue(lvalue, context);
+    }
+}
+
+/// Removes the temps which are never mentioned, renumbering the others.
+pub fn remove_unused_temps(mir: &mut Mir) {
+    let usage = temp_usage(mir);
+    let mut replacements = Vec::with_capacity(usage.len());
+    let mut used = 0;
+    for usage in &usage {
+        replacements.push(used as u32);
+        if usage.defs + usage.uses > 0 {
+            used += 1;
+        }
+    }
+    if used == usage.len() {
+        return;
+    }
+
+    let mut index = 0;
+    mir.temp_decls.retain(|_| {
+        index += 1;
+        usage[index - 1].defs + usage[index - 1].uses > 0
+    });
+    TempRenumberer { replacements: &replacements }.visit_mir(mir);
+}
+
+struct TempRenumberer<'a> {
+    replacements: &'a [u32],
+}
+
+impl<'a, 'tcx> MutVisitor<'tcx> for TempRenumberer<'a> {
+    fn visit_lvalue(&mut self, lvalue: &mut Lvalue<'tcx>, context: LvalueContext) {
+        match *lvalue {
+            Lvalue::Temp(ref mut index) => *index = self.replacements[*index as usize],
+            Lvalue::Projection(ref mut proj) => {
+                if let ProjectionElem::Index(ref mut index) = proj.elem {
+                    self.visit_operand(index);
+                }
+            }
+            _ => {}
+        }
+        self.super_lvalue(lvalue, context);
+    }
+}
@@ -0,0 +1,477 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A sanity check of the invariants of the MIR, run after building it and
+//! after every pass when `-Z validate-mir` is given. A bug in the builder or
+//! in a pass is then reported at the block and statement which break an
+//! invariant, rather than as an assertion failure somewhere in trans.
+//!
+//! The checks are:
+//!
+//! - every reachable block has been terminated (only the `DIVERGE_BLOCK`
+//!   ends with `Diverge`, and only the `END_BLOCK` with `Return`), and all
+//!   jumps go to existing blocks;
+//! - the blocks reachable through cleanup edges (the target of a `Panic`,
+//!   and the unwinding target of a `Call`) are not also reachable along the
+//!   normal path, and do not return;
+//! - temps are not used at points where they are definitely not assigned;
+//! - lvalues only project what their base type allows, and the types
+//!   `mir::tcx` computes for both sides of every assignment agree (up to
+//!   regions), as do those of calls and their destinations.
+
+use dataflow::{self, Access, LocalAccesses, MaybeInitializedLocals};
+use rustc::middle::ty::{self, HasTypeFlags, Ty};
+use rustc::mir::repr::*;
+use rustc::mir::tcx::LvalueTy;
+use syntax::ast::NodeId;
+use syntax::codemap::Span;
+
+/// Checks `mir`, the MIR of the fn `id`, and reports every broken invariant
+/// as an error. `when` says what was last done to it, e.g. "the `inline`
+/// pass".
+pub fn validate<'tcx>(tcx: &ty::ctxt<'tcx>, when: &str, id: NodeId, mir: &Mir<'tcx>) {
+    let mut validator = Validator {
+        tcx: tcx,
+        mir: mir,
+        span: tcx.map.span(id),
+        location: String::new(),
+        errors: vec![],
+    };
+
+    // The other checks rely on the control flow graph being well formed.
+    if validator.check_targets() {
+        let reachable = validator.check_terminators();
+        validator.check_cleanup_edges(&reachable);
+        validator.check_types(&reachable);
+        validator.check_initialization(id, &reachable);
+    }
+
+    let name = tcx.item_path_str(tcx.map.local_def_id(id));
+    for (span, message) in validator.errors {
+        tcx.sess.span_err(span, &format!("invalid MIR of `{}` after {}: {}", name, when, message));
+    }
+}
+
+struct Validator<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    mir: &'a Mir<'tcx>,
+    /// The span of the statement being checked, or of the fn.
+    span: Span,
+    /// The block and statement being checked, e.g. `bb3[1]`.
+    location: String,
+    errors: Vec<(Span, String)>,
+}
+
+impl<'a, 'tcx> Validator<'a, 'tcx> {
+    fn error(&mut self, message: String) {
+        let message = format!("{}: {}", self.location, message);
+        self.errors.push((self.span, message));
+    }
+
+    fn at_terminator(&mut self, bb: BasicBlock) {
+        self.location = format!("{:?} (terminator)", bb);
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    // Control flow
+
+    /// Checks that all jumps go to existing blocks.
+    fn check_targets(&mut self) -> bool {
+        let mir = self.mir;
+        let num_blocks = mir.basic_blocks.len();
+        for bb in mir.all_basic_blocks() {
+            self.at_terminator(bb);
+            for &target in mir.basic_block_data(bb).terminator.successors() {
+                if target.index() >= num_blocks {
+                    self.error(format!("jumps to nonexistent block {:?}", target));
+                }
+            }
+        }
+        self.errors.is_empty()
+    }
+
+    /// Checks that all reachable blocks are properly terminated, and returns
+    /// which blocks are reachable.
+    fn check_terminators(&mut self) -> Vec<bool> {
+        let mir = self.mir;
+        let reachable = reachable_from(mir, &[START_BLOCK], |t| t.successors().to_vec());
+        for bb in mir.all_basic_blocks().into_iter().filter(|bb| reachable[bb.index()]) {
+            self.at_terminator(bb);
+            match mir.basic_block_data(bb).terminator {
+                Terminator::Diverge if bb != DIVERGE_BLOCK => {
+                    self.error(format!("block is never terminated"));
+                }
+                Terminator::Return if bb != END_BLOCK => {
+                    self.error(format!("block other than {:?} returns", END_BLOCK));
+                }
+                _ => {}
+            }
+        }
+        reachable
+    }
+
+    fn check_cleanup_edges(&mut self, reachable: &[bool]) {
+        let mir = self.mir;
+        let normal = reachable_from(mir, &[START_BLOCK], normal_successors);
+        let cleanup_entries: Vec<_> =
+            mir.all_basic_blocks()
+               .into_iter()
+               .filter(|bb| reachable[bb.index()])
+               .flat_map(|bb| cleanup_successors(&mir.basic_block_data(bb).terminator))
+               .collect();
+        let cleanup = reachable_from(mir, &cleanup_entries, |t| t.successors().to_vec());
+
+        for bb in mir.all_basic_blocks() {
+            if !cleanup[bb.index()] {
+                continue;
+            }
+            self.at_terminator(bb);
+            if normal[bb.index()] {
+                self.error(format!("block is reachable both along the normal path and \
+                                    through a cleanup edge"));
+            }
+            if let Terminator::Return = mir.basic_block_data(bb).terminator {
+                self.error(format!("cleanup block returns"));
+            }
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    // Types
+
+    fn check_types(&mut self, reachable: &[bool]) {
+        let mir = self.mir;
+        let fn_span = self.span;
+        for bb in mir.all_basic_blocks().into_iter().filter(|bb| reachable[bb.index()]) {
+            let data = mir.basic_block_data(bb);
+            for (index, statement) in data.statements.iter().enumerate() {
+                self.span = statement.span;
+                self.location = format!("{:?}[{}]", bb, index);
+                self.check_statement(statement);
+            }
+            self.span = fn_span;
+            self.at_terminator(bb);
+            self.check_terminator(&data.terminator);
+        }
+    }
+
+    fn check_statement(&mut self, statement: &Statement<'tcx>) {
+        match statement.kind {
+            StatementKind::Assign(ref lvalue, ref rvalue) => {
+                let lvalue_ty = self.check_lvalue(lvalue);
+                let rvalue_ty = self.check_rvalue(rvalue);
+                if let (Some(lvalue_ty), Some(rvalue_ty)) = (lvalue_ty, rvalue_ty) {
+                    if !self.same_type(lvalue_ty, rvalue_ty) {
+                        self.error(format!("assigns a value of type `{}` to `{:?}` of type `{}`",
+                                           rvalue_ty, lvalue, lvalue_ty));
+                    }
+                }
+            }
+            StatementKind::Drop(_, ref lvalue) => {
+                self.check_lvalue(lvalue);
+            }
+        }
+    }
+
+    fn check_terminator(&mut self, terminator: &Terminator<'tcx>) {
+        match *terminator {
+            Terminator::Goto { .. } |
+            Terminator::Panic { msg: PanicMessage::Message(_), .. } |
+            Terminator::Diverge |
+            Terminator::Return => {}
+            Terminator::Panic { msg: PanicMessage::BoundsCheck { ref len, ref index }, .. } => {
+                for operand in &[len, index] {
+                    if let Some(ty) = self.check_operand(operand) {
+                        if ty != self.tcx.types.usize {
+                            self.error(format!("bounds checks a value of type `{}`", ty));
+                        }
+                    }
+                }
+            }
+            Terminator::If { ref cond, .. } => {
+                if let Some(ty) = self.check_operand(cond) {
+                    if ty != self.tcx.types.bool {
+                        self.error(format!("branches on a value of type `{}`", ty));
+                    }
+                }
+            }
+            Terminator::Switch { ref discr, adt_def, ref targets } => {
+                if let Some(ty) = self.check_lvalue(discr) {
+                    match ty.sty {
+                        ty::TyEnum(def, _) if def == adt_def => {}
+                        _ => self.error(format!("switches on `{:?}` of type `{}` as a `{}`",
+                                                discr, ty, self.tcx.item_path_str(adt_def.did))),
+                    }
+                }
+                if targets.len() != adt_def.variants.len() {
+                    self.error(format!("switches to {} blocks on an enum with {} variants",
+                                       targets.len(), adt_def.variants.len()));
+                }
+            }
+            Terminator::SwitchInt { ref discr, ref values, ref targets, .. } => {
+                self.check_lvalue(discr);
+                if targets.len() != values.len() + 1 {
+                    self.error(format!("switches to {} blocks on {} values",
+                                       targets.len(), values.len()));
+                }
+            }
+            Terminator::Call { ref data, .. } => {
+                let destination_ty = self.check_lvalue(&data.destination);
+                let func_ty = self.check_operand(&data.func);
+                let arg_tys: Vec<_> = data.args.iter().map(|arg| self.check_operand(arg)).collect();
+                let sig = match func_ty {
+                    Some(&ty::TyS { sty: ty::TyBareFn(_, ref f), .. }) => &f.sig,
+                    Some(ty) => return self.error(format!("calls a value of type `{}`", ty)),
+                    None => return,
+                };
+                let inputs = self.tcx.erase_late_bound_regions(&sig.inputs());
+                if arg_tys.len() != inputs.len() && !sig.variadic() {
+                    self.error(format!("passes {} arguments to a fn taking {}",
+                                       arg_tys.len(), inputs.len()));
+                }
+                for (&arg_ty, &input) in arg_tys.iter().zip(&inputs) {
+                    if let Some(arg_ty) = arg_ty {
+                        if !self.same_type(arg_ty, input) {
+                            self.error(format!("passes an argument of type `{}` for a \
+                                                parameter of type `{}`", arg_ty, input));
+                        }
+                    }
+                }
+                let output = self.tcx.erase_late_bound_regions(&sig.output());
+                if let (Some(destination_ty), ty::FnConverging(output)) = (destination_ty,
+                                                                           output) {
+                    if !self.same_type(destination_ty, output) {
+                        self.error(format!("returns a value of type `{}` into `{:?}` of type \
+                                            `{}`", output, data.destination, destination_ty));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks that `lvalue` only refers to existing locals and that its
+    /// projections are valid for their bases, and returns its type.
+    fn check_lvalue(&mut self, lvalue: &Lvalue<'tcx>) -> Option<Ty<'tcx>> {
+        let mir = self.mir;
+        let (what, index, count) = match *lvalue {
+            Lvalue::Var(index) => ("var", index, mir.var_decls.len()),
+            Lvalue::Temp(index) => ("temp", index, mir.temp_decls.len()),
+            Lvalue::Arg(index) => ("arg", index, mir.arg_decls.len()),
+            Lvalue::ReturnPointer => {
+                if let ty::FnDiverging = mir.return_ty {
+                    self.error(format!("uses the return pointer of a diverging fn"));
+                    return None;
+                }
+                return Some(mir.lvalue_ty(self.tcx, lvalue).to_ty(self.tcx));
+            }
+            Lvalue::Static(_) => {
+                return Some(mir.lvalue_ty(self.tcx, lvalue).to_ty(self.tcx));
+            }
+            Lvalue::Projection(ref proj) => {
+                return self.check_projection(lvalue, proj);
+            }
+        };
+        if index as usize >= count {
+            self.error(format!("uses nonexistent {} {}", what, index));
+            return None;
+        }
+        Some(mir.lvalue_ty(self.tcx, lvalue).to_ty(self.tcx))
+    }
+
+    fn check_projection(&mut self, lvalue: &Lvalue<'tcx>, proj: &LvalueProjection<'tcx>)
+                        -> Option<Ty<'tcx>> {
+        let base_ty = match self.check_lvalue(&proj.base) {
+            Some(base_ty) => base_ty,
+            None => return None,
+        };
+        let valid = match proj.elem {
+            ProjectionElem::Deref => {
+                base_ty.builtin_deref(true, ty::LvaluePreference::NoPreference).is_some()
+            }
+            ProjectionElem::Index(ref index) => {
+                if let Some(index_ty) = self.check_operand(index) {
+                    if index_ty != self.tcx.types.usize {
+                        self.error(format!("indexes `{:?}` with a value of type `{}`",
+                                           proj.base, index_ty));
+                    }
+                }
+                base_ty.builtin_index().is_some()
+            }
+            ProjectionElem::ConstantIndex { .. } => base_ty.builtin_index().is_some(),
+            ProjectionElem::Downcast(adt_def, variant) => match base_ty.sty {
+                ty::TyEnum(def, _) => def == adt_def && variant < def.variants.len(),
+                _ => false,
+            },
+            ProjectionElem::Field(field) => {
+                let num_fields = match self.mir.lvalue_ty(self.tcx, &proj.base) {
+                    LvalueTy::Ty { ty } => match ty.sty {
+                        ty::TyStruct(def, _) => Some(def.struct_variant().fields.len()),
+                        ty::TyTuple(ref tys) => Some(tys.len()),
+                        ty::TyClosure(_, ref substs) => Some(substs.upvar_tys.len()),
+                        _ => None,
+                    },
+                    LvalueTy::Downcast { adt_def, variant_index, .. } => {
+                        Some(adt_def.variants[variant_index].fields.len())
+                    }
+                };
+                num_fields.map_or(false, |n| field.index() < n)
+            }
+        };
+        if !valid {
+            self.error(format!("invalid projection `{:?}` of a value of type `{}`",
+                               lvalue, base_ty));
+            return None;
+        }
+        Some(self.mir.lvalue_ty(self.tcx, lvalue).to_ty(self.tcx))
+    }
+
+    fn check_operand(&mut self, operand: &Operand<'tcx>) -> Option<Ty<'tcx>> {
+        match *operand {
+            Operand::Consume(ref lvalue) => self.check_lvalue(lvalue),
+            Operand::Constant(ref constant) => Some(constant.ty),
+        }
+    }
+
+    /// Checks the lvalues and operands of `rvalue`, and returns its type if
+    /// `mir::tcx` can compute it.
+    fn check_rvalue(&mut self, rvalue: &Rvalue<'tcx>) -> Option<Ty<'tcx>> {
+        let valid = match *rvalue {
+            Rvalue::Use(ref operand) |
+            Rvalue::Repeat(ref operand, _) |
+            Rvalue::Cast(_, ref operand, _) |
+            Rvalue::UnaryOp(_, ref operand) => self.check_operand(operand).is_some(),
+            Rvalue::Ref(_, _, ref lvalue) |
+            Rvalue::Len(ref lvalue) |
+            Rvalue::Slice { input: ref lvalue, .. } => self.check_lvalue(lvalue).is_some(),
+            Rvalue::BinaryOp(op, ref lhs, ref rhs) => {
+                match (self.check_operand(lhs), self.check_operand(rhs)) {
+                    (Some(lhs_ty), Some(rhs_ty)) => {
+                        let same_types_required = match op {
+                            BinOp::Shl | BinOp::Shr |
+                            BinOp::Eq | BinOp::Lt | BinOp::Le |
+                            BinOp::Ne | BinOp::Ge | BinOp::Gt => false,
+                            _ => true,
+                        };
+                        if same_types_required && lhs_ty != rhs_ty {
+                            self.error(format!("`{:?}` of values of types `{}` and `{}`",
+                                               op, lhs_ty, rhs_ty));
+                            false
+                        } else {
+                            true
+                        }
+                    }
+                    _ => false,
+                }
+            }
+            Rvalue::Aggregate(_, ref operands) => {
+                operands.iter().fold(true, |valid, operand| {
+                    self.check_operand(operand).is_some() && valid
+                })
+            }
+            Rvalue::Box(_) |
+            Rvalue::InlineAsm(_) => true,
+        };
+        if valid {
+            self.mir.rvalue_ty(self.tcx, rvalue)
+        } else {
+            None
+        }
+    }
+
+    /// Whether `a` and `b` are the same type, ignoring regions. Types which
+    /// contain errors are accepted, as they have already been reported.
+    fn same_type(&self, a: Ty<'tcx>, b: Ty<'tcx>) -> bool {
+        a.references_error() || b.references_error() ||
+            self.tcx.erase_regions(&a) == self.tcx.erase_regions(&b)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    // Initialization
+
+    /// Checks that temps are not read, moved or borrowed where they are
+    /// definitely not assigned. Drops are exempt: this is how the builder
+    /// drops temps which may have been moved out of.
+    fn check_initialization(&mut self, id: NodeId, reachable: &[bool]) {
+        let mir = self.mir;
+        let param_env = ty::ParameterEnvironment::for_item(self.tcx, id);
+        let accesses = LocalAccesses::new(self.tcx, &param_env, mir);
+        let locals = accesses.locals();
+        let initialized = LocalAccesses::new(self.tcx, &param_env, mir);
+        let results = dataflow::do_dataflow(mir, MaybeInitializedLocals::new(initialized));
+
+        let fn_span = self.span;
+        for bb in mir.all_basic_blocks().into_iter().filter(|bb| reachable[bb.index()]) {
+            let data = mir.basic_block_data(bb);
+            for (index, statement) in data.statements.iter().enumerate() {
+                if let StatementKind::Drop(..) = statement.kind {
+                    continue;
+                }
+                self.span = statement.span;
+                self.location = format!("{:?}[{}]", bb, index);
+                let state = results.state_before(mir, bb, index);
+                for (local, access) in accesses.of_statement(statement) {
+                    self.check_access(&locals.lvalue(local), access, state.contains(local));
+                }
+            }
+            self.span = fn_span;
+            self.at_terminator(bb);
+            let state = results.state_before(mir, bb, data.statements.len());
+            for (local, access) in accesses.of_terminator(&data.terminator) {
+                self.check_access(&locals.lvalue(local), access, state.contains(local));
+            }
+        }
+    }
+
+    fn check_access(&mut self, lvalue: &Lvalue<'tcx>, access: Access, maybe_initialized: bool) {
+        if let Lvalue::Temp(_) = *lvalue {
+            match access {
+                Access::Use | Access::Move { .. } | Access::Borrow if !maybe_initialized => {
+                    self.error(format!("uses `{:?}`, which is not assigned", lvalue));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The blocks reachable from `entries` by following the edges given by
+/// `successors`.
+fn reachable_from<'tcx, F>(mir: &Mir<'tcx>, entries: &[BasicBlock], successors: F) -> Vec<bool>
+    where F: Fn(&Terminator<'tcx>) -> Vec<BasicBlock>
+{
+    let mut reachable = vec![false; mir.basic_blocks.len()];
+    let mut worklist = entries.to_vec();
+    while let Some(bb) = worklist.pop() {
+        if reachable[bb.index()] {
+            continue;
+        }
+        reachable[bb.index()] = true;
+        worklist.extend(successors(&mir.basic_block_data(bb).terminator));
+    }
+    reachable
+}
+
+/// The successors of `terminator` along the normal path, i.e. all except
+/// the cleanup edges.
+fn normal_successors(terminator: &Terminator) -> Vec<BasicBlock> {
+    match *terminator {
+        Terminator::Panic { .. } => vec![],
+        Terminator::Call { targets: (success, _), .. } => vec![success],
+        _ => terminator.successors().to_vec(),
+    }
+}
+
+fn cleanup_successors(terminator: &Terminator) -> Vec<BasicBlock> {
+    match *terminator {
+        Terminator::Panic { target, .. } => vec![target],
+        Terminator::Call { targets: (_, cleanup), .. } => vec![cleanup],
+        _ => vec![],
+    }
+}
@@ -25,7 +25,8 @@ use Namespace::{TypeNS, ValueNS};
 use rustc::lint;
 use rustc::middle::privacy::{DependsOn, LastImport, Used, Unused};
 use syntax::ast;
-use syntax::codemap::{Span, DUMMY_SP};
+use syntax::codemap::{BytePos, Pos, Span, DUMMY_SP};
+use syntax::diagnostic::CodeSuggestion;
 
 use rustc_front::hir;
 use rustc_front::hir::{ViewPathGlob, ViewPathList, ViewPathSimple};
@@ -58,16 +59,16 @@ impl<'a, 'b, 'tcx> UnusedImportCheckVisitor<'a, 'b, 'tcx> {
     // only check imports and namespaces which are used. In particular, this
     // means that if an import could name either a public or private item, we
     // will check the correct thing, dependent on how the import is used.
-    fn finalize_import(&mut self, id: ast::NodeId, span: Span) {
+    fn finalize_import(&mut self, id: ast::NodeId, span: Span, fix: Option<CodeSuggestion>) {
         debug!("finalizing import uses for {:?}",
                self.session.codemap().span_to_snippet(span));
 
-        if !self.used_imports.contains(&(id, TypeNS)) &&
-           !self.used_imports.contains(&(id, ValueNS)) {
-            self.session.add_lint(lint::builtin::UNUSED_IMPORTS,
-                                  id,
-                                  span,
-                                  "unused import".to_string());
+        if self.is_unused(id) {
+            self.session.add_lint_with_suggestion(lint::builtin::UNUSED_IMPORTS,
+                                                  id,
+                                                  span,
+                                                  "unused import".to_string(),
+                                                  fix);
         }
 
         let mut def_map = self.def_map.borrow_mut();
@@ -109,6 +110,127 @@ impl<'a, 'b, 'tcx> UnusedImportCheckVisitor<'a, 'b, 'tcx> {
             type_used: t_used,
         };
     }
+
+    fn is_unused(&self, id: ast::NodeId) -> bool {
+        !self.used_imports.contains(&(id, TypeNS)) && !self.used_imports.contains(&(id, ValueNS))
+    }
+
+    /// The fixes for the unused imports of `item`, a `use` item, under `-Z
+    /// fix-unused`: one for each item of `import_ids`, the imports in `item`.
+    ///
+    /// If the whole item is unused, the first unused import removes it. If a
+    /// single import of a list remains, the first unused import collapses
+    /// the list to it. Otherwise, each unused import of a list removes itself
+    /// and a separating comma.
+    fn import_fixes(&self, item: &hir::Item, import_ids: &[ast::NodeId])
+                    -> Vec<Option<CodeSuggestion>> {
+        let mut fixes: Vec<_> = import_ids.iter().map(|_| None).collect();
+        // An attribute of a removed item would apply to the next one instead.
+        if !self.session.opts.debugging_opts.fix_unused || !item.attrs.is_empty() {
+            return fixes;
+        }
+        let unused: Vec<_> = import_ids.iter().map(|&id| self.is_unused(id)).collect();
+        let first_unused = match unused.iter().position(|&unused| unused) {
+            Some(index) => index,
+            None => return fixes,
+        };
+        let kept: Vec<_> = (0..unused.len()).filter(|&index| !unused[index]).collect();
+
+        if kept.is_empty() {
+            fixes[first_unused] = Some(CodeSuggestion {
+                span: self.whole_lines(item.span),
+                msg: "remove the unused import".to_string(),
+                replacement: String::new(),
+            });
+            return fixes;
+        }
+
+        let (view_path, list) = match item.node {
+            hir::ItemUse(ref view_path) => match view_path.node {
+                ViewPathList(ref path, ref list) => (view_path, list),
+                _ => return fixes,
+            },
+            _ => return fixes,
+        };
+        if kept.len() == 1 {
+            let mut replacement = path_to_string(path_of(view_path));
+            let rename = match list[kept[0]].node {
+                hir::PathListIdent { name, rename, .. } => {
+                    if !replacement.is_empty() {
+                        replacement.push_str("::");
+                    }
+                    replacement.push_str(&name.as_str());
+                    rename
+                }
+                hir::PathListMod { rename, .. } => rename,
+            };
+            if let Some(rename) = rename {
+                replacement.push_str(&format!(" as {}", rename));
+            }
+            fixes[first_unused] = Some(CodeSuggestion {
+                span: view_path.span,
+                msg: "remove the unused imports".to_string(),
+                replacement: replacement,
+            });
+            return fixes;
+        }
+
+        // Remove an unused import with the comma after it if it comes before
+        // every used import, and with the comma before it otherwise.
+        for index in 0..list.len() {
+            if !unused[index] {
+                continue;
+            }
+            let span = if index < kept[0] {
+                Span { hi: list[index + 1].span.lo, ..list[index].span }
+            } else {
+                Span { lo: list[index - 1].span.hi, ..list[index].span }
+            };
+            fixes[index] = Some(CodeSuggestion {
+                span: span,
+                msg: "remove the unused import".to_string(),
+                replacement: String::new(),
+            });
+        }
+        fixes
+    }
+
+    /// `span`, extended to the whole lines it covers if nothing else is on
+    /// them.
+    fn whole_lines(&self, span: Span) -> Span {
+        let cm = self.session.codemap();
+        let lo = cm.lookup_byte_offset(span.lo);
+        let hi = cm.lookup_byte_offset(span.hi);
+        let src = match lo.fm.src {
+            Some(ref src) => src,
+            None => return span,
+        };
+        let (lo, hi) = (lo.pos.to_usize(), hi.pos.to_usize());
+        let line_lo = src[..lo].rfind('\n').map_or(0, |newline| newline + 1);
+        let line_hi = src[hi..].find('\n').map_or(src.len(), |newline| hi + newline + 1);
+        if !src[line_lo..lo].trim().is_empty() || !src[hi..line_hi].trim().is_empty() {
+            return span;
+        }
+        Span {
+            lo: span.lo - BytePos((lo - line_lo) as u32),
+            hi: span.hi + BytePos((line_hi - hi) as u32),
+            expn_id: span.expn_id,
+        }
+    }
+}
+
+fn path_of(view_path: &hir::ViewPath) -> &hir::Path {
+    match view_path.node {
+        ViewPathSimple(_, ref path) | ViewPathList(ref path, _) | ViewPathGlob(ref path) => path,
+    }
+}
+
+fn path_to_string(path: &hir::Path) -> String {
+    let segments: Vec<_> = path.segments.iter().map(|segment| {
+        segment.identifier.name.as_str().to_string()
+    }).collect();
+    let prefix = if path.global { "::" } else { "" };
+    format!("{}{}", prefix, segments.join("::"))
 }
 
 impl<'a, 'b, 'v, 'tcx> Visitor<'v> for UnusedImportCheckVisitor<'a, 'b, 'tcx> {
@@ -135,22 +257,26 @@ impl<'a, 'b, 'v, 'tcx> Visitor<'v> for UnusedImportCheckVisitor<'a, 'b, 'tcx> {
             hir::ItemUse(ref p) => {
                 match p.node {
                     ViewPathSimple(_, _) => {
-                        self.finalize_import(item.id, p.span)
+                        let fix = self.import_fixes(item, &[item.id]).pop().unwrap();
+                        self.finalize_import(item.id, p.span, fix)
                     }
 
                     ViewPathList(_, ref list) => {
-                        for i in list {
-                            self.finalize_import(i.node.id(), i.span);
+                        let ids: Vec<_> = list.iter().map(|i| i.node.id()).collect();
+                        let fixes = self.import_fixes(item, &ids);
+                        for (i, fix) in list.iter().zip(fixes) {
+                            self.finalize_import(i.node.id(), i.span, fix);
                         }
                     }
                     ViewPathGlob(_) => {
-                        if !self.used_imports.contains(&(item.id, TypeNS)) &&
-                           !self.used_imports.contains(&(item.id, ValueNS)) {
+                        if self.is_unused(item.id) {
+                            let fix = self.import_fixes(item, &[item.id]).pop().unwrap();
                             self.session
-                                .add_lint(lint::builtin::UNUSED_IMPORTS,
-                                          item.id,
-                                          p.span,
-                                          "unused import".to_string());
+                                .add_lint_with_suggestion(lint::builtin::UNUSED_IMPORTS,
+                                                          item.id,
+                                                          p.span,
+                                                          "unused import".to_string(),
+                                                          fix);
                         }
                     }
                 }
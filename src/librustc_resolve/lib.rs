@@ -67,15 +67,18 @@ use syntax::ast;
 use syntax::ast::{CRATE_NODE_ID, Ident, Name, NodeId, CrateNum, TyIs, TyI8, TyI16, TyI32, TyI64};
 use syntax::ast::{TyUs, TyU8, TyU16, TyU32, TyU64, TyF64, TyF32};
 use syntax::attr::AttrMetaMethods;
+use syntax::parse::ParseSess;
+use syntax::parse::lexer::{self, Reader, TokenAndSpan};
 use syntax::parse::token::{self, special_names, special_idents};
 use syntax::codemap::{self, Span, Pos};
+use syntax::diagnostic::CodeSuggestion;
 use syntax::util::lev_distance::{lev_distance, max_suggestion_distance};
 
 use rustc_front::intravisit::{self, FnKind, Visitor};
 use rustc_front::hir;
 use rustc_front::hir::{Arm, BindByRef, BindByValue, BindingMode, Block};
 use rustc_front::hir::Crate;
-use rustc_front::hir::{Expr, ExprAgain, ExprBreak, ExprField};
+use rustc_front::hir::{Expr, ExprAgain, ExprBreak, ExprCall, ExprField};
 use rustc_front::hir::{ExprLoop, ExprWhile, ExprMethodCall};
 use rustc_front::hir::{ExprPath, ExprStruct, FnDecl};
 use rustc_front::hir::{ForeignItemFn, ForeignItemStatic, Generics};
@@ -1135,6 +1138,10 @@ pub struct Resolver<'a, 'tcx: 'a> {
     // so as to avoid printing duplicate errors
     emit_errors: bool,
 
+    // The callee of the innermost call expression being resolved; only a
+    // path in this position can be replaced by a macro invocation.
+    callee_id: Option<NodeId>,
+
     make_glob_map: bool,
     // Maps imports to the names of items actually imported (this actually maps
     // all imports, but only glob imports are actually interesting).
@@ -1198,6 +1205,7 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
             external_exports: DefIdSet(),
 
             emit_errors: true,
+            callee_id: None,
             make_glob_map: make_glob_map == MakeGlobMap::Yes,
             glob_map: HashMap::new(),
 
@@ -2862,6 +2870,49 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
         ResolveAttempt(resolution)
     }
 
+    /// The fix for an unnecessary qualification of the path at `span` under
+    /// `-Z fix-unused`: removing everything up to the end of its
+    /// `qualifiers`-th `::`, not counting those within type parameters.
+    fn qualification_fix(&self, span: Span, qualifiers: usize) -> Option<CodeSuggestion> {
+        if !self.session.opts.debugging_opts.fix_unused {
+            return None;
+        }
+        let snippet = match self.session.codemap().span_to_snippet(span) {
+            Ok(snippet) => snippet,
+            Err(_) => return None,
+        };
+        // Lex the path rather than scanning its characters, so that the `>`
+        // of an `->` (as in `Box<Fn() -> T>`) is not taken for the end of a
+        // type parameter list. The snippet gets a codemap of its own, which
+        // starts at zero, so token positions are offsets into it.
+        let sess = ParseSess::new();
+        let filemap = sess.codemap().new_filemap(String::from("<path>"), snippet);
+        let mut reader = lexer::StringReader::new(&sess.span_diagnostic, filemap);
+        let mut depth = 0;
+        let mut separators = 0;
+        loop {
+            let TokenAndSpan { tok, sp } = reader.next_token();
+            match tok {
+                token::Lt => depth += 1,
+                token::BinOp(token::Shl) => depth += 2,
+                token::Gt => depth -= 1,
+                token::BinOp(token::Shr) => depth -= 2,
+                token::ModSep if depth == 0 => {
+                    separators += 1;
+                    if separators == qualifiers {
+                        return Some(CodeSuggestion {
+                            span: Span { hi: span.lo + sp.hi, ..span },
+                            msg: "remove the unnecessary qualification".to_string(),
+                            replacement: String::new(),
+                        });
+                    }
+                }
+                token::Eof => return None,
+                _ => {}
+            }
+        }
+    }
+
     /// If `check_ribs` is true, checks the local definitions first; i.e.
     /// doesn't skip straight to the containing module.
     /// Skips `path_depth` trailing segments, which is also reflected in the
@@ -2898,11 +2949,13 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
         let def = self.resolve_module_relative_path(span, segments, namespace);
         match (def, unqualified_def) {
             (Some((ref d, _)), Some(ref ud)) if *d == ud.def => {
+                let fix = self.qualification_fix(span, segments.len() - 1);
                 self.session
-                    .add_lint(lint::builtin::UNUSED_QUALIFICATIONS,
-                              id,
-                              span,
-                              "unnecessary qualification".to_string());
+                    .add_lint_with_suggestion(lint::builtin::UNUSED_QUALIFICATIONS,
+                                              id,
+                                              span,
+                                              "unnecessary qualification".to_string(),
+                                              fix);
             }
             _ => {}
         }
@@ -3491,24 +3544,47 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                                               ResolutionError::SelfNotAvailableInStaticMethod);
                             } else {
                                 let last_name = path.segments.last().unwrap().identifier.name;
+                                // The replacement for the path, if there is one
+                                // which can be applied mechanically.
+                                let mut replacement = None;
                                 let mut msg = match self.find_fallback_in_self_type(last_name) {
                                     NoSuggestion => {
                                         // limit search to 5 to reduce the number
                                         // of stupid suggestions
                                         match self.find_best_match_for_name(&path_name) {
                                             SuggestionType::Macro(s) => {
-                                                format!("the macro `{}`", s)
+                                                let msg = format!("the macro `{}`", s);
+                                                if self.callee_id == Some(expr.id) {
+                                                    replacement = Some(s);
+                                                }
+                                                msg
+                                            }
+                                            SuggestionType::Function(s) => {
+                                                let msg = format!("`{}`", s);
+                                                if !path.global && path.segments.len() == 1 {
+                                                    replacement = Some(s);
+                                                }
+                                                msg
                                             }
-                                            SuggestionType::Function(s) => format!("`{}`", s),
                                             SuggestionType::NotFound => "".to_string(),
                                         }
                                     }
-                                    Field => format!("`self.{}`", path_name),
+                                    Field => {
+                                        replacement = Some(format!("self.{}", path_name));
+                                        format!("`self.{}`", path_name)
+                                    }
                                     Method |
-                                    TraitItem => format!("to call `self.{}`", path_name),
+                                    TraitItem => {
+                                        replacement = Some(format!("self.{}", path_name));
+                                        format!("to call `self.{}`", path_name)
+                                    }
                                     TraitMethod(path_str) |
-                                    StaticMethod(path_str) =>
-                                        format!("to call `{}::{}`", path_str, path_name),
+                                    StaticMethod(path_str) => {
+                                        replacement = Some(format!("{}::{}",
+                                                                   path_str,
+                                                                   path_name));
+                                        format!("to call `{}::{}`", path_str, path_name)
+                                    }
                                 };
 
                                 if !msg.is_empty() {
@@ -3518,6 +3594,14 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                                 resolve_error(self,
                                               expr.span,
                                               ResolutionError::UnresolvedName(&*path_name, &*msg));
+
+                                if let Some(replacement) = replacement {
+                                    if self.emit_errors {
+                                        self.session.span_suggestion(expr.span,
+                                                                     "try this:",
+                                                                     replacement);
+                                    }
+                                }
                             }
                         }
                     }
@@ -3526,6 +3610,11 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                 intravisit::walk_expr(self, expr);
             }
 
+            ExprCall(ref callee, _) => {
+                self.callee_id = Some(callee.id);
+                intravisit::walk_expr(self, expr);
+            }
+
             ExprStruct(ref path, _, _) => {
                 // Resolve the path to the structure it goes to. We don't
                 // check to ensure that the path is actually a structure; that
@@ -0,0 +1,267 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reuse of codegen units across compilations (`-Z incremental`).
+//!
+//! Once the crate has been translated, `compute_graph` uses the dependency
+//! graph recorded in the `ty::ctxt` to collect, for every codegen unit, the
+//! local items which were translated into it along with a hash of each of
+//! them. Everything that can affect more than one codegen unit is folded
+//! into a single global hash: the command line, the upstream crates, and the
+//! *interface* of this crate, which is every item except the bodies of fns
+//! that are neither generic, `const` nor `#[inline]` (the only code which
+//! ends up in the codegen unit of its module and nowhere else).
+//!
+//! `back::write::run_passes` compares the result with the graph saved by the
+//! previous compilation in the `<crate>.incremental` directory next to the
+//! output. Codegen units whose inputs did not change are neither optimized
+//! nor code-generated again; the object file saved for them last time is
+//! used instead.
+//!
+//! This only works if the code generated for a codegen unit does not depend
+//! on how the rest of the crate was edited, so in incremental mode modules
+//! are assigned to codegen units by a hash of their def-path rather than by
+//! size, symbol names are derived from def-paths rather than from types and
+//! node ids, and symbols are never internalized.
+//!
+//! Type checking and MIR construction are recorded in the dependency graph
+//! as well, but their results are not reused yet: the analysis passes still
+//! run over the whole crate.
+
+use back::svh;
+use dep_graph::DepNode;
+use middle::cstore::CrateStore;
+use middle::ty;
+use rustc::front::map::def_path_str;
+use rustc_front::hir;
+use rustc_front::intravisit::Visitor;
+use serialize::json;
+use syntax::ast;
+use syntax::attr;
+use util::nodemap::NodeMap;
+
+use std::env;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher, SipHasher};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever the format of the saved graph changes.
+const GRAPH_VERSION: u32 = 1;
+
+const GRAPH_FILE: &'static str = "dep-graph.json";
+
+#[derive(Clone, PartialEq, Debug, RustcEncodable, RustcDecodable)]
+pub struct IncrementalGraph {
+    version: u32,
+    /// The hash of the inputs shared by all codegen units.
+    global_hash: u64,
+    /// The inputs of each codegen unit, sorted by path, or `None` if there is
+    /// no object file saved for it.
+    codegen_units: Vec<Option<Vec<CodegenUnitInput>>>,
+}
+
+#[derive(Clone, PartialEq, Debug, RustcEncodable, RustcDecodable)]
+struct CodegenUnitInput {
+    /// See `front::map::def_path_str`.
+    path: String,
+    hash: u64,
+}
+
+impl IncrementalGraph {
+    /// Whether the object file saved for `unit` by the compilation which
+    /// produced `previous` is still valid for this compilation.
+    pub fn can_reuse(&self, previous: &IncrementalGraph, unit: usize) -> bool {
+        self.global_hash == previous.global_hash &&
+        self.codegen_units.len() == previous.codegen_units.len() &&
+        previous.codegen_units[unit].is_some() &&
+        previous.codegen_units[unit] == self.codegen_units[unit]
+    }
+
+    /// Forgets the inputs of `unit`, so that it is rebuilt next time.
+    pub fn invalidate(&mut self, unit: usize) {
+        self.codegen_units[unit] = None;
+    }
+}
+
+/// The codegen unit the items of the module `id` are translated into.
+pub fn codegen_unit_for_module(tcx: &ty::ctxt, id: ast::NodeId, codegen_units: usize) -> usize {
+    let mut state = SipHasher::new();
+    def_path_str(&tcx.map.def_path_from_id(id)).hash(&mut state);
+    (state.finish() % codegen_units as u64) as usize
+}
+
+/// Collects the inputs of every codegen unit from the dependency graph of
+/// `tcx`, which must have been built with `-Z incremental`.
+pub fn compute_graph(tcx: &ty::ctxt, codegen_units: usize) -> IncrementalGraph {
+    let krate = tcx.map.krate();
+    let mut hasher = ItemHasher {
+        tcx: tcx,
+        item_hashes: NodeMap(),
+        interface: vec![],
+    };
+    krate.visit_all_items(&mut hasher);
+
+    let mut global = SipHasher::new();
+    option_env!("CFG_VERSION").hash(&mut global);
+    // Rather than trying to work out which options affect code generation,
+    // be conservative and rebuild everything if anything on the command line
+    // changed.
+    for arg in env::args() {
+        arg.hash(&mut global);
+    }
+    for attr in &krate.attrs {
+        attr.node.value.hash(&mut global);
+    }
+    let cstore = &tcx.sess.cstore;
+    let mut upstream = cstore.crates().into_iter().map(|cnum| {
+        (cstore.crate_name(cnum), cstore.crate_hash(cnum).as_str().to_string())
+    }).collect::<Vec<_>>();
+    upstream.sort();
+    upstream.hash(&mut global);
+    hasher.interface.sort();
+    hasher.interface.hash(&mut global);
+
+    let codegen_units = (0..codegen_units).map(|unit| {
+        let mut inputs = vec![];
+        for def_id in tcx.dep_graph.hir_inputs(DepNode::CodegenUnit(unit)) {
+            let id = match tcx.map.as_local_node_id(def_id) {
+                Some(id) => id,
+                None => continue,
+            };
+            // Methods are hashed as part of their impl or trait. Items which
+            // were inlined from other crates have no hash; they are covered
+            // by the hash of the crate they came from.
+            let (path, hash) = match hasher.item_hashes.get(&id).or_else(|| {
+                hasher.item_hashes.get(&tcx.map.get_parent(id))
+            }) {
+                Some(&(ref path, hash)) => (path.clone(), hash),
+                None => continue,
+            };
+            inputs.push(CodegenUnitInput { path: path, hash: hash });
+        }
+        inputs.sort_by(|a, b| a.path.cmp(&b.path));
+        inputs.dedup();
+        Some(inputs)
+    }).collect();
+
+    IncrementalGraph {
+        version: GRAPH_VERSION,
+        global_hash: global.finish(),
+        codegen_units: codegen_units,
+    }
+}
+
+struct ItemHasher<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    /// The def-path and full hash of every item in the crate.
+    item_hashes: NodeMap<(String, u64)>,
+    /// The def-path and the hash of the part of every item which may affect
+    /// more than one codegen unit.
+    interface: Vec<(String, u64)>,
+}
+
+impl<'a, 'tcx, 'v> Visitor<'v> for ItemHasher<'a, 'tcx> {
+    fn visit_item(&mut self, item: &hir::Item) {
+        let krate = self.tcx.map.krate();
+        let path = def_path_str(&self.tcx.map.def_path_from_id(item.id));
+
+        let mut state = SipHasher::new();
+        svh::hash_item(krate, item, true).hash(&mut state);
+        // The code generated for an item records where it came from (in its
+        // debuginfo and panic messages), so an item which merely moved
+        // within its file, or whose formatting changed, has to be rebuilt.
+        let codemap = self.tcx.sess.codemap();
+        let lo = codemap.lookup_char_pos(item.span.lo);
+        (&lo.file.name, lo.line, lo.col.0).hash(&mut state);
+        if let Ok(snippet) = codemap.span_to_snippet(item.span) {
+            snippet.hash(&mut state);
+        }
+        let hash = state.finish();
+
+        if is_local_to_codegen_unit(item) {
+            self.interface.push((path.clone(), svh::hash_item(krate, item, false)));
+        } else {
+            self.interface.push((path.clone(), hash));
+        }
+        self.item_hashes.insert(item.id, (path, hash));
+    }
+}
+
+/// Whether the code generated from the bodies of `item` only ever ends up
+/// in the codegen unit of its module, i.e. none of them can be
+/// monomorphized, inlined or evaluated at compile time elsewhere.
+fn is_local_to_codegen_unit(item: &hir::Item) -> bool {
+    match item.node {
+        hir::ItemFn(_, _, constness, _, ref generics, _) => {
+            constness == hir::Constness::NotConst &&
+            !generics.is_type_parameterized() &&
+            !attr::requests_inline(&item.attrs)
+        }
+        hir::ItemImpl(_, _, ref generics, _, _, ref impl_items) => {
+            !generics.is_type_parameterized() && impl_items.iter().all(|impl_item| {
+                match impl_item.node {
+                    hir::ImplItemKind::Method(ref sig, _) => {
+                        sig.constness == hir::Constness::NotConst &&
+                        !sig.generics.is_type_parameterized() &&
+                        !attr::requests_inline(&impl_item.attrs)
+                    }
+                    hir::ImplItemKind::Type(_) => true,
+                    hir::ImplItemKind::Const(..) => false,
+                }
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Where the object or bitcode (`ext`) of `unit` is kept between
+/// compilations.
+pub fn saved_file(dir: &Path, unit: usize, ext: &str) -> PathBuf {
+    dir.join(format!("cgu-{}.{}", unit, ext))
+}
+
+/// The graph saved by the previous compilation, if there was one and it was
+/// written by a compatible compiler.
+pub fn load_graph(dir: &Path) -> Option<IncrementalGraph> {
+    let mut contents = String::new();
+    if File::open(dir.join(GRAPH_FILE))
+            .and_then(|mut file| file.read_to_string(&mut contents))
+            .is_err() {
+        return None;
+    }
+    match json::decode::<IncrementalGraph>(&contents) {
+        Ok(graph) => {
+            if graph.version == GRAPH_VERSION {
+                Some(graph)
+            } else {
+                None
+            }
+        }
+        Err(e) => {
+            debug!("ignoring unreadable dependency graph in {:?}: {:?}", dir, e);
+            None
+        }
+    }
+}
+
+pub fn save_graph(dir: &Path, graph: &IncrementalGraph) -> io::Result<()> {
+    let mut file = try!(File::create(dir.join(GRAPH_FILE)));
+    write!(file, "{}", json::as_json(graph))
+}
+
+/// Removes the saved graph, so that nothing is reused while the saved object
+/// files are being replaced.
+pub fn remove_graph(dir: &Path) -> io::Result<()> {
+    match fs::remove_file(dir.join(GRAPH_FILE)) {
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
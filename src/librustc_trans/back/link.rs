@@ -22,9 +22,11 @@ use session::search_paths::PathKind;
 use session::Session;
 use middle::cstore::{self, CrateStore, LinkMeta};
 use middle::cstore::{LinkagePreference, NativeLibraryKind};
+use middle::def_id::DefId;
 use middle::dependency_format::Linkage;
+use middle::subst::Substs;
 use middle::ty::{self, Ty};
-use rustc::front::map::DefPath;
+use rustc::front::map::{def_path_str, DefPath};
 use trans::{CrateContext, CrateTranslation, gensym_name};
 use util::common::time;
 use util::sha2::{Digest, Sha256};
@@ -330,6 +332,11 @@ pub fn exported_name(path: DefPath, hash: &str) -> String {
 
 pub fn mangle_exported_name<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>, path: DefPath,
                                       t: Ty<'tcx>, id: ast::NodeId) -> String {
+    if ccx.sess().opts.debugging_opts.incremental {
+        let hash = incremental_symbol_hash(ccx, &path);
+        return exported_name(path, &hash);
+    }
+
     let mut hash = get_symbol_hash(ccx, t);
 
     // Paths can be completely identical for different nodes,
@@ -353,6 +360,54 @@ pub fn mangle_exported_name<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>, path: DefPat
     exported_name(path, &hash[..])
 }
 
+/// Under `-Z incremental`, codegen units reused from a previous compilation
+/// refer to the symbols of the ones which were rebuilt, so symbol names can't
+/// depend on the SVH, on type encodings (which contain def-ids) or on node
+/// ids, all of which change with unrelated edits. The def-path, including
+/// its disambiguators, is unique within the crate and stable instead.
+fn incremental_symbol_hash<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>, path: &DefPath) -> String {
+    let mut symbol_hasher = ccx.symbol_hasher().borrow_mut();
+    symbol_hasher.reset();
+    symbol_hasher.input_str(&ccx.link_meta().crate_name);
+    for meta in ccx.sess().crate_metadata.borrow().iter() {
+        symbol_hasher.input_str(&meta[..]);
+    }
+    symbol_hasher.input_str("-");
+    symbol_hasher.input_str(&def_path_str(path));
+    let mut hash = String::from("h");
+    hash.push_str(&truncated_hash_result(&mut *symbol_hasher));
+    hash
+}
+
+/// As `incremental_symbol_hash`, for the instantiation of the generic fn
+/// `def_id` with `substs`. The types in `substs` are hashed in their printed
+/// form, which names definitions by path rather than by def-id.
+pub fn incremental_mono_symbol_hash<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
+                                              def_id: DefId,
+                                              substs: &Substs<'tcx>)
+                                              -> String {
+    let tcx = ccx.tcx();
+    let mut symbol_hasher = ccx.symbol_hasher().borrow_mut();
+    symbol_hasher.reset();
+    symbol_hasher.input_str(&ccx.link_meta().crate_name);
+    for meta in ccx.sess().crate_metadata.borrow().iter() {
+        symbol_hasher.input_str(&meta[..]);
+    }
+    symbol_hasher.input_str("-");
+    if !def_id.is_local() {
+        symbol_hasher.input_str(&tcx.sess.cstore.crate_name(def_id.krate));
+        symbol_hasher.input_str("::");
+    }
+    symbol_hasher.input_str(&def_path_str(&tcx.def_path(def_id)));
+    for ty in substs.types.iter() {
+        symbol_hasher.input_str("<");
+        symbol_hasher.input_str(&ty.to_string());
+    }
+    let mut hash = String::from("h");
+    hash.push_str(&truncated_hash_result(&mut *symbol_hasher));
+    hash
+}
+
 pub fn mangle_internal_name_by_type_and_seq<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                                                       t: Ty<'tcx>,
                                                       name: &str) -> String {
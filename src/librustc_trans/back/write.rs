@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use back::incremental::{self, IncrementalGraph};
 use back::lto;
 use back::link::{get_linker, remove};
 use session::config::{OutputFilenames, Passes, SomePasses, AllPasses};
@@ -653,7 +654,39 @@ pub fn run_passes(sess: &Session,
         work_items.push(work);
     }
 
+    // Under `-Z incremental`, codegen units whose inputs did not change since
+    // the last compilation are copied from its outputs instead of being built
+    // again. That is only possible if objects and bitcode are all we need.
+    let incremental_dir = crate_output.with_extension("incremental");
+    let mut incremental_graph = if modules_config.emit_obj &&
+                                   !modules_config.emit_no_opt_bc &&
+                                   !modules_config.emit_lto_bc &&
+                                   !modules_config.emit_ir &&
+                                   !modules_config.emit_asm {
+        trans.incremental.clone()
+    } else {
+        None
+    };
+    let previous_graph = incremental_graph.as_ref().and_then(|_| {
+        incremental::load_graph(&incremental_dir)
+    });
+    let mut reused = vec![false; trans.modules.len()];
+
     for (index, mtrans) in trans.modules.iter().enumerate() {
+        if let (Some(graph), Some(previous)) = (incremental_graph.as_ref(),
+                                                previous_graph.as_ref()) {
+            if graph.can_reuse(previous, index) &&
+               restore_codegen_unit(&incremental_dir, index, &modules_config, crate_output) {
+                debug!("reusing codegen unit {}", index);
+                reused[index] = true;
+                unsafe {
+                    llvm::LLVMDisposeModule(mtrans.llmod);
+                    llvm::LLVMContextDispose(mtrans.llcx);
+                }
+                continue;
+            }
+        }
+
         let work = build_work_item(sess,
                                    *mtrans,
                                    modules_config.clone(),
@@ -669,6 +702,13 @@ pub fn run_passes(sess: &Session,
         run_work_multithreaded(sess, work_items, sess.opts.cg.codegen_units);
     }
 
+    if let Some(ref mut graph) = incremental_graph {
+        if !sess.has_errors() {
+            save_codegen_units(sess, &incremental_dir, graph, &reused,
+                               &modules_config, crate_output);
+        }
+    }
+
     // All codegen is finished.
     unsafe {
         llvm::LLVMRustDisposeTargetMachine(tm);
@@ -804,6 +844,72 @@ pub fn run_passes(sess: &Session,
     }
 }
 
+/// The extensions of the files produced for a codegen unit which are kept
+/// between incremental compilations.
+fn saved_extensions(config: &ModuleConfig) -> Vec<&'static str> {
+    let mut extensions = vec!["o"];
+    if config.emit_bc {
+        extensions.push("bc");
+    }
+    extensions
+}
+
+/// Copies the files saved for codegen unit `index` by a previous compilation
+/// to where this compilation would have produced them.
+fn restore_codegen_unit(dir: &Path,
+                        index: usize,
+                        config: &ModuleConfig,
+                        crate_output: &OutputFilenames)
+                        -> bool {
+    saved_extensions(config).iter().all(|ext| {
+        let saved = incremental::saved_file(dir, index, ext);
+        let output = crate_output.with_extension(&format!("{}.{}", index, ext));
+        fs::copy(&saved, &output).is_ok()
+    })
+}
+
+/// Saves the files produced for every codegen unit which was rebuilt, along
+/// with `graph`, for use by the next compilation.
+fn save_codegen_units(sess: &Session,
+                      dir: &Path,
+                      graph: &mut IncrementalGraph,
+                      reused: &[bool],
+                      config: &ModuleConfig,
+                      crate_output: &OutputFilenames) {
+    // Make sure that if we are interrupted, the saved graph can't describe
+    // objects which have already been replaced.
+    let result = incremental::remove_graph(dir).and_then(|()| fs::create_dir_all(dir));
+    if let Err(e) = result {
+        sess.warn(&format!("could not update {}: {}", dir.display(), e));
+        return;
+    }
+
+    let extensions = saved_extensions(config);
+    for (index, &was_reused) in reused.iter().enumerate() {
+        if was_reused {
+            continue;
+        }
+        for ext in &["o", "bc"] {
+            let saved = incremental::saved_file(dir, index, ext);
+            if extensions.contains(ext) {
+                let output = crate_output.with_extension(&format!("{}.{}", index, ext));
+                if fs::copy(&output, &saved).is_err() {
+                    graph.invalidate(index);
+                }
+            } else {
+                // Don't leave files from an older compilation around that
+                // don't match the inputs recorded for this one.
+                let _ = fs::remove_file(&saved);
+            }
+        }
+    }
+
+    if let Err(e) = incremental::save_graph(dir, graph) {
+        sess.warn(&format!("could not save the dependency graph to {}: {}",
+                           dir.display(), e));
+    }
+}
+
 struct WorkItem {
     mtrans: ModuleTranslation,
     config: ModuleConfig,
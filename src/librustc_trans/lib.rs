@@ -59,6 +59,7 @@ extern crate serialize;
 #[macro_use] extern crate log;
 #[macro_use] extern crate syntax;
 
+pub use rustc::dep_graph;
 pub use rustc::session;
 pub use rustc::middle;
 pub use rustc::lint;
@@ -70,6 +71,7 @@ pub mod back {
     pub use rustc_back::svh;
 
     pub mod archive;
+    pub mod incremental;
     pub mod linker;
     pub mod link;
     pub mod lto;
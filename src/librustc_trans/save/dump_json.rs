@@ -0,0 +1,617 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Output a JSON document containing the output from rustc's analysis
+//! (`-Z save-analysis-json`).
+//!
+//! Unlike `dump_csv`, which formats each row as it goes, this collects the
+//! `Data` produced by the `SaveContext` into a single `Analysis` and writes
+//! it out at the end. The document carries a version number, which is bumped
+//! whenever its format changes incompatibly.
+//!
+//! Every definition has an explicit `Id`, made from its `DefId` (or from its
+//! `NodeId` if it has none, adjusted so that the two cannot clash, as in
+//! `recorder::FmtStrs`). References, impls and the parents of definitions
+//! point at definitions by those ids. Ids of definitions in other crates are
+//! only meaningful within this document, so each one which is referred to is
+//! also listed in `external_defs` with the name and SVH of its crate and its
+//! def path, which identify it in any compilation, and its span.
+//!
+//! Code produced by macros is not analysed, but each macro invocation in the
+//! source is recorded along with the macro it invoked.
+
+use super::{generated_code, SaveContext, Data, FunctionData};
+
+use middle::def;
+use middle::cstore::LOCAL_CRATE;
+use middle::def_id::DefId;
+use middle::ty;
+
+use serialize::json;
+use std::collections::HashSet;
+use std::io::Write;
+
+use syntax::ast::{self, NodeId};
+use syntax::ast_util;
+use syntax::codemap::{CodeMap, Span, NO_EXPANSION};
+use syntax::visit::{self, Visitor};
+
+use rustc_front::lowering::{lower_expr, LoweringContext};
+
+/// Bumped whenever the format of the output changes.
+const VERSION: u32 = 1;
+
+pub struct DumpJsonVisitor<'l, 'tcx: 'l> {
+    save_ctxt: SaveContext<'l, 'tcx>,
+    tcx: &'l ty::ctxt<'tcx>,
+    result: Analysis,
+    /// The call sites of the macro invocations recorded so far.
+    macro_call_sites: HashSet<(u32, u32)>,
+    /// The definitions in other crates recorded so far.
+    external_def_ids: HashSet<DefId>,
+}
+
+impl<'l, 'tcx> DumpJsonVisitor<'l, 'tcx> {
+    pub fn new(tcx: &'l ty::ctxt<'tcx>,
+               lcx: &'l LoweringContext<'l>)
+               -> DumpJsonVisitor<'l, 'tcx> {
+        DumpJsonVisitor {
+            save_ctxt: SaveContext::new(tcx, lcx),
+            tcx: tcx,
+            result: Analysis {
+                version: VERSION,
+                krate: None,
+                external_crates: vec![],
+                external_defs: vec![],
+                defs: vec![],
+                refs: vec![],
+                impls: vec![],
+                macro_refs: vec![],
+            },
+            macro_call_sites: HashSet::new(),
+            external_def_ids: HashSet::new(),
+        }
+    }
+
+    pub fn dump_crate_info(&mut self, name: &str, krate: &ast::Crate) {
+        let source_file = self.tcx.sess.local_crate_source_file.as_ref();
+        self.result.krate = Some(CrateInfo {
+            name: name.to_owned(),
+            crate_root: source_file.map(|file| file.display().to_string()),
+            span: self.span_data(krate.span),
+        });
+        self.result.external_crates = self.save_ctxt.get_external_crates().into_iter().map(|c| {
+            ExternalCrate {
+                num: c.number,
+                name: c.name,
+                disambiguator: c.hash,
+            }
+        }).collect();
+    }
+
+    pub fn write(&self, mut out: Box<Write>) {
+        if let Err(e) = write!(out, "{}", json::as_json(&self.result)) {
+            self.tcx.sess.err(&format!("Error writing save-analysis output: {}", e));
+        }
+    }
+
+    fn span_data(&self, span: Span) -> SpanData {
+        SpanData::new(self.tcx.sess.codemap(), span)
+    }
+
+    // Emitted ids are used to cross-reference items across crates, see
+    // `FmtStrs::normalize_node_id`.
+    fn id_from_node_id(&self, id: NodeId) -> Id {
+        match self.tcx.map.opt_local_def_id(id) {
+            Some(def_id) => Id::from_def_id(def_id),
+            None => Id {
+                krate: 0,
+                index: id + self.tcx.map.num_local_def_ids() as u32,
+            },
+        }
+    }
+
+    /// The id of the definition `def_id` refers to, recording it in
+    /// `external_defs` if it is in another crate.
+    fn ref_id(&mut self, def_id: DefId) -> Id {
+        if def_id.krate != LOCAL_CRATE && self.external_def_ids.insert(def_id) {
+            let data = self.save_ctxt.get_external_def_data(def_id);
+            let def = ExternalDef {
+                id: Id::from_def_id(def_id),
+                crate_name: data.crate_name,
+                crate_disambiguator: data.crate_hash,
+                def_path: data.def_path,
+                span: data.span.map(|span| self.span_data(span)),
+            };
+            self.result.external_defs.push(def);
+        }
+        Id::from_def_id(def_id)
+    }
+
+    fn push_def(&mut self,
+                kind: &'static str,
+                id: NodeId,
+                span: Span,
+                name: String,
+                qualname: String,
+                value: String,
+                scope: Option<NodeId>) {
+        let def = Def {
+            kind: kind,
+            id: self.id_from_node_id(id),
+            span: self.span_data(span),
+            name: name,
+            qualname: qualname,
+            value: value,
+            parent: scope.map(|scope| self.id_from_node_id(scope)),
+            decl_id: None,
+        };
+        self.result.defs.push(def);
+    }
+
+    fn push_ref(&mut self, kind: &'static str, span: Span, ref_id: DefId) {
+        let reference = Ref {
+            kind: kind,
+            span: self.span_data(span),
+            ref_id: self.ref_id(ref_id),
+        };
+        self.result.refs.push(reference);
+    }
+
+    /// Records the references and definitions which save-analysis `data`
+    /// describes.
+    fn process_data(&mut self, data: Data) {
+        match data {
+            Data::FunctionData(data) => self.push_function("function", data),
+            Data::VariableData(data) => {
+                self.push_def("variable", data.id, data.span, data.name, data.qualname,
+                              data.type_value, Some(data.scope));
+            }
+            Data::ModData(data) => {
+                self.push_def("mod", data.id, data.span, data.name, data.qualname,
+                              data.filename, Some(data.scope));
+            }
+            Data::EnumData(data) => {
+                let name = self.span_name(data.span);
+                self.push_def("enum", data.id, data.span, name, data.qualname, data.value,
+                              Some(data.scope));
+            }
+            Data::StructData(data) => {
+                let name = self.span_name(data.span);
+                self.push_def("struct", data.id, data.span, name, data.qualname, data.value,
+                              Some(data.scope));
+            }
+            Data::TraitData(data) => {
+                let name = self.span_name(data.span);
+                self.push_def("trait", data.id, data.span, name, data.qualname, data.value,
+                              Some(data.scope));
+            }
+            Data::TypedefData(data) => {
+                let name = self.span_name(data.span);
+                self.push_def("type", data.id, data.span, name, data.qualname, data.value, None);
+            }
+            Data::ImplData(data) => {
+                // The references to the type and trait are recorded when
+                // the impl is walked.
+                let self_ref = data.self_ref.map(|data| self.ref_id(data.ref_id));
+                let trait_ref = data.trait_ref.map(|data| self.ref_id(data.ref_id));
+                let imp = Impl {
+                    id: self.id_from_node_id(data.id),
+                    span: self.span_data(data.span),
+                    self_ref: self_ref,
+                    trait_ref: trait_ref,
+                    parent: Some(self.id_from_node_id(data.scope)),
+                };
+                self.result.impls.push(imp);
+            }
+            Data::VariableRefData(data) => self.push_ref("variable", data.span, data.ref_id),
+            Data::TypeRefData(data) => self.push_ref("type", data.span, data.ref_id),
+            Data::ModRefData(data) => self.push_ref("mod", data.span, data.ref_id),
+            Data::FunctionCallData(data) => self.push_ref("function", data.span, data.ref_id),
+            Data::MethodCallData(data) => {
+                if let Some(ref_id) = data.ref_id.or(data.decl_id) {
+                    self.push_ref("method", data.span, ref_id);
+                }
+            }
+        }
+    }
+
+    fn push_function(&mut self, kind: &'static str, data: FunctionData) {
+        let name = data.qualname.rsplit("::").next().unwrap_or("").to_owned();
+        let decl_id = data.declaration.map(|decl_id| self.ref_id(decl_id));
+        let def = Def {
+            kind: kind,
+            id: self.id_from_node_id(data.id),
+            span: self.span_data(data.span),
+            name: name,
+            qualname: data.qualname,
+            value: String::new(),
+            parent: Some(self.id_from_node_id(data.scope)),
+            decl_id: decl_id,
+        };
+        self.result.defs.push(def);
+    }
+
+    fn span_name(&self, span: Span) -> String {
+        self.tcx.sess.codemap().span_to_snippet(span).unwrap_or(String::new())
+    }
+
+    /// Records the definition `path` (the path of node `id`) refers to,
+    /// unless it is only partially resolved (e.g. an associated type).
+    fn process_path(&mut self, id: NodeId, path: &ast::Path) {
+        let resolved = self.tcx.def_map.borrow().get(&id).map_or(false, |res| res.depth == 0);
+        if !resolved {
+            return;
+        }
+        if let Some(data) = self.save_ctxt.get_path_data(id, path) {
+            self.process_data(data);
+        }
+    }
+
+    fn process_method(&mut self, id: NodeId, name: ast::Name, span: Span) {
+        let data = self.save_ctxt.get_method_data(id, name, span);
+        self.push_function("method", data);
+    }
+
+    fn process_variant_data(&mut self, def: &ast::VariantData, parent: NodeId) {
+        for field in def.fields() {
+            if let Some(data) = self.save_ctxt.get_field_data(field, parent) {
+                self.push_def("field", data.id, data.span, data.name, data.qualname,
+                              data.type_value, Some(data.scope));
+            }
+        }
+    }
+
+    /// Records the invocation of the macro which produced `span`, if it is
+    /// written in the source, and tells whether `span` was produced by a
+    /// macro.
+    fn process_macro_use(&mut self, span: Span) -> bool {
+        if span.expn_id == NO_EXPANSION {
+            return false;
+        }
+        let tcx = self.tcx;
+        let cm = tcx.sess.codemap();
+        let mut expn_id = span.expn_id;
+        while let Some((call_site, name, callee_span)) = cm.with_expn_info(expn_id, |info| {
+            info.map(|info| (info.call_site, info.callee.name(), info.callee.span))
+        }) {
+            if call_site.expn_id != NO_EXPANSION {
+                expn_id = call_site.expn_id;
+                continue;
+            }
+            if self.macro_call_sites.insert((call_site.lo.0, call_site.hi.0)) {
+                let macro_ref = MacroRef {
+                    span: self.span_data(call_site),
+                    name: name.to_string(),
+                    callee_span: callee_span.map(|span| self.span_data(span)),
+                };
+                self.result.macro_refs.push(macro_ref);
+            }
+            break;
+        }
+        true
+    }
+}
+
+impl<'l, 'tcx, 'v> Visitor<'v> for DumpJsonVisitor<'l, 'tcx> {
+    fn visit_item(&mut self, item: &ast::Item) {
+        if self.process_macro_use(item.span) || generated_code(item.span) {
+            return;
+        }
+
+        match item.node {
+            ast::ItemFn(..) |
+            ast::ItemStatic(..) |
+            ast::ItemConst(..) |
+            ast::ItemMod(..) |
+            ast::ItemImpl(..) |
+            ast::ItemTrait(..) |
+            ast::ItemTy(..) => {
+                let data = self.save_ctxt.get_item_data(item);
+                self.process_data(data);
+            }
+            ast::ItemStruct(ref def, _) => {
+                let data = self.save_ctxt.get_item_data(item);
+                self.process_data(data);
+                self.process_variant_data(def, item.id);
+            }
+            ast::ItemEnum(ref enum_def, _) => {
+                let data = self.save_ctxt.get_item_data(item);
+                let qualname = match data {
+                    Data::EnumData(ref data) => data.qualname.clone(),
+                    _ => unreachable!(),
+                };
+                self.process_data(data);
+                for variant in &enum_def.variants {
+                    let name = variant.node.name.to_string();
+                    let sub_span = self.save_ctxt.span_utils.span_for_first_ident(variant.span);
+                    let variant_qualname = format!("{}::{}", qualname, name);
+                    let value = self.span_name(variant.span);
+                    self.push_def("variant",
+                                  variant.node.data.id(),
+                                  sub_span.unwrap_or(variant.span),
+                                  name,
+                                  variant_qualname,
+                                  value,
+                                  Some(item.id));
+                    self.process_variant_data(&variant.node.data, variant.node.data.id());
+                }
+            }
+            ast::ItemUse(ref use_item) => {
+                match use_item.node {
+                    ast::ViewPathSimple(_, ref path) => self.process_path(item.id, path),
+                    ast::ViewPathList(_, ref list) => {
+                        for plid in list {
+                            if let ast::PathListIdent { id, name, .. } = plid.node {
+                                let path = ast_util::ident_to_path(plid.span, name);
+                                self.process_path(id, &path);
+                            }
+                        }
+                    }
+                    ast::ViewPathGlob(_) => {}
+                }
+                return;
+            }
+            _ => {}
+        }
+        visit::walk_item(self, item);
+    }
+
+    fn visit_trait_item(&mut self, trait_item: &ast::TraitItem) {
+        if self.process_macro_use(trait_item.span) {
+            return;
+        }
+        if let ast::MethodTraitItem(..) = trait_item.node {
+            self.process_method(trait_item.id, trait_item.ident.name, trait_item.span);
+        }
+        visit::walk_trait_item(self, trait_item);
+    }
+
+    fn visit_impl_item(&mut self, impl_item: &ast::ImplItem) {
+        if self.process_macro_use(impl_item.span) {
+            return;
+        }
+        if let ast::ImplItemKind::Method(..) = impl_item.node {
+            self.process_method(impl_item.id, impl_item.ident.name, impl_item.span);
+        }
+        visit::walk_impl_item(self, impl_item);
+    }
+
+    fn visit_stmt(&mut self, s: &ast::Stmt) {
+        if self.process_macro_use(s.span) {
+            return;
+        }
+        visit::walk_stmt(self, s);
+    }
+
+    fn visit_expr(&mut self, ex: &ast::Expr) {
+        if self.process_macro_use(ex.span) {
+            return;
+        }
+
+        match ex.node {
+            ast::ExprPath(_, ref path) => self.process_path(ex.id, path),
+            ast::ExprField(..) |
+            ast::ExprMethodCall(..) => {
+                if let Some(data) = self.save_ctxt.get_expr_data(ex) {
+                    self.process_data(data);
+                }
+            }
+            ast::ExprStruct(_, ref fields, _) => {
+                if let Some(data) = self.save_ctxt.get_expr_data(ex) {
+                    self.process_data(data);
+                }
+                let hir_expr = lower_expr(self.save_ctxt.lcx, ex);
+                let adt = self.tcx.expr_ty(&hir_expr).ty_adt_def().unwrap();
+                let variant = adt.variant_of_def(self.tcx.resolve_expr(&hir_expr));
+                let scope = self.save_ctxt.enclosing_scope(ex.id);
+                for field in fields {
+                    if generated_code(field.ident.span) {
+                        continue;
+                    }
+                    let data = self.save_ctxt.get_field_ref_data(field, variant, scope);
+                    self.process_data(Data::VariableRefData(data));
+                }
+            }
+            _ => {}
+        }
+        visit::walk_expr(self, ex);
+    }
+
+    fn visit_ty(&mut self, t: &ast::Ty) {
+        if self.process_macro_use(t.span) {
+            return;
+        }
+        if let ast::TyPath(_, ref path) = t.node {
+            self.process_path(t.id, path);
+        }
+        visit::walk_ty(self, t);
+    }
+
+    fn visit_trait_ref(&mut self, trait_ref: &ast::TraitRef) {
+        // References do not record their scope.
+        if let Some(data) = self.save_ctxt.get_trait_ref_data(trait_ref, 0) {
+            self.process_data(Data::TypeRefData(data));
+        }
+        visit::walk_trait_ref(self, trait_ref);
+    }
+
+    fn visit_pat(&mut self, p: &ast::Pat) {
+        if self.process_macro_use(p.span) {
+            return;
+        }
+
+        match p.node {
+            ast::PatIdent(_, ref ident, _) => {
+                let def = self.tcx.def_map.borrow().get(&p.id).map(|res| res.full_def());
+                match def {
+                    // A new binding, rather than a reference to a constant
+                    // or a unit variant or struct.
+                    Some(def::DefLocal(_, id)) if id == p.id => {
+                        let name = ident.node.to_string();
+                        let qualname = format!("{}${}", name, p.id);
+                        let typ = self.tcx.node_types().get(&p.id).map(|t| t.to_string());
+                        let scope = self.save_ctxt.enclosing_scope(p.id);
+                        self.push_def("local", p.id, ident.span, name, qualname,
+                                      typ.unwrap_or(String::new()), Some(scope));
+                    }
+                    Some(_) => {
+                        let path = ast_util::ident_to_path(ident.span, ident.node);
+                        self.process_path(p.id, &path);
+                    }
+                    None => {}
+                }
+            }
+            ast::PatEnum(ref path, _) |
+            ast::PatStruct(ref path, _, _) |
+            ast::PatQPath(_, ref path) => self.process_path(p.id, path),
+            _ => {}
+        }
+        visit::walk_pat(self, p);
+    }
+
+    fn visit_mac(&mut self, _: &ast::Mac) {
+        // Only macro definitions are left after expansion; there is nothing
+        // to analyse in them.
+    }
+}
+
+// The following data types are provided just for serialisation.
+
+#[derive(RustcEncodable)]
+struct Analysis {
+    version: u32,
+    krate: Option<CrateInfo>,
+    external_crates: Vec<ExternalCrate>,
+    external_defs: Vec<ExternalDef>,
+    defs: Vec<Def>,
+    refs: Vec<Ref>,
+    impls: Vec<Impl>,
+    macro_refs: Vec<MacroRef>,
+}
+
+/// Identifies a definition: `krate` is 0 for the crate being analysed, or
+/// one of the `num`s of `external_crates`.
+#[derive(RustcEncodable, Clone, Copy)]
+struct Id {
+    krate: u32,
+    index: u32,
+}
+
+impl Id {
+    fn from_def_id(def_id: DefId) -> Id {
+        Id {
+            krate: def_id.krate,
+            index: def_id.index.as_u32(),
+        }
+    }
+}
+
+#[derive(RustcEncodable)]
+struct SpanData {
+    file_name: String,
+    byte_start: u32,
+    byte_end: u32,
+    /// 1-based.
+    line_start: usize,
+    line_end: usize,
+    /// 1-based, character offset.
+    column_start: usize,
+    column_end: usize,
+}
+
+impl SpanData {
+    fn new(cm: &CodeMap, span: Span) -> SpanData {
+        let start = cm.lookup_char_pos(span.lo);
+        let end = cm.lookup_char_pos(span.hi);
+        SpanData {
+            file_name: start.file.name.clone(),
+            byte_start: cm.lookup_byte_offset(span.lo).pos.0,
+            byte_end: cm.lookup_byte_offset(span.hi).pos.0,
+            line_start: start.line,
+            line_end: end.line,
+            column_start: start.col.0 + 1,
+            column_end: end.col.0 + 1,
+        }
+    }
+}
+
+#[derive(RustcEncodable)]
+struct CrateInfo {
+    name: String,
+    crate_root: Option<String>,
+    span: SpanData,
+}
+
+#[derive(RustcEncodable)]
+struct ExternalCrate {
+    num: u32,
+    name: String,
+    /// The SVH of the crate.
+    disambiguator: String,
+}
+
+#[derive(RustcEncodable)]
+struct ExternalDef {
+    id: Id,
+    crate_name: String,
+    /// The SVH of the crate.
+    crate_disambiguator: String,
+    /// The path of the definition within its crate, which does not change
+    /// between compilations.
+    def_path: String,
+    /// The span of the definition, in the source of its crate.
+    span: Option<SpanData>,
+}
+
+#[derive(RustcEncodable)]
+struct Def {
+    /// "function", "method", "variable", "local", "field", "mod", "enum",
+    /// "variant", "struct", "trait" or "type".
+    kind: &'static str,
+    id: Id,
+    /// The span of the name of the definition.
+    span: SpanData,
+    name: String,
+    qualname: String,
+    /// The type of variables and fields, the source of enums, structs and
+    /// traits, and the file of modules.
+    value: String,
+    parent: Option<Id>,
+    /// For methods, the trait method they implement, if any.
+    decl_id: Option<Id>,
+}
+
+#[derive(RustcEncodable)]
+struct Ref {
+    /// "function", "method", "variable", "type" or "mod".
+    kind: &'static str,
+    span: SpanData,
+    ref_id: Id,
+}
+
+#[derive(RustcEncodable)]
+struct Impl {
+    id: Id,
+    span: SpanData,
+    /// The type the impl is for, if it is a path.
+    self_ref: Option<Id>,
+    trait_ref: Option<Id>,
+    parent: Option<Id>,
+}
+
+#[derive(RustcEncodable)]
+struct MacroRef {
+    /// The invocation of the macro.
+    span: SpanData,
+    name: String,
+    /// The definition of the macro, if it has one in the source.
+    callee_span: Option<SpanData>,
+}
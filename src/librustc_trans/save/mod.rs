@@ -18,7 +18,7 @@ use std::path::{Path, PathBuf};
 
 use rustc_front;
 use rustc_front::{hir, lowering};
-use rustc::front::map::NodeItem;
+use rustc::front::map::{def_path_str, NodeItem};
 use rustc::session::config::CrateType::CrateTypeExecutable;
 
 use syntax::ast::{self, NodeId};
@@ -35,6 +35,7 @@ pub mod span_utils;
 pub mod recorder;
 
 mod dump_csv;
+mod dump_json;
 
 pub struct SaveContext<'l, 'tcx: 'l> {
     tcx: &'l ty::ctxt<'tcx>,
@@ -42,9 +43,34 @@ pub struct SaveContext<'l, 'tcx: 'l> {
     span_utils: SpanUtils<'l>,
 }
 
+/// The format of the save-analysis output.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// The CSV dialect read by DXR, see `dump_csv`.
+    Csv,
+    /// A versioned JSON document, see `dump_json`.
+    Json,
+}
+
 pub struct CrateData {
     pub name: String,
     pub number: u32,
+    /// The SVH of the crate, which tells apart crates with the same name.
+    pub hash: String,
+}
+
+/// Data for a definition in another crate, in terms which still mean
+/// something outside of this compilation.
+#[derive(Debug)]
+pub struct ExternalDefData {
+    pub def_id: DefId,
+    pub crate_name: String,
+    pub crate_hash: String,
+    /// The path of the definition within its crate, see
+    /// `front::map::def_path_str`.
+    pub def_path: String,
+    /// The span of the definition, if its crate recorded it.
+    pub span: Option<Span>,
 }
 
 /// Data for any entity in the Rust language. The actual data contained varied
@@ -61,6 +87,12 @@ pub enum Data {
     EnumData(EnumData),
     /// Data for impls.
     ImplData(ImplData),
+    /// Data for structs.
+    StructData(StructData),
+    /// Data for traits.
+    TraitData(TraitData),
+    /// Data for type aliases.
+    TypedefData(TypedefData),
 
     /// Data for the use of some variable (e.g., the use of a local variable, which
     /// will refere to that variables declaration).
@@ -131,6 +163,36 @@ pub struct ImplData {
     pub self_ref: Option<TypeRefData>,
 }
 
+/// Data for struct declarations.
+#[derive(Debug)]
+pub struct StructData {
+    pub id: NodeId,
+    pub ctor_id: NodeId,
+    pub qualname: String,
+    pub span: Span,
+    pub scope: NodeId,
+    pub value: String,
+}
+
+/// Data for trait declarations.
+#[derive(Debug)]
+pub struct TraitData {
+    pub id: NodeId,
+    pub qualname: String,
+    pub span: Span,
+    pub scope: NodeId,
+    pub value: String,
+}
+
+/// Data for type aliases.
+#[derive(Debug)]
+pub struct TypedefData {
+    pub id: NodeId,
+    pub qualname: String,
+    pub span: Span,
+    pub value: String,
+}
+
 /// Data for the use of some item (e.g., the use of a local variable, which
 /// will refer to that variables declaration (by ref_id)).
 #[derive(Debug)]
@@ -203,12 +265,24 @@ impl<'l, 'tcx: 'l> SaveContext<'l, 'tcx> {
             result.push(CrateData {
                 name: self.tcx.sess.cstore.crate_name(n),
                 number: n,
+                hash: self.tcx.sess.cstore.crate_hash(n).as_str().to_owned(),
             });
         }
 
         result
     }
 
+    pub fn get_external_def_data(&self, def_id: DefId) -> ExternalDefData {
+        let cstore = &self.tcx.sess.cstore;
+        ExternalDefData {
+            def_id: def_id,
+            crate_name: cstore.crate_name(def_id.krate),
+            crate_hash: cstore.crate_hash(def_id.krate).as_str().to_owned(),
+            def_path: def_path_str(&cstore.extern_def_path(def_id)),
+            span: cstore.def_span(self.tcx, def_id),
+        }
+    }
+
     pub fn get_item_data(&self, item: &ast::Item) -> Data {
         match item.node {
             ast::ItemFn(..) => {
@@ -326,6 +400,42 @@ impl<'l, 'tcx: 'l> SaveContext<'l, 'tcx> {
                     self_ref: type_data,
                 })
             }
+            ast::ItemStruct(ref def, _) => {
+                let qualname = format!("::{}", self.tcx.map.path_to_string(item.id));
+                let sub_span = self.span_utils.sub_span_after_keyword(item.span, keywords::Struct);
+
+                Data::StructData(StructData {
+                    id: item.id,
+                    ctor_id: def.id(),
+                    qualname: qualname,
+                    span: sub_span.unwrap(),
+                    scope: self.enclosing_scope(item.id),
+                    value: self.span_utils.snippet(item.span),
+                })
+            }
+            ast::ItemTrait(..) => {
+                let qualname = format!("::{}", self.tcx.map.path_to_string(item.id));
+                let sub_span = self.span_utils.sub_span_after_keyword(item.span, keywords::Trait);
+
+                Data::TraitData(TraitData {
+                    id: item.id,
+                    qualname: qualname,
+                    span: sub_span.unwrap(),
+                    scope: self.enclosing_scope(item.id),
+                    value: self.span_utils.snippet(item.span),
+                })
+            }
+            ast::ItemTy(ref ty, _) => {
+                let qualname = format!("::{}", self.tcx.map.path_to_string(item.id));
+                let sub_span = self.span_utils.sub_span_after_keyword(item.span, keywords::Type);
+
+                Data::TypedefData(TypedefData {
+                    id: item.id,
+                    qualname: qualname,
+                    span: sub_span.unwrap(),
+                    value: ty_to_string(&ty),
+                })
+            }
             _ => {
                 // FIXME
                 unimplemented!();
@@ -715,7 +825,8 @@ pub fn process_crate<'l, 'tcx>(tcx: &'l ty::ctxt<'tcx>,
                                krate: &ast::Crate,
                                analysis: &ty::CrateAnalysis,
                                cratename: &str,
-                               odir: Option<&Path>) {
+                               odir: Option<&Path>,
+                               format: Format) {
     if generated_code(krate.span) {
         return;
     }
@@ -753,7 +864,10 @@ pub fn process_crate<'l, 'tcx>(tcx: &'l ty::ctxt<'tcx>,
     };
     out_name.push_str(&cratename);
     out_name.push_str(&tcx.sess.opts.cg.extra_filename);
-    out_name.push_str(".csv");
+    out_name.push_str(match format {
+        Format::Csv => ".csv",
+        Format::Json => ".json",
+    });
     root_path.push(&out_name);
     let output_file = match File::create(&root_path) {
         Ok(f) => box f,
@@ -764,10 +878,21 @@ pub fn process_crate<'l, 'tcx>(tcx: &'l ty::ctxt<'tcx>,
     };
     root_path.pop();
 
-    let mut visitor = dump_csv::DumpCsvVisitor::new(tcx, lcx, analysis, output_file);
+    match format {
+        Format::Csv => {
+            let mut visitor = dump_csv::DumpCsvVisitor::new(tcx, lcx, analysis, output_file);
 
-    visitor.dump_crate_info(cratename, krate);
-    visit::walk_crate(&mut visitor, krate);
+            visitor.dump_crate_info(cratename, krate);
+            visit::walk_crate(&mut visitor, krate);
+        }
+        Format::Json => {
+            let mut visitor = dump_json::DumpJsonVisitor::new(tcx, lcx);
+
+            visitor.dump_crate_info(cratename, krate);
+            visit::walk_crate(&mut visitor, krate);
+            visitor.write(output_file);
+        }
+    }
 }
 
 // Utility functions for the module.
@@ -29,8 +29,10 @@ pub use self::ValueOrigin::*;
 use super::CrateTranslation;
 use super::ModuleTranslation;
 
+use back::incremental;
 use back::link::mangle_exported_name;
 use back::{link, abi};
+use dep_graph::DepNode;
 use lint;
 use llvm::{BasicBlockRef, Linkage, ValueRef, Vector, get_param};
 use llvm;
@@ -85,6 +87,7 @@ use trans::value::Value;
 use util::common::indenter;
 use util::sha2::Sha256;
 use util::nodemap::{NodeMap, NodeSet};
+use util::profiling;
 
 use arena::TypedArena;
 use libc::c_uint;
@@ -1506,6 +1509,7 @@ pub fn new_fn_ctxt<'a, 'tcx>(ccx: &'a CrateContext<'a, 'tcx>,
         llupvars: RefCell::new(NodeMap()),
         lldropflag_hints: RefCell::new(DropFlagHintsMap::new()),
         id: id,
+        check_overflow: ccx.tcx().check_overflow(id),
         param_substs: param_substs,
         span: sp,
         block_arena: block_arena,
@@ -1866,7 +1870,16 @@ pub fn trans_closure<'a, 'b, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                       &arena);
     let mut bcx = init_function(&fcx, false, output_type);
 
-    if attributes.iter().any(|item| item.check_name("rustc_mir")) {
+    // With -Z orbit, every fn we built MIR for goes through MIR trans;
+    // otherwise only those which ask for it, and, with -Z mir-inline, those
+    // into which the MIR inliner inlined calls.
+    let use_mir = if ccx.sess().opts.debugging_opts.orbit ||
+                     ccx.mir_inlined().contains(&fn_ast_id) {
+        fcx.mir.is_some()
+    } else {
+        attributes.iter().any(|item| item.check_name("rustc_mir"))
+    };
+    if use_mir {
         mir::trans_mir(bcx);
         fcx.cleanup();
         return;
@@ -2329,6 +2342,18 @@ pub fn trans_item(ccx: &CrateContext, item: &hir::Item) {
 
     let from_external = ccx.external_srcs().borrow().contains_key(&item.id);
 
+    let _task = if from_external {
+        None
+    } else {
+        let def_id = ccx.tcx().map.local_def_id(item.id);
+        let dep_graph = &ccx.tcx().dep_graph;
+        dep_graph.read(DepNode::TransCrateItem(def_id));
+        let task = dep_graph.in_task(DepNode::TransCrateItem(def_id));
+        dep_graph.read(DepNode::Hir(def_id));
+        task
+    };
+    let _profile = profiling::item("trans", || ccx.tcx().map.path_to_string(item.id));
+
     match item.node {
         hir::ItemFn(ref decl, _, _, abi, ref generics, ref body) => {
             if !generics.is_type_parameterized() {
@@ -2971,17 +2996,12 @@ pub fn filter_reachable_ids(ccx: &SharedCrateContext) -> NodeSet {
 
 pub fn trans_crate<'tcx>(tcx: &ty::ctxt<'tcx>,
                          mir_map: &MirMap<'tcx>,
+                         mir_inlined: &NodeSet,
                          analysis: ty::CrateAnalysis)
                          -> CrateTranslation {
     let ty::CrateAnalysis { export_map, reachable, name, .. } = analysis;
     let krate = tcx.map.krate();
 
-    let check_overflow = if let Some(v) = tcx.sess.opts.debugging_opts.force_overflow_checks {
-        v
-    } else {
-        tcx.sess.opts.debug_assertions
-    };
-
     let check_dropflag = if let Some(v) = tcx.sess.opts.debugging_opts.force_dropflag_checks {
         v
     } else {
@@ -3015,11 +3035,11 @@ pub fn trans_crate<'tcx>(tcx: &ty::ctxt<'tcx>,
                                              codegen_units,
                                              tcx,
                                              &mir_map,
+                                             mir_inlined,
                                              export_map,
                                              Sha256::new(),
                                              link_meta.clone(),
                                              reachable,
-                                             check_overflow,
                                              check_dropflag);
 
     {
@@ -3032,7 +3052,11 @@ pub fn trans_crate<'tcx>(tcx: &ty::ctxt<'tcx>,
         // details on why we walk in this particular way.
         {
             let _icx = push_ctxt("text");
-            intravisit::walk_mod(&mut TransItemsWithinModVisitor { ccx: &ccx }, &krate.module);
+            {
+                let _task = tcx.dep_graph.in_task(DepNode::CodegenUnit(ccx.codegen_unit()));
+                intravisit::walk_mod(&mut TransItemsWithinModVisitor { ccx: &ccx },
+                                     &krate.module);
+            }
             krate.visit_all_items(&mut TransModVisitor { ccx: &ccx });
         }
     }
@@ -3111,7 +3135,9 @@ pub fn trans_crate<'tcx>(tcx: &ty::ctxt<'tcx>,
         }
     }
 
-    if codegen_units > 1 {
+    // Incremental compilation can't internalize symbols: a codegen unit which
+    // is reused from a previous compilation may refer to any of them.
+    if codegen_units > 1 && !sess.opts.debugging_opts.incremental {
         internalize_symbols(&shared_ccx,
                             &reachable_symbols.iter().map(|x| &x[..]).collect());
     }
@@ -3127,6 +3153,12 @@ pub fn trans_crate<'tcx>(tcx: &ty::ctxt<'tcx>,
     };
     let no_builtins = attr::contains_name(&krate.attrs, "no_builtins");
 
+    let incremental = if sess.opts.debugging_opts.incremental {
+        Some(incremental::compute_graph(tcx, codegen_units))
+    } else {
+        None
+    };
+
     CrateTranslation {
         modules: modules,
         metadata_module: metadata_module,
@@ -3134,6 +3166,7 @@ pub fn trans_crate<'tcx>(tcx: &ty::ctxt<'tcx>,
         metadata: metadata,
         reachable: reachable_symbols,
         no_builtins: no_builtins,
+        incremental: incremental,
     }
 }
 
@@ -3154,7 +3187,19 @@ impl<'a, 'tcx, 'v> Visitor<'v> for TransModVisitor<'a, 'tcx> {
     fn visit_item(&mut self, i: &hir::Item) {
         match i.node {
             hir::ItemMod(_) => {
-                let item_ccx = self.ccx.rotate();
+                // Incremental compilation needs each module to end up in the
+                // same codegen unit every time.
+                let item_ccx = if self.ccx.sess().opts.debugging_opts.incremental {
+                    let codegen_units = self.ccx.sess().opts.cg.codegen_units;
+                    let unit = incremental::codegen_unit_for_module(self.ccx.tcx(),
+                                                                    i.id,
+                                                                    codegen_units);
+                    self.ccx.shared().get_ccx(unit)
+                } else {
+                    self.ccx.rotate()
+                };
+                let _task = item_ccx.tcx().dep_graph.in_task(
+                    DepNode::CodegenUnit(item_ccx.codegen_unit()));
                 intravisit::walk_item(&mut TransItemsWithinModVisitor { ccx: &item_ccx }, i);
             }
             _ => { }
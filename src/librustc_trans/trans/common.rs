@@ -389,6 +389,10 @@ pub struct FunctionContext<'a, 'tcx: 'a> {
     // a user-defined function.
     pub id: ast::NodeId,
 
+    // Whether integer arithmetic in this function panics on overflow, as
+    // decided by `#[overflow_checks]` or the session options.
+    pub check_overflow: bool,
+
     // If this function is being monomorphized, this contains the type
     // substitutions used.
     pub param_substs: &'tcx Substs<'tcx>,
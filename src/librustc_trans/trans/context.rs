@@ -69,9 +69,10 @@ pub struct SharedCrateContext<'a, 'tcx: 'a> {
     symbol_hasher: RefCell<Sha256>,
     tcx: &'a ty::ctxt<'tcx>,
     stats: Stats,
-    check_overflow: bool,
     check_drop_flag_for_sanity: bool,
     mir_map: &'a MirMap<'tcx>,
+    /// The fns into which the MIR inliner inlined calls.
+    mir_inlined: &'a NodeSet,
 
     available_drop_glues: RefCell<FnvHashMap<DropGlueKind<'tcx>, String>>,
     use_dll_storage_attrs: bool,
@@ -254,11 +255,11 @@ impl<'b, 'tcx> SharedCrateContext<'b, 'tcx> {
                local_count: usize,
                tcx: &'b ty::ctxt<'tcx>,
                mir_map: &'b MirMap<'tcx>,
+               mir_inlined: &'b NodeSet,
                export_map: ExportMap,
                symbol_hasher: Sha256,
                link_meta: LinkMeta,
                reachable: NodeSet,
-               check_overflow: bool,
                check_drop_flag_for_sanity: bool)
                -> SharedCrateContext<'b, 'tcx> {
         let (metadata_llcx, metadata_llmod) = unsafe {
@@ -321,6 +322,7 @@ impl<'b, 'tcx> SharedCrateContext<'b, 'tcx> {
             symbol_hasher: RefCell::new(symbol_hasher),
             tcx: tcx,
             mir_map: mir_map,
+            mir_inlined: mir_inlined,
             stats: Stats {
                 n_glues_created: Cell::new(0),
                 n_null_glues: Cell::new(0),
@@ -333,7 +335,6 @@ impl<'b, 'tcx> SharedCrateContext<'b, 'tcx> {
                 llvm_insns: RefCell::new(FnvHashMap()),
                 fn_stats: RefCell::new(Vec::new()),
             },
-            check_overflow: check_overflow,
             check_drop_flag_for_sanity: check_drop_flag_for_sanity,
             available_drop_glues: RefCell::new(FnvHashMap()),
             use_dll_storage_attrs: use_dll_storage_attrs,
@@ -536,6 +537,11 @@ impl<'b, 'tcx> CrateContext<'b, 'tcx> {
         self.shared.get_smallest_ccx()
     }
 
+    /// The index of the codegen unit this context translates into.
+    pub fn codegen_unit(&self) -> usize {
+        self.index
+    }
+
     /// Either iterate over only `self`, or iterate over all `CrateContext`s in
     /// the `SharedCrateContext`.  The iterator produces `(ccx, is_origin)`
     /// pairs, where `is_origin` is `true` if `ccx` is `self` and `false`
@@ -793,10 +799,6 @@ impl<'b, 'tcx> CrateContext<'b, 'tcx> {
         TypeOfDepthLock(self.local)
     }
 
-    pub fn check_overflow(&self) -> bool {
-        self.shared.check_overflow
-    }
-
     pub fn check_drop_flag_for_sanity(&self) -> bool {
         // This controls whether we emit a conditional llvm.debugtrap
         // guarded on whether the dropflag is one of its (two) valid
@@ -811,6 +813,10 @@ impl<'b, 'tcx> CrateContext<'b, 'tcx> {
     pub fn mir_map(&self) -> &'b MirMap<'tcx> {
         self.shared.mir_map
     }
+
+    pub fn mir_inlined(&self) -> &'b NodeSet {
+        self.shared.mir_inlined
+    }
 }
 
 pub struct TypeOfDepthLock<'a, 'tcx: 'a>(&'a LocalCrateContext<'tcx>);
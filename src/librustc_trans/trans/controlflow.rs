@@ -30,6 +30,7 @@ use rustc_front::hir;
 use rustc_front::util as ast_util;
 
 use syntax::ast;
+use syntax::codemap::Span;
 use syntax::parse::token::InternedString;
 use syntax::parse::token;
 
@@ -396,14 +397,7 @@ pub fn trans_fail<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
         return bcx;
     }
 
-    let v_str = C_str_slice(ccx, fail_str);
-    let loc = bcx.sess().codemap().lookup_char_pos(call_info.span.lo);
-    let filename = token::intern_and_get_ident(&loc.file.name);
-    let filename = C_str_slice(ccx, filename);
-    let line = C_u32(ccx, loc.line as u32);
-    let expr_file_line_const = C_struct(ccx, &[v_str, filename, line], false);
-    let align = machine::llalign_of_min(ccx, val_ty(expr_file_line_const));
-    let expr_file_line = consts::addr_of(ccx, expr_file_line_const, align, "panic_loc");
+    let expr_file_line = panic_loc(ccx, fail_str, call_info.span);
     let args = vec!(expr_file_line);
     let did = langcall(bcx, Some(call_info.span), "", PanicFnLangItem);
     let bcx = callee::trans_lang_call(bcx,
@@ -415,6 +409,19 @@ pub fn trans_fail<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
     return bcx;
 }
 
+/// The `&(&'static str, &'static str, u32)` holding the message, file
+/// and line which the `panic` lang item is called with.
+pub fn panic_loc(ccx: &CrateContext, fail_str: InternedString, span: Span) -> ValueRef {
+    let v_str = C_str_slice(ccx, fail_str);
+    let loc = ccx.sess().codemap().lookup_char_pos(span.lo);
+    let filename = token::intern_and_get_ident(&loc.file.name);
+    let filename = C_str_slice(ccx, filename);
+    let line = C_u32(ccx, loc.line as u32);
+    let expr_file_line_const = C_struct(ccx, &[v_str, filename, line], false);
+    let align = machine::llalign_of_min(ccx, val_ty(expr_file_line_const));
+    consts::addr_of(ccx, expr_file_line_const, align, "panic_loc")
+}
+
 pub fn trans_fail_bounds_check<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                                            call_info: NodeIdAndSpan,
                                            index: ValueRef,
@@ -427,16 +434,8 @@ pub fn trans_fail_bounds_check<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
         return bcx;
     }
 
-    // Extract the file/line from the span
-    let loc = bcx.sess().codemap().lookup_char_pos(call_info.span.lo);
-    let filename = token::intern_and_get_ident(&loc.file.name);
-
     // Invoke the lang item
-    let filename = C_str_slice(ccx,  filename);
-    let line = C_u32(ccx, loc.line as u32);
-    let file_line_const = C_struct(ccx, &[filename, line], false);
-    let align = machine::llalign_of_min(ccx, val_ty(file_line_const));
-    let file_line = consts::addr_of(ccx, file_line_const, align, "panic_bounds_check_loc");
+    let file_line = panic_bounds_check_loc(ccx, call_info.span);
     let args = vec!(file_line, index, len);
     let did = langcall(bcx, Some(call_info.span), "", PanicBoundsCheckFnLangItem);
     let bcx = callee::trans_lang_call(bcx,
@@ -447,3 +446,15 @@ pub fn trans_fail_bounds_check<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
     Unreachable(bcx);
     return bcx;
 }
+
+/// The `&(&'static str, u32)` holding the file and line which the
+/// `panic_bounds_check` lang item is called with.
+pub fn panic_bounds_check_loc(ccx: &CrateContext, span: Span) -> ValueRef {
+    let loc = ccx.sess().codemap().lookup_char_pos(span.lo);
+    let filename = token::intern_and_get_ident(&loc.file.name);
+    let filename = C_str_slice(ccx, filename);
+    let line = C_u32(ccx, loc.line as u32);
+    let file_line_const = C_struct(ccx, &[filename, line], false);
+    let align = machine::llalign_of_min(ccx, val_ty(file_line_const));
+    consts::addr_of(ccx, file_line_const, align, "panic_bounds_check_loc")
+}
@@ -1619,7 +1619,7 @@ fn trans_unary<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                 } else {
                     let is_signed = un_ty.is_signed();
                     let result = Neg(bcx, val, debug_loc);
-                    let bcx = if bcx.ccx().check_overflow() && is_signed {
+                    let bcx = if bcx.fcx.check_overflow && is_signed {
                         let (llty, min) = base::llty_and_min_for_signed_ty(bcx, un_ty);
                         let is_min = ICmp(bcx, llvm::IntEQ, val,
                                           C_integral(llty, min, true), debug_loc);
@@ -2010,50 +2010,50 @@ pub fn cast_is_noop<'tcx>(tcx: &ty::ctxt<'tcx>,
     }
 }
 
-fn trans_imm_cast<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
-                              expr: &hir::Expr,
-                              id: ast::NodeId)
-                              -> DatumBlock<'blk, 'tcx, Expr>
-{
-    use middle::ty::cast::CastTy::*;
-    use middle::ty::cast::IntTy::*;
-
-    fn int_cast(bcx: Block,
+pub fn int_cast(bcx: Block,
                 lldsttype: Type,
                 llsrctype: Type,
                 llsrc: ValueRef,
                 signed: bool)
                 -> ValueRef
-    {
-        let _icx = push_ctxt("int_cast");
-        let srcsz = llsrctype.int_width();
-        let dstsz = lldsttype.int_width();
-        return if dstsz == srcsz {
-            BitCast(bcx, llsrc, lldsttype)
-        } else if srcsz > dstsz {
-            TruncOrBitCast(bcx, llsrc, lldsttype)
-        } else if signed {
-            SExtOrBitCast(bcx, llsrc, lldsttype)
-        } else {
-            ZExtOrBitCast(bcx, llsrc, lldsttype)
-        }
+{
+    let _icx = push_ctxt("int_cast");
+    let srcsz = llsrctype.int_width();
+    let dstsz = lldsttype.int_width();
+    return if dstsz == srcsz {
+        BitCast(bcx, llsrc, lldsttype)
+    } else if srcsz > dstsz {
+        TruncOrBitCast(bcx, llsrc, lldsttype)
+    } else if signed {
+        SExtOrBitCast(bcx, llsrc, lldsttype)
+    } else {
+        ZExtOrBitCast(bcx, llsrc, lldsttype)
     }
+}
 
-    fn float_cast(bcx: Block,
+pub fn float_cast(bcx: Block,
                   lldsttype: Type,
                   llsrctype: Type,
                   llsrc: ValueRef)
                   -> ValueRef
-    {
-        let _icx = push_ctxt("float_cast");
-        let srcsz = llsrctype.float_width();
-        let dstsz = lldsttype.float_width();
-        return if dstsz > srcsz {
-            FPExt(bcx, llsrc, lldsttype)
-        } else if srcsz > dstsz {
-            FPTrunc(bcx, llsrc, lldsttype)
-        } else { llsrc };
-    }
+{
+    let _icx = push_ctxt("float_cast");
+    let srcsz = llsrctype.float_width();
+    let dstsz = lldsttype.float_width();
+    return if dstsz > srcsz {
+        FPExt(bcx, llsrc, lldsttype)
+    } else if srcsz > dstsz {
+        FPTrunc(bcx, llsrc, lldsttype)
+    } else { llsrc };
+}
+
+fn trans_imm_cast<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
+                              expr: &hir::Expr,
+                              id: ast::NodeId)
+                              -> DatumBlock<'blk, 'tcx, Expr>
+{
+    use middle::ty::cast::CastTy::*;
+    use middle::ty::cast::IntTy::*;
 
     let _icx = push_ctxt("trans_cast");
     let mut bcx = bcx;
@@ -2505,7 +2505,7 @@ fn with_overflow_check<'blk, 'tcx>(bcx: Block<'blk, 'tcx>, oop: OverflowOp, info
                                    binop_debug_loc: DebugLoc)
                                    -> (Block<'blk, 'tcx>, ValueRef) {
     if bcx.unreachable.get() { return (bcx, _Undef(lhs)); }
-    if bcx.ccx().check_overflow() {
+    if bcx.fcx.check_overflow {
 
         match oop.codegen_strategy() {
             OverflowCodegen::ViaIntrinsic(oop) =>
@@ -148,23 +148,43 @@ pub fn trans_static_method_callee<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                                             -> Datum<'tcx, Rvalue>
 {
     let _icx = push_ctxt("meth::trans_static_method_callee");
-    let tcx = ccx.tcx();
 
     debug!("trans_static_method_callee(method_id={:?}, trait_id={}, \
             expr_id={})",
            method_id,
-           tcx.item_path_str(trait_id),
+           ccx.tcx().item_path_str(trait_id),
            expr_id);
 
-    let mname = tcx.item_name(method_id);
-
-    debug!("trans_static_method_callee: method_id={:?}, expr_id={}, \
-            name={}", method_id, expr_id, mname);
-
     // Find the substitutions for the fn itself. This includes
     // type parameters that belong to the trait but also some that
     // belong to the method:
     let rcvr_substs = node_id_substs(ccx, ExprId(expr_id), param_substs);
+    trans_static_method_callee_with_substs(ccx,
+                                           method_id,
+                                           trait_id,
+                                           ExprId(expr_id),
+                                           rcvr_substs,
+                                           param_substs)
+}
+
+/// Like `trans_static_method_callee`, but for a reference whose
+/// (monomorphized) substitutions are already known, such as one from MIR.
+pub fn trans_static_method_callee_with_substs<'a, 'tcx>(
+    ccx: &CrateContext<'a, 'tcx>,
+    method_id: DefId,
+    trait_id: DefId,
+    node: ExprOrMethodCall,
+    rcvr_substs: subst::Substs<'tcx>,
+    param_substs: &'tcx subst::Substs<'tcx>)
+    -> Datum<'tcx, Rvalue>
+{
+    let tcx = ccx.tcx();
+
+    let mname = tcx.item_name(method_id);
+
+    debug!("trans_static_method_callee_with_substs: method_id={:?}, node={:?}, \
+            name={}", method_id, node, mname);
+
     let subst::SeparateVecsPerParamSpace {
         types: rcvr_type,
         selfs: rcvr_self,
@@ -240,7 +260,7 @@ pub fn trans_static_method_callee<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                                                      rcvr_method));
 
             let mth = tcx.get_impl_method(impl_did, callee_substs, mname);
-            trans_fn_ref_with_substs(ccx, mth.method.def_id, ExprId(expr_id),
+            trans_fn_ref_with_substs(ccx, mth.method.def_id, node,
                                      param_substs,
                                      mth.substs)
         }
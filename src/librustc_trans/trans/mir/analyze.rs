@@ -52,11 +52,12 @@ pub fn lvalue_temps<'bcx,'tcx>(bcx: Block<'bcx,'tcx>,
 
 struct TempAnalyzer {
     lvalue_temps: FnvHashSet<usize>,
+    assigned_temps: FnvHashSet<usize>,
 }
 
 impl TempAnalyzer {
     fn new() -> TempAnalyzer {
-        TempAnalyzer { lvalue_temps: FnvHashSet() }
+        TempAnalyzer { lvalue_temps: FnvHashSet(), assigned_temps: FnvHashSet() }
     }
 
     fn mark_as_lvalue(&mut self, temp: usize) {
@@ -74,7 +75,11 @@ impl<'tcx> Visitor<'tcx> for TempAnalyzer {
 
         match *lvalue {
             mir::Lvalue::Temp(index) => {
-                if !rvalue::rvalue_creates_operand(rvalue) {
+                // An operand temp holds a single value, so a temp which is
+                // assigned more than once (e.g. in both arms of an `if`)
+                // needs an alloca.
+                if !self.assigned_temps.insert(index as usize) ||
+                   !rvalue::rvalue_creates_operand(rvalue) {
                     self.mark_as_lvalue(index as usize);
                 }
             }
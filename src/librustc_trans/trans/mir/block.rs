@@ -8,14 +8,31 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use llvm::BasicBlockRef;
+use llvm::{BasicBlockRef, ValueRef};
+use middle::infer;
+use middle::lang_items::{PanicFnLangItem, PanicBoundsCheckFnLangItem};
+use middle::subst::{FnSpace, Substs};
+use middle::ty::{self, Ty};
 use rustc::mir::repr as mir;
+use syntax::abi::Abi;
+use syntax::codemap::{Span, DUMMY_SP};
+use trans::adt;
+use trans::attributes;
 use trans::base;
 use trans::build;
-use trans::common::Block;
+use trans::callee;
+use trans::common::{self, Block, ExprId, NodeIdAndSpan};
+use trans::controlflow;
 use trans::debuginfo::DebugLoc;
+use trans::expr;
+use trans::foreign;
+use trans::inline;
+use trans::intrinsic;
+use trans::type_::Type;
+use trans::type_of;
 
 use super::MirContext;
+use super::operand::{OperandRef, OperandValue};
 
 impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
     pub fn trans_block(&mut self, bb: mir::BasicBlock) {
@@ -35,8 +52,8 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                 build::Br(bcx, self.llblock(target), DebugLoc::None)
             }
 
-            mir::Terminator::Panic { .. } => {
-                unimplemented!()
+            mir::Terminator::Panic { ref msg, span, target } => {
+                self.trans_panic(bcx, msg, span, target);
             }
 
             mir::Terminator::If { ref cond, targets: (true_bb, false_bb) } => {
@@ -46,8 +63,26 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                 build::CondBr(bcx, cond.immediate(), lltrue, llfalse, DebugLoc::None);
             }
 
-            mir::Terminator::Switch { .. } => {
-                unimplemented!()
+            mir::Terminator::Switch { ref discr, ref adt_def, ref targets } => {
+                if targets.len() == 1 {
+                    build::Br(bcx, self.llblock(targets[0]), DebugLoc::None);
+                    return;
+                }
+                let discr_lvalue = self.trans_lvalue(bcx, discr);
+                let ty = discr_lvalue.ty.to_ty(bcx.tcx());
+                let repr = adt::represent_type(bcx.ccx(), ty);
+                let discr = adt::trans_get_discr(bcx, &repr, discr_lvalue.llval, None);
+
+                // The otherwise branch can never be taken, as the switch
+                // has a target for every variant.
+                let unreachable = self.unreachable_block().llbb;
+                let switch = build::Switch(bcx, discr, unreachable, targets.len());
+                assert_eq!(adt_def.variants.len(), targets.len());
+                for (variant, target) in adt_def.variants.iter().zip(targets) {
+                    let llval = adt::trans_case(bcx, &repr, variant.disr_val);
+                    let llbb = self.llblock(*target);
+                    build::AddCase(switch, llval, llbb)
+                }
             }
 
             mir::Terminator::SwitchInt { ref discr, switch_ty, ref values, ref targets } => {
@@ -80,29 +115,306 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                 base::build_return_block(bcx.fcx, bcx, return_ty, DebugLoc::None);
             }
 
-            mir::Terminator::Call { .. } => {
-                unimplemented!()
-                //let llbb = unimplemented!(); // self.make_landing_pad(panic_bb);
-                //
-                //let tr_dest = self.trans_lvalue(bcx, &data.destination);
-                //
-                //// Create the callee. This will always be a fn
-                //// ptr and hence a kind of scalar.
-                //let callee = self.trans_operand(bcx, &data.func);
-                //
-                //// Process the arguments.
-                //
-                //let args = unimplemented!();
-                //
-                //callee::trans_call_inner(bcx,
-                //                         DebugLoc::None,
-                //                         |bcx, _| Callee {
-                //                             bcx: bcx,
-                //                             data: CalleeData::Fn(callee.llval),
-                //                             ty: callee.ty,
-                //                         },
-                //                         args,
-                //                         Some(Dest::SaveIn(tr_dest.llval)));
+            mir::Terminator::Call { ref data, targets: (success, cleanup) } => {
+                self.trans_call(bcx, data, success, cleanup);
+            }
+        }
+    }
+
+    fn trans_call(&mut self,
+                  bcx: Block<'bcx, 'tcx>,
+                  data: &mir::CallData<'tcx>,
+                  success: mir::BasicBlock,
+                  cleanup: mir::BasicBlock) {
+        let ccx = bcx.ccx();
+
+        // The location we'll write the result of the call into.
+        let call_dest = self.trans_lvalue(bcx, &data.destination);
+        let dest_ty = call_dest.ty.to_ty(bcx.tcx());
+
+        // Tuple struct and enum variant constructors just build their
+        // value in place.
+        if let Some(disr) = constructor_disr(&data.func, dest_ty) {
+            let bcx = self.trans_adt_into(bcx, call_dest.llval, dest_ty, disr, &data.args);
+            build::Br(bcx, self.llblock(success), DebugLoc::None);
+            return;
+        }
+
+        // Create the callee. This will always be a fn ptr and hence a
+        // kind of scalar.
+        let callee = self.trans_operand(bcx, &data.func);
+        let (abi, ret_ty) = match callee.ty.sty {
+            ty::TyBareFn(_, ref f) => {
+                let sig = bcx.tcx().erase_late_bound_regions(&f.sig);
+                let sig = infer::normalize_associated_type(bcx.tcx(), &sig);
+                (f.abi, sig.output)
+            }
+            _ => bcx.sess().bug(&format!("call to non-fn {:?}", callee.ty)),
+        };
+
+        if abi == Abi::RustIntrinsic || abi == Abi::PlatformIntrinsic {
+            let bcx = self.trans_intrinsic_call(bcx, &data.func, callee.ty,
+                                                call_dest.llval, &data.args);
+            build::Br(bcx, self.llblock(success), DebugLoc::None);
+            return;
+        }
+
+        let mut llargs = Vec::with_capacity(data.args.len() + 1);
+
+        let is_rust_fn = abi == Abi::Rust || abi == Abi::RustCall;
+        if let ty::FnConverging(ret_ty) = ret_ty {
+            if is_rust_fn && type_of::return_uses_outptr(ccx, ret_ty) {
+                let llformal_ret_ty = type_of::type_of(ccx, ret_ty).ptr_to();
+                llargs.push(build::PointerCast(bcx, call_dest.llval, llformal_ret_ty));
+            }
+        }
+
+        // Process the arguments. A "rust-call" fn takes the fields of its
+        // last argument, which is a tuple, as separate arguments.
+        let (untupled, args) = match data.args.split_last() {
+            Some((last, args)) if abi == Abi::RustCall => (Some(last), args),
+            _ => (None, &data.args[..]),
+        };
+        let mut arg_tys = Vec::with_capacity(data.args.len());
+        for arg in args {
+            let arg = self.trans_operand(bcx, arg);
+            arg_tys.push(arg.ty);
+            self.trans_argument(bcx, arg, &mut llargs);
+        }
+        if let Some(tuple) = untupled {
+            let tuple = self.trans_operand(bcx, tuple);
+            let lltuple = self.spill_operand(bcx, tuple);
+            let field_tys = match tuple.ty.sty {
+                ty::TyTuple(ref tys) => tys,
+                _ => bcx.sess().bug(&format!("rust-call argument {:?} is not a tuple",
+                                             tuple.ty)),
+            };
+            for (i, &field_ty) in field_tys.iter().enumerate() {
+                let llfield = build::GEPi(bcx, lltuple, &[0, i]);
+                let field = self.trans_load(bcx, llfield, field_ty);
+                self.trans_argument(bcx, field, &mut llargs);
+            }
+        }
+
+        let llfn = callee.immediate();
+
+        if !is_rust_fn {
+            // Foreign fns don't unwind, so there is nothing to clean up.
+            let bcx = foreign::trans_native_call(bcx,
+                                                 callee.ty,
+                                                 llfn,
+                                                 call_dest.llval,
+                                                 &llargs[..],
+                                                 arg_tys,
+                                                 DebugLoc::None);
+            build::Br(bcx, self.llblock(success), DebugLoc::None);
+            return;
+        }
+
+        let (llret, bcx) = self.invoke(bcx, llfn, &llargs, callee.ty, cleanup);
+        match ret_ty {
+            ty::FnConverging(ret_ty) => {
+                if !type_of::return_uses_outptr(ccx, ret_ty) &&
+                   !common::type_is_zero_size(ccx, ret_ty) {
+                    base::store_ty(bcx, llret, call_dest.llval, ret_ty);
+                }
+                build::Br(bcx, self.llblock(success), DebugLoc::None);
+            }
+            ty::FnDiverging => build::Unreachable(bcx),
+        }
+    }
+
+    /// Calls an intrinsic, writing its result into `lldest`. Intrinsics
+    /// cannot unwind, so there is no cleanup to do.
+    fn trans_intrinsic_call(&mut self,
+                            bcx: Block<'bcx, 'tcx>,
+                            func: &mir::Operand<'tcx>,
+                            callee_ty: Ty<'tcx>,
+                            lldest: ValueRef,
+                            args: &[mir::Operand<'tcx>])
+                            -> Block<'bcx, 'tcx> {
+        let ccx = bcx.ccx();
+        let tcx = bcx.tcx();
+        let (def_id, substs) = match *func {
+            mir::Operand::Constant(mir::Constant {
+                literal: mir::Literal::Item { def_id, substs }, ..
+            }) => (def_id, bcx.monomorphize(substs)),
+            _ => tcx.sess.bug("intrinsic called through a fn pointer"),
+        };
+
+        // These two are only a store at the LLVM level; trans_intrinsic_call
+        // expects their arguments as expressions.
+        match &*tcx.item_name(def_id).as_str() {
+            "transmute" => {
+                let in_ty = *substs.types.get(FnSpace, 0);
+                let value = self.trans_operand(bcx, &args[0]);
+                let llintype = type_of::type_of(ccx, in_ty).ptr_to();
+                self.store_operand(bcx, build::PointerCast(bcx, lldest, llintype), value);
+                return bcx;
+            }
+            "move_val_init" => {
+                let dest = self.trans_operand(bcx, &args[0]);
+                let value = self.trans_operand(bcx, &args[1]);
+                self.store_operand(bcx, dest.immediate(), value);
+                return bcx;
+            }
+            _ => {}
+        }
+
+        let mut llargs = Vec::with_capacity(args.len());
+        for arg in args {
+            let arg = self.trans_operand(bcx, arg);
+            self.trans_argument(bcx, arg, &mut llargs);
+        }
+
+        let call_info = NodeIdAndSpan {
+            id: bcx.fcx.id,
+            span: bcx.fcx.span.unwrap_or(DUMMY_SP),
+        };
+        // Intrinsics from other crates are only known by the foreign item
+        // declaring them, which has to be inlined into this crate.
+        let node_id = match tcx.map.as_local_node_id(inline::maybe_instantiate_inline(ccx,
+                                                                                      def_id)) {
+            Some(node_id) => node_id,
+            None => {
+                tcx.sess.span_err(call_info.span,
+                                  &format!("could not find the declaration of intrinsic `{}`",
+                                           tcx.item_path_str(def_id)));
+                return bcx;
+            }
+        };
+        let scope = bcx.fcx.push_custom_cleanup_scope();
+        intrinsic::trans_intrinsic_call(bcx,
+                                        node_id,
+                                        callee_ty,
+                                        scope,
+                                        callee::ArgVals(&llargs),
+                                        expr::SaveIn(lldest),
+                                        substs,
+                                        call_info).bcx
+    }
+
+    /// Calls the `panic` lang item with `msg`, or `panic_bounds_check` for
+    /// a failed bounds check, unwinding to `cleanup`.
+    fn trans_panic(&mut self,
+                   bcx: Block<'bcx, 'tcx>,
+                   msg: &mir::PanicMessage<'tcx>,
+                   span: Span,
+                   cleanup: mir::BasicBlock) {
+        let ccx = bcx.ccx();
+        let (did, llargs) = match *msg {
+            mir::PanicMessage::BoundsCheck { ref len, ref index } => {
+                let len = self.trans_operand(bcx, len).immediate();
+                let index = self.trans_operand(bcx, index).immediate();
+                let did = common::langcall(bcx, Some(span), "", PanicBoundsCheckFnLangItem);
+                (did, vec![controlflow::panic_bounds_check_loc(ccx, span), index, len])
+            }
+            mir::PanicMessage::Message(ref msg) => {
+                let did = common::langcall(bcx, Some(span), "", PanicFnLangItem);
+                (did, vec![controlflow::panic_loc(ccx, msg.clone(), span)])
+            }
+        };
+        let callee = callee::trans_fn_ref_with_substs(ccx,
+                                                      did,
+                                                      ExprId(0),
+                                                      bcx.fcx.param_substs,
+                                                      Substs::trans_empty());
+        let (_, bcx) = self.invoke(bcx, callee.val, &llargs, callee.ty, cleanup);
+        build::Unreachable(bcx);
+    }
+
+    /// Calls `llfn`, branching to `cleanup` if it unwinds. Returns the
+    /// result and the block in which the call has returned normally.
+    fn invoke(&mut self,
+              bcx: Block<'bcx, 'tcx>,
+              llfn: ValueRef,
+              llargs: &[ValueRef],
+              fn_ty: Ty<'tcx>,
+              cleanup: mir::BasicBlock)
+              -> (ValueRef, Block<'bcx, 'tcx>) {
+        let attributes = attributes::from_fn_type(bcx.ccx(), fn_ty);
+        // See base::need_invoke for why we never invoke with SEH.
+        if bcx.sess().no_landing_pads() || base::wants_msvc_seh(bcx.sess()) {
+            let llret = build::Call(bcx, llfn, llargs, Some(attributes), DebugLoc::None);
+            return (llret, bcx);
+        }
+        let normal_bcx = bcx.fcx.new_block(false, "normal-return", None);
+        let landing_pad = self.make_landing_pad(cleanup);
+        let llret = build::Invoke(bcx,
+                                  llfn,
+                                  llargs,
+                                  normal_bcx.llbb,
+                                  landing_pad,
+                                  Some(attributes),
+                                  DebugLoc::None);
+        (llret, normal_bcx)
+    }
+
+    /// Creates a landing pad which saves the in-flight exception in the
+    /// personality slot and branches to `cleanup`, which is expected to
+    /// end in `Diverge` and so resume unwinding with it.
+    fn make_landing_pad(&mut self, cleanup: mir::BasicBlock) -> BasicBlockRef {
+        let pad_bcx = self.bcx(cleanup).fcx.new_block(true, "cleanup", None);
+        let ccx = pad_bcx.ccx();
+        let llpersonality = pad_bcx.fcx.eh_personality();
+        let llretty = Type::struct_(ccx, &[Type::i8p(ccx), Type::i32(ccx)], false);
+        let llretval = build::LandingPad(pad_bcx, llretty, llpersonality, 1);
+        build::SetCleanup(pad_bcx, llretval);
+        let slot = match self.llpersonalityslot {
+            Some(slot) => slot,
+            None => {
+                let slot = base::alloca(pad_bcx, llretty, "personalityslot");
+                self.llpersonalityslot = Some(slot);
+                slot
+            }
+        };
+        build::Store(pad_bcx, llretval, slot);
+        build::Br(pad_bcx, self.llblock(cleanup), DebugLoc::None);
+        pad_bcx.llbb
+    }
+
+    fn unreachable_block(&mut self) -> Block<'bcx, 'tcx> {
+        match self.unreachable_block {
+            Some(bcx) => bcx,
+            None => {
+                let bcx = self.bcx(mir::START_BLOCK).fcx.new_block(false, "unreachable", None);
+                build::Unreachable(bcx);
+                self.unreachable_block = Some(bcx);
+                bcx
+            }
+        }
+    }
+
+    /// Pushes the LLVM argument(s) passing `arg` to a Rust fn. Values
+    /// passed by reference are copied first, as the callee may modify
+    /// them in place.
+    fn trans_argument(&mut self,
+                      bcx: Block<'bcx, 'tcx>,
+                      arg: OperandRef<'tcx>,
+                      llargs: &mut Vec<ValueRef>) {
+        match arg.val {
+            OperandValue::Ref(llval) => {
+                let llcopy = base::alloc_ty(bcx, arg.ty, "__arg");
+                base::memcpy_ty(bcx, llcopy, llval, arg.ty);
+                llargs.push(llcopy);
+            }
+            OperandValue::Immediate(llval) => llargs.push(llval),
+            OperandValue::FatPtr(lldata, llextra) => {
+                llargs.push(lldata);
+                llargs.push(llextra);
+            }
+        }
+    }
+
+    /// A pointer to the value of `operand`, storing it in a temporary if
+    /// it is not already in memory.
+    fn spill_operand(&mut self, bcx: Block<'bcx, 'tcx>, operand: OperandRef<'tcx>) -> ValueRef {
+        match operand.val {
+            OperandValue::Ref(llval) => llval,
+            _ => {
+                let lltemp = base::alloc_ty(bcx, operand.ty, "__spill");
+                self.store_operand(bcx, lltemp, operand);
+                lltemp
             }
         }
     }
@@ -115,3 +427,20 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
         self.blocks[bb.index()].llbb
     }
 }
+
+/// The discriminant of the variant built by `func`, if it is the
+/// constructor of a tuple struct or enum variant of type `ty`.
+fn constructor_disr<'tcx>(func: &mir::Operand<'tcx>, ty: Ty<'tcx>) -> Option<ty::Disr> {
+    let def_id = match *func {
+        mir::Operand::Constant(mir::Constant {
+            literal: mir::Literal::Item { def_id, .. }, ..
+        }) => def_id,
+        _ => return None,
+    };
+    match ty.sty {
+        ty::TyEnum(adt_def, _) | ty::TyStruct(adt_def, _) => {
+            adt_def.variants.iter().find(|v| v.did == def_id).map(|v| v.disr_val)
+        }
+        _ => None,
+    }
+}
@@ -8,11 +8,19 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use middle::ty::{Ty, HasTypeFlags};
-use rustc::middle::const_eval::ConstVal;
+use middle::check_const;
+use middle::def_id::DefId;
+use middle::subst::Substs;
+use middle::ty::{self, Ty, HasTypeFlags};
+use rustc::middle::const_eval::{self, ConstVal};
 use rustc::mir::repr as mir;
+use trans::adt;
+use trans::base;
+use trans::callee;
 use trans::consts;
-use trans::common::{self, Block};
+use trans::common::{self, Block, ExprId};
+use trans::meth;
+use trans::type_of;
 
 use super::operand::OperandRef;
 use super::MirContext;
@@ -49,12 +57,90 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
     {
         let constant_ty = bcx.monomorphize(&constant.ty);
         match constant.literal {
-            mir::Literal::Item { .. } => {
-                unimplemented!()
+            mir::Literal::Item { def_id, substs } => {
+                self.trans_item_ref(bcx, constant_ty, def_id, substs)
             }
             mir::Literal::Value { ref value } => {
                 self.trans_constval(bcx, value, constant_ty)
             }
         }
     }
+
+    /// Translates a reference to an item: a fn or method, a unit struct
+    /// or enum variant, or a constant.
+    fn trans_item_ref(&mut self,
+                      bcx: Block<'bcx, 'tcx>,
+                      ty: Ty<'tcx>,
+                      def_id: DefId,
+                      substs: &'tcx Substs<'tcx>)
+                      -> OperandRef<'tcx>
+    {
+        use super::operand::OperandValue::Immediate;
+
+        let ccx = bcx.ccx();
+        let tcx = bcx.tcx();
+
+        match ty.sty {
+            ty::TyBareFn(..) => {
+                let substs = bcx.monomorphize(substs);
+                let param_substs = bcx.fcx.param_substs;
+                let datum = match tcx.trait_of_item(def_id) {
+                    Some(trait_id) => {
+                        meth::trans_static_method_callee_with_substs(ccx,
+                                                                     def_id,
+                                                                     trait_id,
+                                                                     ExprId(0),
+                                                                     substs,
+                                                                     param_substs)
+                    }
+                    None => {
+                        callee::trans_fn_ref_with_substs(ccx,
+                                                         def_id,
+                                                         ExprId(0),
+                                                         param_substs,
+                                                         substs)
+                    }
+                };
+                // The fn may have been declared with a type which differs
+                // from the one the MIR expects (see trans_fn_ref_with_substs).
+                let llty = type_of::type_of_fn_from_ty(ccx, ty).ptr_to();
+                let llfn = if common::val_ty(datum.val) != llty {
+                    consts::ptrcast(datum.val, llty)
+                } else {
+                    datum.val
+                };
+                OperandRef {
+                    val: Immediate(llfn),
+                    ty: ty
+                }
+            }
+            ty::TyEnum(adt_def, _) | ty::TyStruct(adt_def, _)
+                if adt_def.variants.iter().any(|v| v.did == def_id) => {
+                let disr = adt_def.variants.iter().find(|v| v.did == def_id).unwrap().disr_val;
+                let repr = adt::represent_type(ccx, ty);
+                let lltemp = base::alloc_ty(bcx, ty, "__unit_temp");
+                adt::trans_set_discr(bcx, &repr, lltemp, disr);
+                self.trans_load(bcx, lltemp, ty)
+            }
+            _ => {
+                // The substs select the impl of an associated const.
+                let substs = bcx.monomorphize(substs);
+                let expr = match const_eval::lookup_const_by_id_with_substs(tcx, def_id,
+                                                                            Some(substs.clone())) {
+                    Some(expr) => expr,
+                    None => tcx.sess.bug(&format!("constant {:?} not found", def_id)),
+                };
+                let llglobal = match consts::get_const_expr_as_global(
+                        ccx, expr, check_const::ConstQualif::empty(),
+                        bcx.fcx.param_substs, consts::TrueConst::Yes) {
+                    Ok(llglobal) => llglobal,
+                    Err(err) => {
+                        tcx.sess.span_err(expr.span, &err.description());
+                        common::C_undef(type_of::type_of(ccx, ty).ptr_to())
+                    }
+                };
+                self.trans_load(bcx, llglobal, ty)
+            }
+        }
+    }
 }
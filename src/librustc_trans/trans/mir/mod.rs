@@ -10,8 +10,10 @@
 
 use libc::c_uint;
 use llvm::{self, ValueRef};
+use rustc::middle::ty::{self, Ty};
 use rustc::mir::repr as mir;
 use rustc::mir::tcx::LvalueTy;
+use syntax::abi::Abi;
 use trans::base;
 use trans::build;
 use trans::common::{self, Block};
@@ -37,6 +39,11 @@ pub struct MirContext<'bcx, 'tcx:'bcx> {
     /// then later loaded when generating the DIVERGE_BLOCK.
     llpersonalityslot: Option<ValueRef>,
 
+    /// A block containing just an `unreachable` instruction, created
+    /// the first time a terminator needs a branch which can never be
+    /// taken (e.g. the otherwise case of an exhaustive `Switch`).
+    unreachable_block: Option<Block<'bcx, 'tcx>>,
+
     /// A `Block` for each MIR `BasicBlock`
     blocks: Vec<Block<'bcx, 'tcx>>,
 
@@ -117,6 +124,7 @@ pub fn trans_mir<'bcx, 'tcx>(bcx: Block<'bcx, 'tcx>) {
     let mut mircx = MirContext {
         mir: mir,
         llpersonalityslot: None,
+        unreachable_block: None,
         blocks: block_bcxs,
         vars: vars,
         temps: temps,
@@ -140,19 +148,44 @@ pub fn trans_mir<'bcx, 'tcx>(bcx: Block<'bcx, 'tcx>) {
 /// Produce, for each argument, a `ValueRef` pointing at the
 /// argument's value. As arguments are lvalues, these are always
 /// indirect.
+///
+/// The MIR of a closure takes the environment as its first argument,
+/// so we start at the environment's position rather than after it.
 fn arg_value_refs<'bcx, 'tcx>(bcx: Block<'bcx, 'tcx>,
                               mir: &mir::Mir<'tcx>)
                               -> Vec<LvalueRef<'tcx>> {
-    // FIXME tupled_args? I think I'd rather that mapping is done in MIR land though
     let fcx = bcx.fcx;
     let tcx = bcx.tcx();
-    let mut idx = fcx.arg_offset() as c_uint;
+    let mut idx = fcx.env_arg_pos() as c_uint;
+
+    // A "rust-call" fn which is not a closure takes its last argument as
+    // a tuple in MIR, but receives the fields of that tuple as separate
+    // LLVM arguments.
+    let fn_ty = bcx.monomorphize(&tcx.node_id_to_type(fcx.id));
+    let tupled_arg = match fn_ty.sty {
+        ty::TyBareFn(_, ref f) if f.abi == Abi::RustCall && fcx.llenv.is_none() => {
+            mir.arg_decls.len().checked_sub(1)
+        }
+        _ => None,
+    };
+
     mir.arg_decls
        .iter()
        .enumerate()
        .map(|(arg_index, arg_decl)| {
            let arg_ty = bcx.monomorphize(&arg_decl.ty);
-           let llval = if type_of::arg_is_indirect(bcx.ccx(), arg_ty) {
+           let llval = if Some(arg_index) == tupled_arg {
+               let lltemp = base::alloc_ty(bcx, arg_ty, &format!("arg{}", arg_index));
+               let field_tys = match arg_ty.sty {
+                   ty::TyTuple(ref tys) => tys,
+                   _ => tcx.sess.bug(&format!("rust-call arg {:?} is not a tuple", arg_ty)),
+               };
+               for (i, &field_ty) in field_tys.iter().enumerate() {
+                   let lldest = build::GEPi(bcx, lltemp, &[0, i]);
+                   store_arg(bcx, field_ty, &mut idx, lldest);
+               }
+               lltemp
+           } else if type_of::arg_is_indirect(bcx.ccx(), arg_ty) {
                // Don't copy an indirect argument to an alloca, the caller
                // already put it in a temporary alloca and gave it up, unless
                // we emit extra-debug-info, which requires local allocas :(.
@@ -185,6 +218,28 @@ fn arg_value_refs<'bcx, 'tcx>(bcx: Block<'bcx, 'tcx>,
        .collect()
 }
 
+/// Stores the LLVM argument(s) at `*idx` for a value of type `ty` into
+/// `lldest`, and advances `idx` past them.
+fn store_arg<'bcx, 'tcx>(bcx: Block<'bcx, 'tcx>,
+                         ty: Ty<'tcx>,
+                         idx: &mut c_uint,
+                         lldest: ValueRef) {
+    let llfn = bcx.fcx.llfn;
+    if type_of::arg_is_indirect(bcx.ccx(), ty) {
+        base::memcpy_ty(bcx, lldest, llvm::get_param(llfn, *idx), ty);
+        *idx += 1;
+    } else if common::type_is_fat_ptr(bcx.tcx(), ty) {
+        let lldata = llvm::get_param(llfn, *idx);
+        let llextra = llvm::get_param(llfn, *idx + 1);
+        build::Store(bcx, lldata, expr::get_dataptr(bcx, lldest));
+        build::Store(bcx, llextra, expr::get_meta(bcx, lldest));
+        *idx += 2;
+    } else {
+        base::store_ty(bcx, llvm::get_param(llfn, *idx), lldest, ty);
+        *idx += 1;
+    }
+}
+
 mod analyze;
 mod block;
 mod constant;
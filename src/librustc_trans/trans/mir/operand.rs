@@ -112,23 +112,7 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                 debug!("trans_operand: tr_lvalue={} @ {:?}",
                        bcx.val_to_string(tr_lvalue.llval),
                        ty);
-                let val = match datum::appropriate_rvalue_mode(bcx.ccx(), ty) {
-                    datum::ByValue => {
-                        OperandValue::Immediate(base::load_ty(bcx, tr_lvalue.llval, ty))
-                    }
-                    datum::ByRef if common::type_is_fat_ptr(bcx.tcx(), ty) => {
-                        let (lldata, llextra) = base::load_fat_ptr(bcx, tr_lvalue.llval, ty);
-                        OperandValue::FatPtr(lldata, llextra)
-                    }
-                    datum::ByRef => OperandValue::Ref(tr_lvalue.llval)
-                };
-
-                assert!(!ty.has_erasable_regions());
-
-                OperandRef {
-                    val: val,
-                    ty: ty
-                }
+                self.trans_load(bcx, tr_lvalue.llval, ty)
             }
 
             mir::Operand::Constant(ref constant) => {
@@ -137,6 +121,32 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
         }
     }
 
+    /// Loads the value of type `ty` stored at `llval` as an operand.
+    pub fn trans_load(&mut self,
+                      bcx: Block<'bcx, 'tcx>,
+                      llval: ValueRef,
+                      ty: Ty<'tcx>)
+                      -> OperandRef<'tcx>
+    {
+        let val = match datum::appropriate_rvalue_mode(bcx.ccx(), ty) {
+            datum::ByValue => {
+                OperandValue::Immediate(base::load_ty(bcx, llval, ty))
+            }
+            datum::ByRef if common::type_is_fat_ptr(bcx.tcx(), ty) => {
+                let (lldata, llextra) = base::load_fat_ptr(bcx, llval, ty);
+                OperandValue::FatPtr(lldata, llextra)
+            }
+            datum::ByRef => OperandValue::Ref(llval)
+        };
+
+        assert!(!ty.has_erasable_regions());
+
+        OperandRef {
+            val: val,
+            ty: ty
+        }
+    }
+
     pub fn trans_operand_into(&mut self,
                               bcx: Block<'bcx, 'tcx>,
                               lldest: ValueRef,
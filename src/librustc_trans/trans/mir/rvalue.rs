@@ -9,9 +9,11 @@
 // except according to those terms.
 
 use llvm::ValueRef;
-use rustc::middle::ty::{self, Ty};
+use rustc::middle::ty::{self, Disr, Ty};
+use rustc::middle::ty::cast::{CastTy, IntTy};
 use rustc::mir::repr as mir;
 
+use trans::adt;
 use trans::asm;
 use trans::base;
 use trans::build;
@@ -93,6 +95,18 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                 })
             }
 
+            mir::Rvalue::Aggregate(mir::AggregateKind::Adt(adt_def, index, substs),
+                                   ref operands) => {
+                let tcx = bcx.tcx();
+                let adt_ty = match adt_def.adt_kind() {
+                    ty::AdtKind::Struct => tcx.mk_struct(adt_def, substs),
+                    ty::AdtKind::Enum => tcx.mk_enum(adt_def, substs),
+                };
+                let adt_ty = bcx.monomorphize(&adt_ty);
+                let disr = adt_def.variants[index].disr_val;
+                self.trans_adt_into(bcx, lldest, adt_ty, disr, operands)
+            }
+
             mir::Rvalue::Aggregate(_, ref operands) => {
                 for (i, operand) in operands.iter().enumerate() {
                     // Note: perhaps this should be StructGep, but
@@ -185,7 +199,7 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                             }
                         }
                     }
-                    mir::CastKind::Misc => unimplemented!()
+                    mir::CastKind::Misc => self.trans_misc_cast(bcx, operand, cast_ty),
                 };
                 (bcx, OperandRef {
                     val: val,
@@ -301,6 +315,97 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
         }
     }
 
+    /// Stores the value of the variant with discriminant `disr` of
+    /// `adt_ty`, with `operands` as its fields, into `lldest`.
+    pub fn trans_adt_into(&mut self,
+                          bcx: Block<'bcx, 'tcx>,
+                          lldest: ValueRef,
+                          adt_ty: Ty<'tcx>,
+                          disr: Disr,
+                          operands: &[mir::Operand<'tcx>])
+                          -> Block<'bcx, 'tcx>
+    {
+        let repr = adt::represent_type(bcx.ccx(), adt_ty);
+        adt::trans_set_discr(bcx, &repr, lldest, disr);
+        for (i, operand) in operands.iter().enumerate() {
+            let lldest_i = adt::trans_field_ptr(bcx,
+                                                &repr,
+                                                adt::MaybeSizedValue::sized(lldest),
+                                                disr,
+                                                i);
+            self.trans_operand_into(bcx, lldest_i, operand);
+        }
+        bcx
+    }
+
+    /// Translates an `as` cast between numeric, enum and pointer types.
+    fn trans_misc_cast(&mut self,
+                       bcx: Block<'bcx, 'tcx>,
+                       operand: OperandRef<'tcx>,
+                       cast_ty: Ty<'tcx>)
+                       -> OperandValue
+    {
+        let ccx = bcx.ccx();
+        let ll_t_out = type_of::arg_type_of(ccx, cast_ty);
+
+        if let OperandValue::FatPtr(lldata, llextra) = operand.val {
+            if common::type_is_fat_ptr(bcx.tcx(), cast_ty) {
+                // only the type of the data pointer changes
+                let pointee = cast_ty.builtin_deref(true, ty::NoPreference).unwrap().ty;
+                let ll_data_ty = type_of::in_memory_type_of(ccx, pointee).ptr_to();
+                return OperandValue::FatPtr(build::PointerCast(bcx, lldata, ll_data_ty),
+                                            llextra);
+            } else {
+                // casting to a thin pointer keeps just the address
+                return OperandValue::Immediate(build::PointerCast(bcx, lldata, ll_t_out));
+            }
+        }
+
+        let r_t_in = CastTy::from_ty(operand.ty).expect("bad input type for cast");
+        let r_t_out = CastTy::from_ty(cast_ty).expect("bad output type for cast");
+
+        let (llval, ll_t_in, signed) = if let CastTy::Int(IntTy::CEnum) = r_t_in {
+            let repr = adt::represent_type(ccx, operand.ty);
+            let llptr = match operand.val {
+                OperandValue::Ref(llptr) => llptr,
+                OperandValue::Immediate(llval) => {
+                    let lltemp = base::alloc_ty(bcx, operand.ty, "__cast_temp");
+                    base::store_ty(bcx, llval, lltemp, operand.ty);
+                    lltemp
+                }
+                OperandValue::FatPtr(..) => unreachable!(),
+            };
+            let discr = adt::trans_get_discr(bcx, &repr, llptr, Some(Type::i64(ccx)));
+            (discr, common::val_ty(discr), adt::is_discr_signed(&repr))
+        } else {
+            (operand.immediate(), type_of::arg_type_of(ccx, operand.ty), operand.ty.is_signed())
+        };
+
+        let newval = match (r_t_in, r_t_out) {
+            (CastTy::Ptr(_), CastTy::Ptr(_)) |
+            (CastTy::FnPtr, CastTy::Ptr(_)) |
+            (CastTy::RPtr(_), CastTy::Ptr(_)) => {
+                build::PointerCast(bcx, llval, ll_t_out)
+            }
+            (CastTy::Ptr(_), CastTy::Int(_)) |
+            (CastTy::FnPtr, CastTy::Int(_)) => build::PtrToInt(bcx, llval, ll_t_out),
+            (CastTy::Int(_), CastTy::Ptr(_)) => build::IntToPtr(bcx, llval, ll_t_out),
+
+            (CastTy::Int(_), CastTy::Int(_)) => {
+                expr::int_cast(bcx, ll_t_out, ll_t_in, llval, signed)
+            }
+            (CastTy::Float, CastTy::Float) => expr::float_cast(bcx, ll_t_out, ll_t_in, llval),
+            (CastTy::Int(_), CastTy::Float) if signed => build::SIToFP(bcx, llval, ll_t_out),
+            (CastTy::Int(_), CastTy::Float) => build::UIToFP(bcx, llval, ll_t_out),
+            (CastTy::Float, CastTy::Int(IntTy::I)) => build::FPToSI(bcx, llval, ll_t_out),
+            (CastTy::Float, CastTy::Int(_)) => build::FPToUI(bcx, llval, ll_t_out),
+
+            _ => bcx.sess().bug(&format!("translating unsupported cast: {:?} -> {:?}",
+                                         operand.ty, cast_ty))
+        };
+        OperandValue::Immediate(newval)
+    }
+
     pub fn trans_scalar_binop(&mut self,
                               bcx: Block<'bcx, 'tcx>,
                               op: mir::BinOp,
@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use back::incremental::IncrementalGraph;
 use llvm::{ContextRef, ModuleRef};
 use middle::cstore::LinkMeta;
 
@@ -75,4 +76,6 @@ pub struct CrateTranslation {
     pub metadata: Vec<u8>,
     pub reachable: Vec<String>,
     pub no_builtins: bool,
+    /// The inputs of each codegen unit, under `-Z incremental`.
+    pub incremental: Option<IncrementalGraph>,
 }
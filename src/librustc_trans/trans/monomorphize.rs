@@ -8,7 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use back::link::exported_name;
+use back::link::{exported_name, incremental_mono_symbol_hash};
+use dep_graph::DepNode;
 use session;
 use llvm::ValueRef;
 use llvm;
@@ -51,6 +52,7 @@ pub fn monomorphic_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
 
     // we can only monomorphize things in this crate (or inlined into it)
     let fn_node_id = ccx.tcx().map.as_local_node_id(fn_id).unwrap();
+    ccx.tcx().dep_graph.read(DepNode::Hir(fn_id));
 
     let _icx = push_ctxt("monomorphic_fn");
 
@@ -125,11 +127,15 @@ pub fn monomorphic_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
 
     let hash;
     let s = {
-        let mut state = SipHasher::new();
-        hash_id.hash(&mut state);
-        mono_ty.hash(&mut state);
-
-        hash = format!("h{}", state.finish());
+        // Types hash by address, which is only stable within one compilation.
+        hash = if ccx.sess().opts.debugging_opts.incremental {
+            incremental_mono_symbol_hash(ccx, fn_id, psubsts)
+        } else {
+            let mut state = SipHasher::new();
+            hash_id.hash(&mut state);
+            mono_ty.hash(&mut state);
+            format!("h{}", state.finish())
+        };
         let path = ccx.tcx().map.def_path_from_id(fn_node_id);
         exported_name(path, &hash[..])
     };
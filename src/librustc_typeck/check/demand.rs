@@ -67,6 +67,38 @@ pub fn coerce<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
       Ok(()) => { /* ok */ }
       Err(ref err) => {
         fcx.report_mismatched_types(sp, expected, expr_ty, err);
+        suggest_borrow(fcx, expected, expr_ty, expr);
       }
     }
 }
+
+// If `expr` would have the `expected` reference type once borrowed, suggest
+// borrowing it.
+fn suggest_borrow<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
+                            expected: Ty<'tcx>,
+                            expr_ty: Ty<'tcx>,
+                            expr: &hir::Expr) {
+    let (referent, mutbl) = match expected.sty {
+        ty::TyRef(_, ty::TypeAndMut { ty, mutbl }) => (ty, mutbl),
+        _ => return,
+    };
+    if infer::can_mk_subty(fcx.infcx(), expr_ty, referent).is_err() {
+        return;
+    }
+    if let Ok(snippet) = fcx.tcx().sess.codemap().span_to_snippet(expr.span) {
+        let prefix = match mutbl {
+            hir::MutMutable => "&mut ",
+            hir::MutImmutable => "&",
+        };
+        // `&a + b` would borrow only `a`
+        let snippet = match expr.node {
+            hir::ExprPath(..) | hir::ExprField(..) | hir::ExprTupField(..) |
+            hir::ExprIndex(..) | hir::ExprCall(..) | hir::ExprMethodCall(..) |
+            hir::ExprLit(..) => snippet,
+            _ => format!("({})", snippet),
+        };
+        fcx.tcx().sess.span_suggestion(expr.span,
+                                       "consider borrowing here:",
+                                       format!("{}{}", prefix, snippet));
+    }
+}
@@ -26,7 +26,7 @@ use middle::traits::{Obligation, SelectionContext};
 use util::nodemap::{FnvHashSet};
 
 use syntax::ast;
-use syntax::codemap::Span;
+use syntax::codemap::{BytePos, Span};
 use rustc_front::print::pprust;
 use rustc_front::hir;
 
@@ -235,6 +235,30 @@ pub fn report_error<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
 
 pub type AllTraitsVec = Vec<TraitInfo>;
 
+/// Suggests importing `path` at the start of the body being checked, where
+/// a `use` is in scope for the whole body.
+fn suggest_use(fcx: &FnCtxt, path: &str) {
+    let block = match fcx.tcx().map.find(fcx.body_id) {
+        Some(hir_map::NodeBlock(block)) => block,
+        _ => return,
+    };
+    let cm = fcx.sess().codemap();
+    let loc = cm.lookup_char_pos(block.span.lo);
+    let indent: String = match loc.file.get_line(loc.line - 1) {
+        Some(line) => line.chars().take_while(|c| c.is_whitespace()).collect(),
+        None => return,
+    };
+    // just after the opening brace
+    let sp = Span {
+        lo: block.span.lo + BytePos(1),
+        hi: block.span.lo + BytePos(1),
+        expn_id: block.span.expn_id,
+    };
+    fcx.sess().span_suggestion(sp,
+                               &format!("import `{}` here:", path),
+                               format!("\n{}    use {};", indent, path));
+}
+
 fn suggest_traits_to_import<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                                       span: Span,
                                       rcvr_ty: Ty<'tcx>,
@@ -264,6 +288,9 @@ fn suggest_traits_to_import<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                                                fcx.tcx().item_path_str(*trait_did)))
 
         }
+        if candidates.len() == 1 {
+            suggest_use(fcx, &fcx.tcx().item_path_str(candidates[0]));
+        }
         return
     }
 
@@ -82,6 +82,7 @@ use self::TupleArgumentsFlag::*;
 
 use astconv::{self, ast_region_to_region, ast_ty_to_ty, AstConv, PathParamMode};
 use check::_match::pat_ctxt;
+use dep_graph::DepNode;
 use fmt_macros::{Parser, Piece, Position};
 use middle::astconv_util::prohibit_type_params;
 use middle::cstore::LOCAL_CRATE;
@@ -110,6 +111,7 @@ use TypeAndSubsts;
 use lint;
 use util::common::{block_query, ErrorReported, indenter, loop_query};
 use util::nodemap::{DefIdMap, FnvHashMap, NodeMap};
+use util::profiling;
 
 use std::cell::{Cell, Ref, RefCell};
 use std::collections::{HashSet};
@@ -380,6 +382,10 @@ impl<'a, 'tcx> Visitor<'tcx> for CheckItemTypesVisitor<'a, 'tcx> {
 
 impl<'a, 'tcx> Visitor<'tcx> for CheckItemBodiesVisitor<'a, 'tcx> {
     fn visit_item(&mut self, i: &'tcx hir::Item) {
+        let def_id = self.ccx.tcx.map.local_def_id(i.id);
+        let _task = self.ccx.tcx.dep_graph.in_task(DepNode::TypeckItemBody(def_id));
+        self.ccx.tcx.dep_graph.read(DepNode::Hir(def_id));
+        let _profile = profiling::item("typeck", || self.ccx.tcx.map.path_to_string(i.id));
         check_item_body(self.ccx, i);
     }
 }
@@ -3016,8 +3022,9 @@ fn check_expr_with_unifier<'a, 'tcx, F>(fcx: &FnCtxt<'a, 'tcx>,
             }
         }
         if let Some(n) = best {
-            tcx.sess.span_help(field.span,
-                &format!("did you mean `{}`?", n));
+            tcx.sess.span_suggestion(field.span,
+                                     &format!("did you mean `{}`?", n),
+                                     n.to_string());
         }
     }
 
@@ -775,6 +775,11 @@ pub enum Stmt_ {
     StmtSemi(P<Expr>, NodeId),
 
     StmtMac(P<Mac>, MacStmtStyle, ThinAttributes),
+
+    /// A placeholder for a statement which failed to parse. The parser
+    /// reports an error whenever it produces one, so compilation stops
+    /// before they would reach lowering.
+    StmtError,
 }
 
 impl Stmt_ {
@@ -784,6 +789,7 @@ impl Stmt_ {
             StmtExpr(_, id) => Some(id),
             StmtSemi(_, id) => Some(id),
             StmtMac(..) => None,
+            StmtError => None,
         }
     }
 
@@ -794,6 +800,7 @@ impl Stmt_ {
             StmtSemi(ref e, _) => e.attrs(),
             StmtMac(_, _, Some(ref b)) => b,
             StmtMac(_, _, None) => &[],
+            StmtError => &[],
         }
     }
 }
@@ -2049,6 +2056,10 @@ pub enum Item_ {
              Vec<P<ImplItem>>),
     /// A macro invocation (which includes macro definition)
     ItemMac(Mac),
+    /// A placeholder for an item which failed to parse. The parser reports
+    /// an error whenever it produces one, so compilation stops before they
+    /// would reach lowering.
+    ItemError,
 }
 
 impl Item_ {
@@ -2066,6 +2077,7 @@ impl Item_ {
             ItemStruct(..) => "struct",
             ItemTrait(..) => "trait",
             ItemMac(..) |
+            ItemError |
             ItemImpl(..) |
             ItemDefaultImpl(..) => "item"
         }
@@ -16,7 +16,7 @@ pub use self::IntType::*;
 
 use ast;
 use ast::{AttrId, Attribute, Attribute_, MetaItem, MetaWord, MetaNameValue, MetaList};
-use ast::{Stmt, StmtDecl, StmtExpr, StmtMac, StmtSemi, DeclItem, DeclLocal};
+use ast::{Stmt, StmtDecl, StmtExpr, StmtMac, StmtSemi, StmtError, DeclItem, DeclLocal};
 use ast::{Expr, Item, Local, Decl};
 use codemap::{Span, Spanned, spanned, dummy_spanned};
 use codemap::BytePos;
@@ -358,6 +358,35 @@ pub fn requests_inline(attrs: &[Attribute]) -> bool {
     }
 }
 
+/// Find the setting of an `#[overflow_checks(on|off)]` attribute in `attrs`,
+/// if there is one.
+pub fn find_overflow_checks(diagnostic: Option<&SpanHandler>, attrs: &[Attribute])
+                            -> Option<bool> {
+    attrs.iter().fold(None, |on, attr| {
+        if !attr.check_name("overflow_checks") {
+            return on;
+        }
+        match attr.meta_item_list() {
+            Some(items) if items.len() == 1 => {
+                if contains_name(items, "on") {
+                    Some(true)
+                } else if contains_name(items, "off") {
+                    Some(false)
+                } else {
+                    diagnostic.map(|d| { d.span_err(items[0].span, "invalid argument"); });
+                    on
+                }
+            }
+            _ => {
+                diagnostic.map(|d| {
+                    d.span_err(attr.span, "expected one argument, `on` or `off`");
+                });
+                on
+            }
+        }
+    })
+}
+
 /// Tests if a cfg-pattern matches the cfg set
 pub fn cfg_matches<T: CfgDiag>(cfgs: &[P<MetaItem>],
                            cfg: &ast::MetaItem,
@@ -881,6 +910,7 @@ impl WithAttrs for P<Stmt> {
                         ats.update(|a| a.append(attrs));
                         StmtMac(mac, style, ats)
                     }
+                    StmtError => StmtError,
                 },
             }
         })
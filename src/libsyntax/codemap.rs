@@ -20,6 +20,7 @@
 pub use self::ExpnFormat::*;
 
 use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::ops::{Add, Sub};
 use std::path::Path;
 use std::rc::Rc;
@@ -596,6 +597,8 @@ impl FileLoader for RealFileLoader {
 pub struct CodeMap {
     pub files: RefCell<Vec<Rc<FileMap>>>,
     expansions: RefCell<Vec<ExpnInfo>>,
+    /// The `macro_rules!` arm each expansion of such a macro used.
+    macro_arms: RefCell<HashMap<ExpnId, Span>>,
     file_loader: Box<FileLoader>
 }
 
@@ -604,6 +607,7 @@ impl CodeMap {
         CodeMap {
             files: RefCell::new(Vec::new()),
             expansions: RefCell::new(Vec::new()),
+            macro_arms: RefCell::new(HashMap::new()),
             file_loader: Box::new(RealFileLoader)
         }
     }
@@ -612,6 +616,7 @@ impl CodeMap {
         CodeMap {
             files: RefCell::new(Vec::new()),
             expansions: RefCell::new(Vec::new()),
+            macro_arms: RefCell::new(HashMap::new()),
             file_loader: file_loader
         }
     }
@@ -1073,6 +1078,17 @@ impl CodeMap {
         }
     }
 
+    /// Records that the expansion `id` of a `macro_rules!` macro used the
+    /// arm at `arm`.
+    pub fn record_macro_arm(&self, id: ExpnId, arm: Span) {
+        self.macro_arms.borrow_mut().insert(id, arm);
+    }
+
+    /// The `macro_rules!` arm the expansion `id` used, if it was recorded.
+    pub fn macro_arm(&self, id: ExpnId) -> Option<Span> {
+        self.macro_arms.borrow().get(&id).cloned()
+    }
+
     /// Check if a span is "internal" to a macro in which #[unstable]
     /// items can be used (that is, a macro marked with
     /// `#[allow_internal_unstable]`).
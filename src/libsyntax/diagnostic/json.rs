@@ -0,0 +1,296 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A JSON emitter for errors.
+//!
+//! This works by converting diagnostics to an internal representation (the
+//! `Diagnostic` struct below) which is then serialised with
+//! `libserialize::json`. Each top-level diagnostic is written as a single
+//! JSON object on its own line. Notes and help messages which follow an error
+//! or warning are attached to it as children rather than being printed on
+//! their own, so a consumer sees one object per logical diagnostic.
+//!
+//! The format of the JSON output should be considered *unstable*.
+
+use codemap::{self, CodeMap, Span, COMMAND_LINE_EXPN, DUMMY_SP};
+use diagnostics::registry::Registry;
+use diagnostic::{RenderSpan, Level, Emitter};
+use diagnostic::{FullSpan, EndSpan, Suggestion, FileLine};
+use diagnostic::{Bug, Fatal, Error, Warning, Note, Help};
+
+use serialize::json::as_json;
+use std::io::{self, Write};
+
+pub struct JsonEmitter {
+    dst: Box<Write + Send>,
+    registry: Option<Registry>,
+    /// The last top-level diagnostic, which is held back until we know that
+    /// no more notes or help messages are going to be attached to it.
+    pending: Option<Diagnostic>,
+}
+
+impl JsonEmitter {
+    pub fn stderr(registry: Option<Registry>) -> JsonEmitter {
+        JsonEmitter::new(Box::new(io::stderr()), registry)
+    }
+
+    pub fn new(dst: Box<Write + Send>, registry: Option<Registry>) -> JsonEmitter {
+        JsonEmitter {
+            dst: dst,
+            registry: registry,
+            pending: None,
+        }
+    }
+
+    fn push(&mut self, diag: Diagnostic, lvl: Level) {
+        match lvl {
+            Note | Help if self.pending.is_some() => {
+                self.pending.as_mut().unwrap().children.push(diag);
+            }
+            Error | Warning => {
+                self.flush_pending();
+                self.pending = Some(diag);
+            }
+            // Fatal errors and bugs are usually followed by a panic, so make
+            // sure they reach the output straight away.
+            Bug | Fatal | Note | Help => {
+                self.flush_pending();
+                self.write(&diag);
+            }
+        }
+    }
+
+    fn flush_pending(&mut self) {
+        if let Some(diag) = self.pending.take() {
+            self.write(&diag);
+        }
+    }
+
+    fn write(&mut self, diag: &Diagnostic) {
+        if let Err(e) = writeln!(&mut self.dst, "{}", as_json(diag)) {
+            panic!("failed to print diagnostics: {:?}", e);
+        }
+    }
+}
+
+impl Emitter for JsonEmitter {
+    fn emit(&mut self,
+            cmsp: Option<(&codemap::CodeMap, Span)>,
+            msg: &str,
+            code: Option<&str>,
+            lvl: Level) {
+        let spans = match cmsp {
+            Some((cm, sp)) => DiagnosticSpan::from_render_span(cm, &FullSpan(sp)),
+            None => vec![],
+        };
+        let diag = Diagnostic::new(msg, code, lvl, spans, self.registry.as_ref());
+        self.push(diag, lvl);
+    }
+
+    fn custom_emit(&mut self, cm: &codemap::CodeMap, sp: RenderSpan, msg: &str, lvl: Level) {
+        let spans = DiagnosticSpan::from_render_span(cm, &sp);
+        let diag = Diagnostic::new(msg, None, lvl, spans, self.registry.as_ref());
+        self.push(diag, lvl);
+    }
+}
+
+impl Drop for JsonEmitter {
+    fn drop(&mut self) {
+        self.flush_pending();
+    }
+}
+
+// The following data types are provided just for serialisation.
+
+#[derive(RustcEncodable)]
+struct Diagnostic {
+    /// The primary error message.
+    message: String,
+    code: Option<DiagnosticCode>,
+    /// "error: internal compiler error", "error", "warning", "note", "help".
+    level: String,
+    /// The primary span (if any) comes first, followed by the call sites of
+    /// any macros the primary span was expanded from.
+    spans: Vec<DiagnosticSpan>,
+    /// Notes and help messages attached to this diagnostic.
+    children: Vec<Diagnostic>,
+}
+
+#[derive(RustcEncodable)]
+struct DiagnosticSpan {
+    file_name: String,
+    byte_start: u32,
+    byte_end: u32,
+    /// 1-based.
+    line_start: usize,
+    line_end: usize,
+    /// 1-based, character offset.
+    column_start: usize,
+    column_end: usize,
+    /// Whether this is the span the diagnostic is reported at, as opposed to
+    /// a secondary span such as a macro invocation site.
+    is_primary: bool,
+    /// A short description of a secondary span.
+    label: Option<String>,
+    /// Source text which is suggested to replace the text covered by the
+    /// span, if this span belongs to a suggestion.
+    suggested_replacement: Option<String>,
+}
+
+#[derive(RustcEncodable)]
+struct DiagnosticCode {
+    /// The code itself.
+    code: String,
+    /// An explanation for the code.
+    explanation: Option<&'static str>,
+}
+
+impl Diagnostic {
+    fn new(msg: &str,
+           code: Option<&str>,
+           lvl: Level,
+           spans: Vec<DiagnosticSpan>,
+           registry: Option<&Registry>)
+           -> Diagnostic {
+        Diagnostic {
+            message: msg.to_string(),
+            code: code.map(|c| DiagnosticCode::new(c, registry)),
+            level: lvl.to_string(),
+            spans: spans,
+            children: vec![],
+        }
+    }
+}
+
+impl DiagnosticSpan {
+    fn from_render_span(cm: &CodeMap, rsp: &RenderSpan) -> Vec<DiagnosticSpan> {
+        let sp = rsp.span();
+
+        // Spans pointing at the command line don't correspond to any source.
+        if sp.expn_id == COMMAND_LINE_EXPN {
+            return vec![];
+        }
+
+        let mut primary = match *rsp {
+            EndSpan(_) => {
+                DiagnosticSpan::new(cm, Span { lo: sp.hi, hi: sp.hi, expn_id: sp.expn_id })
+            }
+            FullSpan(_) | Suggestion(..) | FileLine(_) => DiagnosticSpan::new(cm, sp),
+        };
+        primary.is_primary = true;
+        if let Suggestion(_, ref replacement) = *rsp {
+            primary.suggested_replacement = Some(replacement.clone());
+        }
+
+        let mut spans = vec![primary];
+        spans.extend(DiagnosticSpan::macro_backtrace(cm, sp));
+        spans
+    }
+
+    fn new(cm: &CodeMap, sp: Span) -> DiagnosticSpan {
+        let start = cm.lookup_char_pos(sp.lo);
+        let end = cm.lookup_char_pos(sp.hi);
+        DiagnosticSpan {
+            file_name: start.file.name.clone(),
+            byte_start: cm.lookup_byte_offset(sp.lo).pos.0,
+            byte_end: cm.lookup_byte_offset(sp.hi).pos.0,
+            line_start: start.line,
+            line_end: end.line,
+            column_start: start.col.0 + 1,
+            column_end: end.col.0 + 1,
+            is_primary: false,
+            label: None,
+            suggested_replacement: None,
+        }
+    }
+
+    /// The invocation sites of the macros `sp` was expanded from, innermost
+    /// first, mirroring the "in this expansion of" notes of `EmitterWriter`.
+    fn macro_backtrace(cm: &CodeMap, sp: Span) -> Vec<DiagnosticSpan> {
+        let mut spans = vec![];
+        let mut last_span = DUMMY_SP;
+        let mut sp_opt = Some(sp);
+
+        while let Some(sp) = sp_opt {
+            sp_opt = cm.with_expn_info(sp.expn_id, |expn_info| {
+                expn_info.map(|ei| {
+                    let (pre, post) = match ei.callee.format {
+                        codemap::MacroAttribute(..) => ("#[", "]"),
+                        codemap::MacroBang(..) => ("", "!"),
+                    };
+                    // Don't record recursive invocations
+                    if ei.call_site != last_span {
+                        last_span = ei.call_site;
+                        let mut span = DiagnosticSpan::new(cm, ei.call_site);
+                        span.label = Some(format!("in this expansion of {}{}{}",
+                                                  pre, ei.callee.name(), post));
+                        spans.push(span);
+                    }
+                    ei.call_site
+                })
+            });
+        }
+
+        spans
+    }
+}
+
+impl DiagnosticCode {
+    fn new(code: &str, registry: Option<&Registry>) -> DiagnosticCode {
+        DiagnosticCode {
+            code: code.to_string(),
+            explanation: registry.and_then(|registry| registry.find_description(code)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::JsonEmitter;
+    use codemap::{mk_sp, BytePos, CodeMap};
+    use diagnostic::{Emitter, Error, Note, Help};
+    use std::sync::{Arc, Mutex};
+    use std::io::{self, Write};
+    use std::str::from_utf8;
+
+    struct Sink(Arc<Mutex<Vec<u8>>>);
+    impl Write for Sink {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            Write::write(&mut *self.0.lock().unwrap(), data)
+        }
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+
+    #[test]
+    fn test_notes_are_attached_as_children() {
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let cm = CodeMap::new();
+        cm.new_filemap_and_lines("dummy.txt", "first line\nsecond line\n");
+        let sp = mk_sp(BytePos(11), BytePos(17));
+        {
+            let mut je = JsonEmitter::new(Box::new(Sink(data.clone())), None);
+            je.emit(Some((&cm, sp)), "first", Some("E0001"), Error);
+            je.emit(Some((&cm, sp)), "a note", None, Note);
+            je.emit(None, "some help", None, Help);
+            je.emit(None, "second", None, Error);
+        }
+        let vec = data.lock().unwrap().clone();
+        let output = from_utf8(&vec).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(r#"{"message":"first","code":{"code":"E0001","#));
+        assert!(lines[0].contains(
+            r#""line_start":2,"line_end":2,"column_start":1,"column_end":7"#));
+        assert!(lines[0].contains(r#""children":[{"message":"a note","#));
+        assert!(lines[0].contains(r#"{"message":"some help","#));
+        assert!(lines[1].starts_with(r#"{"message":"second","#));
+        assert!(lines[1].ends_with(r#""children":[]}"#));
+    }
+}
@@ -16,12 +16,15 @@ use self::Destination::*;
 use codemap::{self, COMMAND_LINE_SP, COMMAND_LINE_EXPN, Pos, Span};
 use diagnostics;
 
-use std::cell::{RefCell, Cell};
+use std::cell::{RefCell, Cell, Ref};
 use std::{cmp, error, fmt};
 use std::io::prelude::*;
 use std::io;
 use term;
 
+pub mod json;
+pub mod suggestions;
+
 /// maximum number of lines we will print for each error; arbitrary.
 const MAX_LINES: usize = 6;
 
@@ -62,6 +65,15 @@ impl RenderSpan {
     }
 }
 
+/// A machine-applicable edit attached to a diagnostic: the source text
+/// covered by `span` should be replaced with `replacement`.
+#[derive(Clone, Debug)]
+pub struct CodeSuggestion {
+    pub span: Span,
+    pub msg: String,
+    pub replacement: String,
+}
+
 #[derive(Clone, Copy)]
 pub enum ColorConfig {
     Auto,
@@ -118,6 +130,8 @@ impl error::Error for ExplicitBug {
 pub struct SpanHandler {
     pub handler: Handler,
     pub cm: codemap::CodeMap,
+    /// Every suggestion emitted so far, in emission order.
+    suggestions: RefCell<Vec<CodeSuggestion>>,
 }
 
 impl SpanHandler {
@@ -125,6 +139,7 @@ impl SpanHandler {
         SpanHandler {
             handler: handler,
             cm: cm,
+            suggestions: RefCell::new(Vec::new()),
         }
     }
     pub fn span_fatal(&self, sp: Span, msg: &str) -> FatalError {
@@ -160,10 +175,24 @@ impl SpanHandler {
     }
     /// Prints out a message with a suggested edit of the code.
     ///
+    /// The edit is also recorded as a `CodeSuggestion`, so that it can be
+    /// applied to the source automatically (see `diagnostic::suggestions`).
+    /// Only use this when `suggestion` is valid code which can be spliced in
+    /// place of `sp` as is.
+    ///
     /// See `diagnostic::RenderSpan::Suggestion` for more information.
     pub fn span_suggestion(&self, sp: Span, msg: &str, suggestion: String) {
+        self.suggestions.borrow_mut().push(CodeSuggestion {
+            span: sp,
+            msg: msg.to_string(),
+            replacement: suggestion.clone(),
+        });
         self.handler.custom_emit(&self.cm, Suggestion(sp, suggestion), msg, Help);
     }
+    /// The suggestions emitted so far through `span_suggestion`.
+    pub fn suggestions(&self) -> Ref<Vec<CodeSuggestion>> {
+        self.suggestions.borrow()
+    }
     pub fn fileline_note(&self, sp: Span, msg: &str) {
         self.handler.custom_emit(&self.cm, FileLine(sp), msg, Note);
     }
@@ -314,7 +343,10 @@ impl Level {
 
 pub struct EmitterWriter {
     dst: Destination,
-    registry: Option<diagnostics::registry::Registry>
+    registry: Option<diagnostics::registry::Registry>,
+    /// Whether to show the source of every macro invocation a diagnostic
+    /// is inside, and the `macro_rules!` arm it matched.
+    macro_backtrace: bool,
 }
 
 enum Destination {
@@ -352,15 +384,22 @@ impl EmitterWriter {
                 Some(t) => Terminal(t),
                 None    => Raw(Box::new(stderr)),
             };
-            EmitterWriter { dst: dst, registry: registry }
+            EmitterWriter { dst: dst, registry: registry, macro_backtrace: false }
         } else {
-            EmitterWriter { dst: Raw(Box::new(stderr)), registry: registry }
+            EmitterWriter { dst: Raw(Box::new(stderr)), registry: registry, macro_backtrace: false }
         }
     }
 
     pub fn new(dst: Box<Write + Send>,
                registry: Option<diagnostics::registry::Registry>) -> EmitterWriter {
-        EmitterWriter { dst: Raw(dst), registry: registry }
+        EmitterWriter { dst: Raw(dst), registry: registry, macro_backtrace: false }
+    }
+
+    /// Shows the full macro backtrace of diagnostics (`-Z macro-backtrace`):
+    /// the source of each invocation, and of the `macro_rules!` arm that
+    /// matched it, even for diagnostics which are not shown with source.
+    pub fn set_macro_backtrace(&mut self, macro_backtrace: bool) {
+        self.macro_backtrace = macro_backtrace;
     }
 
     fn print_maybe_styled(&mut self,
@@ -455,6 +494,9 @@ impl EmitterWriter {
             }
             FileLine(..) => {
                 // no source text in this case!
+                if self.macro_backtrace {
+                    try!(self.print_macro_backtrace(cm, sp));
+                }
             }
         }
 
@@ -756,6 +798,25 @@ impl EmitterWriter {
                                                        Note,
                                                        &diag_string,
                                                        None));
+
+                            if self.macro_backtrace {
+                                try!(self.highlight_lines(cm,
+                                                          ei.call_site,
+                                                          Note,
+                                                          cm.span_to_lines(ei.call_site)));
+                                if let Some(arm) = cm.macro_arm(sp.expn_id) {
+                                    try!(self.print_diagnostic(&cm.span_to_string(arm),
+                                                               Note,
+                                                               &format!("which matched this arm \
+                                                                         of {}!",
+                                                                        ei.callee.name()),
+                                                               None));
+                                    try!(self.highlight_lines(cm,
+                                                              arm,
+                                                              Note,
+                                                              cm.span_to_lines(arm)));
+                                }
+                            }
                         }
                         Ok(Some(ei.call_site))
                     }
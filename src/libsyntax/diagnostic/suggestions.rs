@@ -0,0 +1,119 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Writing the suggestions recorded by a `SpanHandler` back to the source
+//! files they were made against.
+//!
+//! Edits are always spliced into the original source text held by the
+//! `CodeMap`, never into what is currently on disk, so applying a growing
+//! list of suggestions several times during a compilation gives the same
+//! result as applying the final list once. Suggestions whose span overlaps
+//! an edit that has already been accepted for the same file, or which lie
+//! inside a macro expansion, are skipped.
+
+use codemap::{CodeMap, Pos, NO_EXPANSION};
+use diagnostic::CodeSuggestion;
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Write};
+
+#[derive(PartialEq, Debug)]
+struct Edit<'a> {
+    lo: usize,
+    hi: usize,
+    replacement: &'a str,
+}
+
+/// Rewrites every source file touched by `suggestions`, returning the names
+/// of the files which were written.
+pub fn apply_suggestions(cm: &CodeMap,
+                         suggestions: &[CodeSuggestion])
+                         -> io::Result<Vec<String>> {
+    let mut edits_by_file = BTreeMap::new();
+    for suggestion in suggestions {
+        let sp = suggestion.span;
+        // Code produced by a macro has no single place in the source we
+        // could rewrite.
+        if sp.expn_id != NO_EXPANSION {
+            debug!("skipping suggestion in macro expansion: {:?}", suggestion);
+            continue;
+        }
+
+        let lo = cm.lookup_byte_offset(sp.lo);
+        let hi = cm.lookup_byte_offset(sp.hi);
+        if lo.fm.name != hi.fm.name || !lo.fm.is_real_file() || lo.fm.is_imported() {
+            debug!("skipping suggestion outside of a local source file: {:?}", suggestion);
+            continue;
+        }
+
+        let edit = Edit {
+            lo: lo.pos.to_usize(),
+            hi: hi.pos.to_usize(),
+            replacement: &suggestion.replacement[..],
+        };
+        edits_by_file.entry(lo.fm.name.clone())
+                     .or_insert_with(|| (lo.fm.clone(), vec![]))
+                     .1.push(edit);
+    }
+
+    let mut written = vec![];
+    for (name, (fm, edits)) in edits_by_file {
+        let src = splice(fm.src.as_ref().unwrap(), edits);
+        let mut file = try!(File::create(&name));
+        try!(file.write_all(src.as_bytes()));
+        written.push(name);
+    }
+    Ok(written)
+}
+
+/// Applies `edits` to `src`, dropping duplicates and any edit which overlaps
+/// an earlier one.
+fn splice(src: &str, mut edits: Vec<Edit>) -> String {
+    edits.sort_by(|a, b| (a.lo, a.hi).cmp(&(b.lo, b.hi)));
+    edits.dedup();
+
+    let mut result = String::with_capacity(src.len());
+    let mut last = 0;
+    for edit in edits {
+        if edit.lo < last {
+            debug!("skipping overlapping suggestion: {:?}", edit);
+            continue;
+        }
+        result.push_str(&src[last..edit.lo]);
+        result.push_str(edit.replacement);
+        last = edit.hi;
+    }
+    result.push_str(&src[last..]);
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::{splice, Edit};
+
+    fn edit(lo: usize, hi: usize, replacement: &str) -> Edit {
+        Edit { lo: lo, hi: hi, replacement: replacement }
+    }
+
+    #[test]
+    fn test_splice_in_order() {
+        let src = "let x = foo.baa;";
+        let edits = vec![edit(12, 15, "bar"), edit(4, 5, "y")];
+        assert_eq!(splice(src, edits), "let y = foo.bar;");
+    }
+
+    #[test]
+    fn test_splice_skips_duplicates_and_overlaps() {
+        let src = "a + b";
+        let edits = vec![edit(0, 1, "c"), edit(0, 1, "c"), edit(0, 5, "d"), edit(4, 4, "&")];
+        assert_eq!(splice(src, edits), "c + &b");
+    }
+}
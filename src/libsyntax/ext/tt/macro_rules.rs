@@ -9,7 +9,7 @@
 // except according to those terms.
 
 use ast::{self, TokenTree};
-use codemap::{Span, DUMMY_SP};
+use codemap::{Span, DUMMY_SP, mk_sp};
 use ext::base::{DummyResult, ExtCtxt, MacResult, SyntaxExtension};
 use ext::base::{NormalTT, TTMacroExpander};
 use ext::tt::macro_parser::{Success, Error, Failure};
@@ -180,6 +180,10 @@ fn generic_extension<'cx>(cx: &'cx ExtCtxt,
 
         match TokenTree::parse(cx, lhs_tt, arg) {
             Success(named_matches) => {
+                // Remember the arm for `-Z macro-backtrace`.
+                let arm = mk_sp(lhs.get_span().lo, rhses[i].get_span().hi);
+                cx.codemap().record_macro_arm(cx.backtrace(), arm);
+
                 let rhs = match rhses[i] {
                     // ignore delimiters
                     TokenTree::Delimited(_, ref delimed) => delimed.tts.clone(),
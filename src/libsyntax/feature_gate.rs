@@ -230,6 +230,9 @@ const KNOWN_FEATURES: &'static [(&'static str, &'static str, Option<u32>, Status
 
     // Allow attributes on expressions and non-item statements
     ("stmt_expr_attributes", "1.6.0", Some(15701), Active),
+
+    // Allows `#[overflow_checks(on|off)]` on fns, impls and modules
+    ("overflow_checks", "1.7.0", None, Active),
 ];
 // (changing above list without updating src/doc/reference.md makes @cmr sad)
 
@@ -366,6 +369,9 @@ pub const KNOWN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeGat
            "unsafe_destructor_blind_to_params has unstable semantics \
             and may be removed in the future")),
     ("unwind", Whitelisted, Gated("unwind_attributes", "#[unwind] is experimental")),
+    ("overflow_checks", Whitelisted, Gated("overflow_checks",
+                                           "the `#[overflow_checks]` attribute \
+                                            is an experimental feature")),
 
     // used in resolve
     ("prelude_import", Whitelisted, Gated("prelude_import",
@@ -959,6 +959,7 @@ pub fn noop_fold_item_underscore<T: Folder>(i: Item_, folder: &mut T) -> Item_ {
                       items)
         }
         ItemMac(m) => ItemMac(folder.fold_mac(m)),
+        ItemError => ItemError,
     }
 }
 
@@ -1378,6 +1379,10 @@ pub fn noop_fold_stmt<T: Folder>(Spanned {node, span}: Stmt, folder: &mut T)
                           semi,
                           attrs.map_thin_attrs(|v| fold_attrs(v, folder))),
             span: span
+        })),
+        StmtError => SmallVector::one(P(Spanned {
+            node: StmtError,
+            span: span
         }))
     }
 }
@@ -32,7 +32,7 @@ use ast::{ForeignItem, ForeignItemStatic, ForeignItemFn, ForeignMod, FunctionRet
 use ast::{Ident, Inherited, ImplItem, Item, Item_, ItemStatic};
 use ast::{ItemEnum, ItemFn, ItemForeignMod, ItemImpl, ItemConst};
 use ast::{ItemMac, ItemMod, ItemStruct, ItemTrait, ItemTy, ItemDefaultImpl};
-use ast::{ItemExternCrate, ItemUse};
+use ast::{ItemExternCrate, ItemUse, ItemError};
 use ast::{LifetimeDef, Lit, Lit_};
 use ast::{LitBool, LitChar, LitByte, LitByteStr};
 use ast::{LitStr, LitInt, Local};
@@ -44,7 +44,7 @@ use ast::{Pat, PatBox, PatEnum, PatIdent, PatLit, PatQPath, PatMac, PatRange};
 use ast::{PatRegion, PatStruct, PatTup, PatVec, PatWild};
 use ast::{PolyTraitRef, QSelf};
 use ast::{Return, BiShl, BiShr, Stmt, StmtDecl};
-use ast::{StmtExpr, StmtSemi, StmtMac, VariantData, StructField};
+use ast::{StmtExpr, StmtSemi, StmtMac, StmtError, VariantData, StructField};
 use ast::{BiSub, StrStyle};
 use ast::{SelfExplicit, SelfRegion, SelfStatic, SelfValue};
 use ast::{Delimited, SequenceRepetition, TokenTree, TraitItem, TraitRef};
@@ -280,6 +280,10 @@ pub struct Parser<'a> {
     /// into modules, and sub-parsers have new values for this name.
     pub root_module_name: Option<String>,
     pub expected_tokens: Vec<TokenType>,
+    /// The number of delimiters opened by the tokens consumed so far which
+    /// have not been closed yet. Used to find where to carry on after a
+    /// syntax error.
+    pub delim_depth: usize,
 }
 
 #[derive(PartialEq, Eq, Clone)]
@@ -303,6 +307,15 @@ fn is_plain_ident_or_underscore(t: &token::Token) -> bool {
     t.is_plain_ident() || *t == token::Underscore
 }
 
+/// The value of `Parser::delim_depth` once `t` has been consumed.
+fn delim_depth_after(depth: usize, t: &token::Token) -> usize {
+    match *t {
+        token::OpenDelim(_) => depth + 1,
+        token::CloseDelim(_) => depth.saturating_sub(1),
+        _ => depth,
+    }
+}
+
 /// Information about the path to a module.
 pub struct ModulePath {
     pub name: String,
@@ -381,6 +394,7 @@ impl<'a> Parser<'a> {
             owns_directory: true,
             root_module_name: None,
             expected_tokens: Vec::new(),
+            delim_depth: 0,
         }
     }
 
@@ -920,6 +934,7 @@ impl<'a> Parser<'a> {
 
     /// Advance the parser by one token
     pub fn bump(&mut self) -> PResult<()> {
+        self.delim_depth = delim_depth_after(self.delim_depth, &self.token);
         self.last_span = self.span;
         // Stash token for error recovery (sometimes; clone is not necessarily cheap).
         self.last_token = if self.token.is_ident() ||
@@ -954,6 +969,7 @@ impl<'a> Parser<'a> {
     /// Advance the parser by one token and return the bumped token.
     pub fn bump_and_get(&mut self) -> PResult<token::Token> {
         let old_token = mem::replace(&mut self.token, token::Underscore);
+        self.delim_depth = delim_depth_after(self.delim_depth, &old_token);
         try!(self.bump());
         Ok(old_token)
     }
@@ -3758,78 +3774,18 @@ impl<'a> Parser<'a> {
         let mut expr = None;
 
         while !try!(self.eat(&token::CloseDelim(token::Brace))) {
-            let Spanned {node, span} = if let Some(s) = try!(self.parse_stmt_()) {
-                s
-            } else {
-                // Found only `;` or `}`.
-                continue;
-            };
-            match node {
-                StmtExpr(e, _) => {
-                    try!(self.handle_expression_like_statement(e, span, &mut stmts, &mut expr));
-                }
-                StmtMac(mac, MacStmtWithoutBraces, attrs) => {
-                    // statement macro without braces; might be an
-                    // expr depending on whether a semicolon follows
-                    match self.token {
-                        token::Semi => {
-                            stmts.push(P(Spanned {
-                                node: StmtMac(mac, MacStmtWithSemicolon, attrs),
-                                span: mk_sp(span.lo, self.span.hi),
-                            }));
-                            try!(self.bump());
-                        }
-                        _ => {
-                            let e = self.mk_mac_expr(span.lo, span.hi,
-                                                     mac.and_then(|m| m.node),
-                                                     None);
-                            let e = try!(self.parse_dot_or_call_expr_with(e, attrs));
-                            let e = try!(self.parse_assoc_expr_with(0, LhsExpr::AlreadyParsed(e)));
-                            try!(self.handle_expression_like_statement(
-                                e,
-                                span,
-                                &mut stmts,
-                                &mut expr));
-                        }
-                    }
-                }
-                StmtMac(m, style, attrs) => {
-                    // statement macro; might be an expr
-                    match self.token {
-                        token::Semi => {
-                            stmts.push(P(Spanned {
-                                node: StmtMac(m, MacStmtWithSemicolon, attrs),
-                                span: mk_sp(span.lo, self.span.hi),
-                            }));
-                            try!(self.bump());
-                        }
-                        token::CloseDelim(token::Brace) => {
-                            // if a block ends in `m!(arg)` without
-                            // a `;`, it must be an expr
-                            expr = Some(self.mk_mac_expr(span.lo, span.hi,
-                                                         m.and_then(|x| x.node),
-                                                         attrs));
-                        }
-                        _ => {
-                            stmts.push(P(Spanned {
-                                node: StmtMac(m, style, attrs),
-                                span: span
-                            }));
-                        }
-                    }
-                }
-                _ => { // all other kinds of statements:
-                    let mut hi = span.hi;
-                    if classify::stmt_ends_with_semi(&node) {
-                        try!(self.commit_stmt_expecting(token::Semi));
-                        hi = self.last_span.hi;
-                    }
-
-                    stmts.push(P(Spanned {
-                        node: node,
-                        span: mk_sp(span.lo, hi)
-                    }));
+            let lo = self.span.lo;
+            let depth = self.delim_depth;
+            let start = self.tokens_consumed;
+            let err_count = self.sess.span_diagnostic.handler().err_count();
+            let restrictions = self.restrictions;
+            if let Err(e) = self.parse_block_stmt(&mut stmts, &mut expr) {
+                self.restrictions = restrictions;
+                self.skip_after_error(depth, start, err_count, true);
+                if self.tokens_consumed == start || self.token == token::Eof {
+                    return Err(e);
                 }
+                stmts.push(P(spanned(lo, self.last_span.hi, StmtError)));
             }
         }
 
@@ -3842,6 +3798,83 @@ impl<'a> Parser<'a> {
         }))
     }
 
+    /// Parse a statement of a block, adding it to `stmts`, or to `expr` if it
+    /// is the trailing expression of the block.
+    fn parse_block_stmt(&mut self,
+                        stmts: &mut Vec<P<Stmt>>,
+                        expr: &mut Option<P<Expr>>) -> PResult<()> {
+        let Spanned {node, span} = if let Some(s) = try!(self.parse_stmt_()) {
+            s
+        } else {
+            // Found only `;` or `}`.
+            return Ok(());
+        };
+        match node {
+            StmtExpr(e, _) => {
+                try!(self.handle_expression_like_statement(e, span, stmts, expr));
+            }
+            StmtMac(mac, MacStmtWithoutBraces, attrs) => {
+                // statement macro without braces; might be an
+                // expr depending on whether a semicolon follows
+                match self.token {
+                    token::Semi => {
+                        stmts.push(P(Spanned {
+                            node: StmtMac(mac, MacStmtWithSemicolon, attrs),
+                            span: mk_sp(span.lo, self.span.hi),
+                        }));
+                        try!(self.bump());
+                    }
+                    _ => {
+                        let e = self.mk_mac_expr(span.lo, span.hi,
+                                                 mac.and_then(|m| m.node),
+                                                 None);
+                        let e = try!(self.parse_dot_or_call_expr_with(e, attrs));
+                        let e = try!(self.parse_assoc_expr_with(0, LhsExpr::AlreadyParsed(e)));
+                        try!(self.handle_expression_like_statement(e, span, stmts, expr));
+                    }
+                }
+            }
+            StmtMac(m, style, attrs) => {
+                // statement macro; might be an expr
+                match self.token {
+                    token::Semi => {
+                        stmts.push(P(Spanned {
+                            node: StmtMac(m, MacStmtWithSemicolon, attrs),
+                            span: mk_sp(span.lo, self.span.hi),
+                        }));
+                        try!(self.bump());
+                    }
+                    token::CloseDelim(token::Brace) => {
+                        // if a block ends in `m!(arg)` without
+                        // a `;`, it must be an expr
+                        *expr = Some(self.mk_mac_expr(span.lo, span.hi,
+                                                      m.and_then(|x| x.node),
+                                                      attrs));
+                    }
+                    _ => {
+                        stmts.push(P(Spanned {
+                            node: StmtMac(m, style, attrs),
+                            span: span
+                        }));
+                    }
+                }
+            }
+            _ => { // all other kinds of statements:
+                let mut hi = span.hi;
+                if classify::stmt_ends_with_semi(&node) {
+                    try!(self.commit_stmt_expecting(token::Semi));
+                    hi = self.last_span.hi;
+                }
+
+                stmts.push(P(Spanned {
+                    node: node,
+                    span: mk_sp(span.lo, hi)
+                }));
+            }
+        }
+        Ok(())
+    }
+
     fn handle_expression_like_statement(
             &mut self,
             e: P<Expr>,
@@ -4938,16 +4971,93 @@ impl<'a> Parser<'a> {
         else { Ok(Inherited) }
     }
 
+    /// Skips the rest of an item or statement which failed to parse, so that
+    /// parsing can carry on with the next one. `depth` and `start` are the
+    /// values of `delim_depth` and `tokens_consumed` when the item or
+    /// statement started, and `err_count` the number of errors reported by
+    /// then.
+    ///
+    /// Stops after a `;` or a block closed at that depth, or before an item
+    /// keyword (or `let`, if `in_block`) at that depth or the delimiter
+    /// closing the enclosing block.
+    fn skip_after_error(&mut self, depth: usize, start: usize, err_count: usize, in_block: bool) {
+        // Most syntax errors are reported as fatal errors, which do not count
+        // towards the total. Make sure compilation stops after parsing now
+        // that the error is no longer fatal.
+        {
+            let handler = self.sess.span_diagnostic.handler();
+            if handler.err_count() == err_count {
+                handler.bump_err_count();
+            }
+        }
+
+        loop {
+            match self.token {
+                token::Eof => return,
+                token::CloseDelim(_) if self.delim_depth <= depth => return,
+                token::Semi if self.delim_depth == depth => {
+                    let _ = self.bump();
+                    return;
+                }
+                _ => {}
+            }
+            if self.delim_depth == depth && self.tokens_consumed != start &&
+               (self.token_starts_item() || in_block && self.token.is_keyword(keywords::Let)) {
+                return;
+            }
+            let closes_block = self.delim_depth == depth + 1 &&
+                               self.token == token::CloseDelim(token::Brace);
+            let _ = self.bump();
+            if closes_block {
+                return;
+            }
+        }
+    }
+
+    /// Whether the current token can only be the start of an item.
+    fn token_starts_item(&self) -> bool {
+        self.token == token::Pound ||
+        [keywords::Fn, keywords::Struct, keywords::Enum, keywords::Impl, keywords::Trait,
+         keywords::Mod, keywords::Use, keywords::Static, keywords::Const, keywords::Type,
+         keywords::Extern, keywords::Pub].iter().any(|&kw| self.token.is_keyword(kw))
+    }
+
     /// Given a termination token, parse all of the items in a module
     fn parse_mod_items(&mut self, term: &token::Token, inner_lo: BytePos) -> PResult<Mod> {
         let mut items = vec![];
-        while let Some(item) = try!(self.parse_item()) {
-            items.push(item);
-        }
-
-        if !try!(self.eat(term)) {
-            let token_str = self.this_token_to_string();
-            return Err(self.fatal(&format!("expected item, found `{}`", token_str)));
+        loop {
+            let lo = self.span.lo;
+            let depth = self.delim_depth;
+            let start = self.tokens_consumed;
+            let err_count = self.sess.span_diagnostic.handler().err_count();
+            let restrictions = self.restrictions;
+            let err = match self.parse_item() {
+                Ok(Some(item)) => {
+                    items.push(item);
+                    continue;
+                }
+                Ok(None) => {
+                    if try!(self.eat(term)) {
+                        break;
+                    }
+                    let token_str = self.this_token_to_string();
+                    self.fatal(&format!("expected item, found `{}`", token_str))
+                }
+                Err(e) => e,
+            };
+            self.restrictions = restrictions;
+            self.skip_after_error(depth, start, err_count, false);
+            if self.tokens_consumed == start {
+                return Err(err);
+            }
+            items.push(P(ast::Item {
+                ident: special_idents::invalid,
+                attrs: vec![],
+                id: ast::DUMMY_NODE_ID,
+                node: ItemError,
+                vis: Inherited,
+                span: mk_sp(lo, self.last_span.hi),
+            }));
         }
 
         let hi = if self.span == codemap::DUMMY_SP {
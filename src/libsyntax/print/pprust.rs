@@ -1337,6 +1337,9 @@ impl<'a> State<'a> {
                 try!(word(&mut self.s, ";"));
                 try!(self.end());
             }
+            ast::ItemError => {
+                try!(word(&mut self.s, "/* error */"));
+            }
         }
         self.ann.post(self, NodeItem(item))
     }
@@ -1647,6 +1650,10 @@ impl<'a> State<'a> {
                     _ => try!(word(&mut self.s, ";")),
                 }
             }
+            ast::StmtError => {
+                try!(self.space_if_not_bol());
+                try!(word(&mut self.s, "/* error */"));
+            }
         }
         if parse::classify::stmt_ends_with_semi(&st.node) {
             try!(word(&mut self.s, ";"));
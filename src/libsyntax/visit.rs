@@ -304,6 +304,7 @@ pub fn walk_item<'v, V: Visitor<'v>>(visitor: &mut V, item: &'v Item) {
             walk_list!(visitor, visit_trait_item, methods);
         }
         ItemMac(ref mac) => visitor.visit_mac(mac),
+        ItemError => {}
     }
     walk_list!(visitor, visit_attribute, &item.attrs);
 }
@@ -635,6 +636,7 @@ pub fn walk_stmt<'v, V: Visitor<'v>>(visitor: &mut V, statement: &'v Stmt) {
                 visitor.visit_attribute(attr);
             }
         }
+        StmtError => {}
     }
 }
 
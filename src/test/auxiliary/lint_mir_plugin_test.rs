@@ -0,0 +1,56 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// force-host
+
+#![feature(plugin_registrar)]
+#![feature(box_syntax, rustc_private)]
+
+extern crate syntax;
+
+// Load rustc as a plugin to get macros
+#[macro_use]
+extern crate rustc;
+extern crate rustc_plugin;
+
+use rustc::lint::{MirContext, LintPass, MirLintPass, MirLintPassObject, LintArray};
+use rustc::mir::repr::*;
+use rustc_plugin::Registry;
+
+declare_lint!(TEST_MIR_LINT, Warn, "Warn about calls to fns named 'lintme'");
+
+struct Pass;
+
+impl LintPass for Pass {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(TEST_MIR_LINT)
+    }
+}
+
+impl MirLintPass for Pass {
+    fn check_mir<'a, 'tcx>(&mut self, cx: &MirContext<'a, 'tcx>, mir: &Mir<'tcx>) {
+        for bb in mir.all_basic_blocks() {
+            if let Terminator::Call { ref data, .. } = mir.basic_block_data(bb).terminator {
+                if let Operand::Constant(Constant {
+                    span, literal: Literal::Item { def_id, .. }, ..
+                }) = data.func {
+                    if cx.tcx.item_name(def_id).as_str() == "lintme" {
+                        cx.span_lint(TEST_MIR_LINT, span, "call to fn named 'lintme'");
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[plugin_registrar]
+pub fn plugin_registrar(reg: &mut Registry) {
+    reg.register_mir_lint_pass(box Pass as MirLintPassObject);
+}
@@ -0,0 +1,39 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(overflow_checks)]
+
+pub struct Wrapping;
+
+#[overflow_checks(off)]
+impl Wrapping {
+    #[inline]
+    pub fn add(&self, a: u8, b: u8) -> u8 {
+        a + b
+    }
+}
+
+#[overflow_checks(off)]
+pub mod hot {
+    pub fn shl<T: Into<u32>>(a: T, b: u32) -> u32 {
+        a.into() << b
+    }
+
+    #[inline]
+    #[overflow_checks(on)]
+    pub fn sub(a: u64, b: u64) -> u64 {
+        a - b
+    }
+}
+
+#[inline]
+pub fn add_default(a: u8, b: u8) -> u8 {
+    a + b
+}
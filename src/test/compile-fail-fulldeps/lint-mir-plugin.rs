@@ -0,0 +1,33 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:lint_mir_plugin_test.rs
+// ignore-stage1
+
+#![feature(plugin)]
+#![plugin(lint_mir_plugin_test)]
+#![deny(test_mir_lint)]
+
+fn lintme() { }
+
+fn denied() {
+    lintme(); //~ ERROR call to fn named 'lintme'
+}
+
+#[allow(test_mir_lint)]
+fn allowed() {
+    lintme();
+}
+
+pub fn main() {
+    denied();
+    allowed();
+    let _ = || lintme(); //~ ERROR call to fn named 'lintme'
+}
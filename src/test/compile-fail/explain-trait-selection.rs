@@ -0,0 +1,33 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z explain-trait-selection
+
+// Check that `-Z explain-trait-selection` lists the impls which were
+// considered for an obligation which does not hold.
+
+trait Serialize {}
+
+struct Wrapper<T>(T);
+struct Foo;
+
+impl Serialize for Wrapper<u32> {}
+//~^ NOTE impl `Serialize` for `Wrapper<u32>` does not match
+
+impl<T: Serialize> Serialize for Vec<T> {}
+
+fn serialize<T: Serialize>(_: T) {}
+
+fn main() {
+    let v: Vec<Wrapper<Foo>> = Vec::new();
+    serialize(v);
+    //~^ ERROR the trait `Serialize` is not implemented for the type `Wrapper<Foo>`
+    //~| NOTE candidates considered for `Wrapper<Foo>: Serialize`
+}
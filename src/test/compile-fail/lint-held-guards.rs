@@ -0,0 +1,95 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(held_guards)]
+#![allow(dead_code, unused_variables)]
+
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex, RwLock};
+
+fn relock(m: &Mutex<i32>) {
+    let guard = m.lock().unwrap();
+    let again = m.lock().unwrap(); //~ ERROR lock acquired while an exclusive guard
+}
+
+fn relock_arc(m: Arc<Mutex<i32>>) {
+    let guard = m.lock().unwrap();
+    *m.lock().unwrap() += 1; //~ ERROR lock acquired while an exclusive guard
+}
+
+fn relock_in_match(m: &Mutex<Option<i32>>) {
+    match *m.lock().unwrap() {
+        Some(_) => {
+            m.lock().unwrap(); //~ ERROR lock acquired while an exclusive guard
+        }
+        None => {}
+    }
+}
+
+fn read_while_written(l: &RwLock<i32>) {
+    let w = l.write().unwrap();
+    let r = l.read().unwrap(); //~ ERROR lock acquired while an exclusive guard
+}
+
+fn maybe_dropped(m: &Mutex<i32>, c: bool) {
+    let guard = m.lock().unwrap();
+    if c {
+        drop(guard);
+    }
+    m.lock().unwrap(); //~ ERROR lock acquired while an exclusive guard
+}
+
+fn reborrow(c: &RefCell<Vec<i32>>) {
+    let mut v = c.borrow_mut();
+    v.push(c.borrow().len()); //~ ERROR `RefCell` borrowed while a `RefMut` for it
+}
+
+// None of these should warn.
+
+fn dropped(m: &Mutex<i32>) {
+    let guard = m.lock().unwrap();
+    drop(guard);
+    m.lock().unwrap();
+}
+
+fn scoped(m: &Mutex<i32>) {
+    {
+        let guard = m.lock().unwrap();
+    }
+    m.lock().unwrap();
+}
+
+fn statement_temporary(m: &Mutex<i32>) {
+    *m.lock().unwrap() += 1;
+    *m.lock().unwrap() += 1;
+}
+
+fn other_lock(a: &Mutex<i32>, b: &Mutex<i32>) {
+    let guard = a.lock().unwrap();
+    b.lock().unwrap();
+}
+
+fn readers(l: &RwLock<i32>) {
+    let a = l.read().unwrap();
+    let b = l.read().unwrap();
+}
+
+fn shared_borrows(c: &RefCell<i32>) {
+    let a = c.borrow();
+    let b = c.borrow();
+}
+
+#[allow(relock_while_held)]
+fn allowed(m: &Mutex<i32>) {
+    let guard = m.lock().unwrap();
+    m.lock().unwrap();
+}
+
+fn main() {}
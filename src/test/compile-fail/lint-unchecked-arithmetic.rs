@@ -0,0 +1,50 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(unchecked_arithmetic)]
+#![allow(dead_code)]
+
+const LIMIT: u32 = 1000;
+
+fn unbounded(a: u32, b: u32, c: i64, n: usize) {
+    let _ = a + b; //~ ERROR `+` on `u32` may overflow
+    //~^ HELP use `checked_add`, `wrapping_add` or `saturating_add`
+    let _ = a - 1; //~ ERROR `-` on `u32` may overflow
+    //~^ HELP use `checked_sub`, `wrapping_sub` or `saturating_sub`
+    let _ = c * 2; //~ ERROR `*` on `i64` may overflow
+    //~^ HELP use `checked_mul`, `wrapping_mul` or `saturating_mul`
+    let mut d = a;
+    d += b; //~ ERROR `+` on `u32` may overflow
+    //~^ HELP use `checked_add`
+    let _ = a as u8; //~ ERROR cast from `u32` to `u8` may truncate or wrap
+    //~^ HELP check that the value is within the range of `u8` first
+    let _ = c as u64; //~ ERROR cast from `i64` to `u64` may truncate or wrap
+    //~^ HELP check that the value is within the range of `u64` first
+    let _ = n as u32; //~ ERROR cast from `usize` to `u32` may truncate or wrap
+    //~^ HELP check that the value is within the range of `u32` first
+}
+
+fn bounded(a: u8, b: u8, c: u32) {
+    let _ = a as u32 + b as u32;
+    let _ = a as u32 * b as u32 + LIMIT;
+    let _ = (c & 0xff) + 1;
+    let _ = (c % 100) * 1000;
+    let _ = LIMIT - 1;
+    let _ = c as u64;
+    let _ = a as i16 - 255;
+    let _ = (c & 0x7f) as u8;
+}
+
+#[allow(unchecked_arithmetic)]
+fn allowed(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+fn main() {}
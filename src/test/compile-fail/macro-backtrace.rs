@@ -0,0 +1,29 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z macro-backtrace
+
+// Check that `-Z macro-backtrace` shows every invocation of a nested macro
+// and the `macro_rules!` arm each of them matched.
+
+macro_rules! inner {
+    (fail) => { missing_fn() }; //~ ERROR unresolved name `missing_fn`
+    //~^ NOTE which matched this arm of inner!
+    ($e:expr) => { $e };
+}
+
+macro_rules! outer {
+    () => { inner!(fail) }; //~ NOTE in this expansion of inner!
+    //~^ NOTE which matched this arm of outer!
+}
+
+fn main() {
+    outer!(); //~ NOTE in this expansion of outer!
+}
@@ -0,0 +1,31 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the MIR interpreter reports the checks emitted by the MIR
+// builder which fail inside a `const fn`, at the expression checked.
+
+// compile-flags: -Z mir-const-check
+
+#![feature(const_fn)]
+
+const fn div(x: u32) -> u32 {
+    x / 0 //~ ERROR constant evaluation error: attempted to divide by zero
+}
+
+const fn get(i: usize) -> u8 {
+    [1, 2, 3][i] //~ ERROR constant evaluation error: index out of bounds
+}
+
+const QUOTIENT: u32 = div(1); //~ NOTE inside call to `div`
+const FOURTH: u8 = get(3); //~ NOTE inside call to `get`
+
+fn main() {
+    let _ = (QUOTIENT, FOURTH);
+}
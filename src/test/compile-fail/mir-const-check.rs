@@ -0,0 +1,36 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the MIR interpreter reports errors inside the `const fn`s
+// called by a constant, along with the chain of calls.
+
+// compile-flags: -Z mir-const-check
+
+#![feature(const_fn)]
+
+struct Pair {
+    a: u8,
+    b: u8,
+}
+
+const fn pair(a: u8) -> Pair {
+    Pair { a: a, b: a * 2 } //~ ERROR constant evaluation error: attempted to multiply with overflow
+}
+
+const fn wrap(a: u8) -> Pair {
+    pair(a) //~ NOTE inside call to `pair`
+}
+
+const SMALL: Pair = pair(100);
+const LARGE: Pair = wrap(200); //~ NOTE inside call to `wrap`
+
+fn main() {
+    let _ = (SMALL.a, SMALL.b, LARGE.a, LARGE.b);
+}
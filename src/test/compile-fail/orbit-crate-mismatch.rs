@@ -0,0 +1,18 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:orbit_crate.rs
+
+// Check that a crate compiled without `-Z orbit`, which relies on drop
+// flags, cannot use a crate whose types have none.
+
+extern crate orbit_crate; //~ ERROR has been compiled with `-Z orbit`
+
+fn main() {}
@@ -0,0 +1,31 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(overflow_checks, stmt_expr_attributes)]
+
+#[overflow_checks(off)] //~ ERROR attribute should be applied to function, impl or module
+struct Foo;
+
+#[overflow_checks(maybe)] //~ ERROR invalid argument
+fn bar() {}
+
+#[overflow_checks] //~ ERROR expected one argument, `on` or `off`
+mod baz {}
+
+impl Foo {
+    #[overflow_checks(on, off)] //~ ERROR expected one argument, `on` or `off`
+    fn qux(&self) {}
+}
+
+fn main() {
+    let f = #[overflow_checks(off)] |a: u8| a + 1;
+    //~^ ERROR attribute should be applied to function, impl or module
+    f(255);
+}
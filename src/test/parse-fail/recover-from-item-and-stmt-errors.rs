@@ -0,0 +1,39 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z parse-only
+
+// Check that the parser carries on after a syntax error, reporting every
+// error in the file at once.
+
+struct S {
+    a: u32
+    b: u32, //~ ERROR expected
+}
+
+fn first() {
+    let x: [isize 3]; //~ ERROR expected one of
+    let y = 1;
+    x + y
+}
+
+fn second(a: u32 b: u32) {} //~ ERROR expected one of
+
+mod m {
+    fn f() {}
+    fn g() -> {} //~ ERROR expected type, found `{`
+}
+
+fn third() {
+    let z = 4 5; //~ ERROR expected one of
+    z
+}
+
+fn main() {}
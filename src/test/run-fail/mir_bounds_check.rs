@@ -0,0 +1,24 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z orbit
+// error-pattern:index out of bounds: the len is 3 but the index is 5
+// error-pattern:mir_bounds_check.rs:19
+
+// Check that a failed bounds check in a MIR-translated fn reports the
+// length and index, and the line of the indexing.
+
+fn get(v: &[u8], i: usize) -> u8 {
+    v[i]
+}
+
+fn main() {
+    get(&[1, 2, 3], 5);
+}
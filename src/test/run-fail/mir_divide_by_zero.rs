@@ -0,0 +1,23 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z orbit
+// error-pattern:attempted to divide by zero
+
+// Check that MIR-translated integer division checks for a zero divisor,
+// even without overflow checks.
+
+fn div(a: u32, b: u32) -> u32 {
+    a / b
+}
+
+fn main() {
+    div(1, 0);
+}
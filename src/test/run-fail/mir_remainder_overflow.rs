@@ -0,0 +1,25 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z orbit -C debug-assertions=no
+// error-pattern:attempted remainder with overflow
+
+// Check that MIR-translated signed remainder checks for `MIN % -1`, even
+// without overflow checks.
+
+use std::i32;
+
+fn rem(a: i32, b: i32) -> i32 {
+    a % b
+}
+
+fn main() {
+    rem(i32::MIN, -1);
+}
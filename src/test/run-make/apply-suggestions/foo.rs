@@ -0,0 +1,43 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+mod shapes {
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    impl Area for f32 {
+        fn area(&self) -> f32 {
+            *self * *self
+        }
+    }
+}
+
+struct BuildData {
+    foo: isize,
+    bar: f32,
+}
+
+fn takes_ref(x: &f32) -> f32 {
+    *x
+}
+
+fn main() {
+    let data = BuildData {
+        foo: 0,
+        baz: 0.5,
+    };
+    let value = data.baa;
+    println!("{}", value);
+    let side = 2.0f32;
+    let total = takes_ref(side) + side.area();
+    println!("{}", total);
+    assert(total > 0.0);
+}
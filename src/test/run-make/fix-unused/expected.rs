@@ -0,0 +1,29 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::HashSet;
+use std::collections::LinkedList;
+use std::io::Write;
+
+mod inner {
+    pub fn helper() -> usize { 1 }
+}
+
+use inner::helper;
+
+fn main() {
+    let mut set = HashSet::new();
+    set.insert(helper());
+    let _ = helper();
+    let mut list = LinkedList::new();
+    list.push_back(1);
+    let mut out = std::io::stdout();
+    out.flush().unwrap();
+}
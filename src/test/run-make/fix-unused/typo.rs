@@ -0,0 +1,28 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// `-Z fix-unused` must only write back the unused import fixes, never the
+// "did you mean" suggestion for the misspelt field.
+
+use std::fmt;
+
+struct BuildData {
+    foo: isize,
+    bar: f32,
+}
+
+fn main() {
+    let data = BuildData {
+        foo: 0,
+        bar: 0.5,
+    };
+    let value = data.baa;
+    println!("{}", value);
+}
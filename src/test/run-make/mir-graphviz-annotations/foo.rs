@@ -0,0 +1,37 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_attrs)]
+
+#[rustc_mir(graphviz="foo.gv", annotate="temps,scopes,live,init")]
+fn annotated(n: u32) -> String {
+    let mut s = String::new();
+    for _ in 0..n {
+        let piece = format!("{}", n);
+        s.push_str(&piece);
+    }
+    s
+}
+
+#[rustc_mir(graphviz="plain.gv")]
+fn plain(n: u32) -> u32 {
+    n + 1
+}
+
+#[rustc_mir(graphviz="scoped.gv", annotate="scopes")]
+fn scoped(n: u32) -> u32 {
+    n + 1
+}
+
+fn main() {
+    assert_eq!(annotated(2), "22");
+    assert_eq!(plain(1), 2);
+    assert_eq!(scoped(1), 2);
+}
@@ -0,0 +1,22 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate upstream;
+
+use upstream::Scale;
+
+fn caller(x: u32) -> u32 {
+    upstream::double(x) + upstream::opaque(x)
+}
+
+fn main() {
+    assert_eq!(caller(3), 10);
+    assert_eq!(().scale(3), 12);
+}
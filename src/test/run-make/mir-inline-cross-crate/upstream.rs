@@ -0,0 +1,44 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "rlib"]
+
+#[inline]
+pub fn double(x: u32) -> u32 {
+    helper(x) * 2
+}
+
+fn helper(x: u32) -> u32 {
+    x
+}
+
+pub fn opaque(x: u32) -> u32 {
+    x + 1
+}
+
+// The MIR of default methods is exported, so `scale` must keep calling
+// `scale_by` rather than take in the call to `secret`, which has no symbol
+// other crates can link to.
+pub trait Scale {
+    fn scale(&self, x: u32) -> u32 {
+        scale_by(x, 4)
+    }
+}
+
+impl Scale for () {}
+
+fn scale_by(x: u32, factor: u32) -> u32 {
+    secret(x) * factor
+}
+
+#[inline(never)]
+fn secret(x: u32) -> u32 {
+    x
+}
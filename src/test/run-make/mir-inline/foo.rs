@@ -0,0 +1,38 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "rlib"]
+
+trait Half {
+    fn half(self) -> Self;
+}
+
+impl Half for u32 {
+    fn half(self) -> u32 {
+        self / 2
+    }
+}
+
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+#[inline(never)]
+fn opaque(x: u32) -> u32 {
+    x + 1
+}
+
+fn countdown(x: u32) -> u32 {
+    if x == 0 { 0 } else { countdown(x - 1) }
+}
+
+pub fn caller(x: u32) -> u32 {
+    double(x) + Half::half(x) + opaque(x) + countdown(x)
+}
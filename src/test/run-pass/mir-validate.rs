@@ -0,0 +1,64 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the MIR of matches, loops, calls and temps with destructors
+// is valid after building it and after every MIR pass.
+
+// compile-flags: -Z validate-mir -C opt-level=2
+
+enum Shape {
+    Circle(u32),
+    Rect { w: u32, h: u32 },
+    Empty,
+}
+
+fn area(shape: &Shape) -> u32 {
+    match *shape {
+        Shape::Circle(r) => 3 * r * r,
+        Shape::Rect { w, h } => w * h,
+        Shape::Empty => 0,
+    }
+}
+
+fn first_even(xs: &[u32]) -> Option<u32> {
+    for &x in xs {
+        if x % 2 == 0 {
+            return Some(x);
+        }
+    }
+    None
+}
+
+fn describe(n: u8) -> String {
+    match n {
+        0 => "zero".to_string(),
+        1 | 2 => format!("small {}", n),
+        _ => {
+            let mut s = String::new();
+            for _ in 0..n {
+                s.push('*');
+            }
+            s
+        }
+    }
+}
+
+fn main() {
+    let shapes = vec![Shape::Circle(1), Shape::Rect { w: 2, h: 3 }, Shape::Empty];
+    let total = shapes.iter().map(area).fold(0, |sum, a| sum + a);
+    assert_eq!(total, 9);
+    assert_eq!(first_even(&[1, 3, 4, 5]), Some(4));
+    assert_eq!(first_even(&[]), None);
+    assert_eq!(describe(0), "zero");
+    assert_eq!(describe(2), "small 2");
+    assert_eq!(describe(3), "***");
+    let array = [describe(1), describe(4)];
+    assert_eq!(array[1].len(), 4);
+}
@@ -0,0 +1,76 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z orbit -Z validate-mir
+
+// Check that elaborated drops run exactly once, whether the value was moved
+// out of unconditionally, conditionally or not at all.
+
+use std::cell::Cell;
+
+struct Noisy<'a>(&'a Cell<usize>);
+
+impl<'a> Drop for Noisy<'a> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn consume(_n: Noisy) {}
+
+fn never_moved(drops: &Cell<usize>) {
+    let _a = Noisy(drops);
+}
+
+fn always_moved(drops: &Cell<usize>) {
+    let a = Noisy(drops);
+    consume(a);
+}
+
+fn maybe_moved(drops: &Cell<usize>, cond: bool) {
+    let a = Noisy(drops);
+    if cond {
+        consume(a);
+    }
+}
+
+fn maybe_initialized(drops: &Cell<usize>, cond: bool) {
+    let a;
+    if cond {
+        a = Noisy(drops);
+    }
+}
+
+fn moved_arg(n: Noisy, cond: bool) {
+    if cond {
+        consume(n);
+    }
+}
+
+fn main() {
+    let drops = Cell::new(0);
+
+    never_moved(&drops);
+    assert_eq!(drops.get(), 1);
+    always_moved(&drops);
+    assert_eq!(drops.get(), 2);
+    maybe_moved(&drops, true);
+    assert_eq!(drops.get(), 3);
+    maybe_moved(&drops, false);
+    assert_eq!(drops.get(), 4);
+    maybe_initialized(&drops, true);
+    assert_eq!(drops.get(), 5);
+    maybe_initialized(&drops, false);
+    assert_eq!(drops.get(), 5);
+    moved_arg(Noisy(&drops), true);
+    assert_eq!(drops.get(), 6);
+    moved_arg(Noisy(&drops), false);
+    assert_eq!(drops.get(), 7);
+}
@@ -0,0 +1,48 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-inline
+
+// Check that fns into which calls were inlined run correctly. They are
+// translated through the MIR even without `-Z orbit`, and callees which
+// return from several branches write their result into a temp of the
+// caller.
+
+trait Half {
+    fn half(self) -> Self;
+}
+
+impl Half for u32 {
+    fn half(self) -> u32 {
+        self / 2
+    }
+}
+
+fn pick(c: bool) -> u32 {
+    if c { 10 } else { 20 }
+}
+
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+fn first<T: Copy>(pair: (T, T)) -> T {
+    pair.0
+}
+
+fn caller(c: bool, x: u32) -> u32 {
+    add(pick(c), Half::half(x)) + first((x, 0))
+}
+
+fn main() {
+    assert_eq!(caller(true, 8), 10 + 4 + 8);
+    assert_eq!(caller(false, 8), 20 + 4 + 8);
+    assert_eq!(pick(true), 10);
+}
@@ -0,0 +1,44 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z orbit
+
+// Check that the types of a crate translated through the MIR have no
+// drop-flag fields, and that their values are still dropped exactly once.
+
+use std::cell::Cell;
+use std::mem::size_of;
+
+struct Noisy<'a>(&'a Cell<u32>);
+
+impl<'a> Drop for Noisy<'a> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn consume(_: Noisy) {}
+
+fn maybe_consume(c: bool, drops: &Cell<u32>) {
+    let n = Noisy(drops);
+    if c {
+        consume(n);
+    }
+}
+
+fn main() {
+    assert_eq!(size_of::<Noisy>(), size_of::<&Cell<u32>>());
+
+    let drops = Cell::new(0);
+    maybe_consume(true, &drops);
+    assert_eq!(drops.get(), 1);
+    maybe_consume(false, &drops);
+    assert_eq!(drops.get(), 2);
+}
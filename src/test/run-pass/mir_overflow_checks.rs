@@ -0,0 +1,83 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z orbit -C debug-assertions=no
+
+// Check that MIR-translated fns honour `#[overflow_checks(on)]`, and panic
+// with the same messages as the old trans.
+
+#![feature(overflow_checks)]
+
+use std::thread;
+
+#[overflow_checks(on)]
+mod checked {
+    pub fn add_u8(a: u8, b: u8) -> u8 { a + b }
+    pub fn add_i8(a: i8, b: i8) -> i8 { a + b }
+    pub fn sub_u8(a: u8, b: u8) -> u8 { a - b }
+    pub fn sub_i8(a: i8, b: i8) -> i8 { a - b }
+    pub fn mul_u8(a: u8, b: u8) -> u8 { a * b }
+    pub fn mul_i32(a: i32, b: i32) -> i32 { a * b }
+    pub fn neg_i8(a: i8) -> i8 { -a }
+    pub fn shr_i64(a: i64, b: i32) -> i64 { a >> b }
+    pub fn add_assign(a: usize, b: usize) -> usize {
+        let mut c = a;
+        c += b;
+        c
+    }
+}
+
+fn unchecked_add(a: u8, b: u8) -> u8 {
+    a + b
+}
+
+const ARITH: &'static str = "arithmetic operation overflowed";
+const NEG: &'static str = "attempted to negate with overflow";
+const SHIFT: &'static str = "shift operation overflowed";
+
+fn panics_with<F: FnOnce() + Send + 'static>(msg: &str, f: F) -> bool {
+    match thread::spawn(f).join() {
+        Ok(()) => false,
+        Err(payload) => *payload.downcast::<String>().unwrap() == msg,
+    }
+}
+
+fn main() {
+    use checked::*;
+    use std::{i8, i32, usize};
+
+    assert_eq!(add_u8(254, 1), 255);
+    assert_eq!(add_i8(-128, 127), -1);
+    assert_eq!(sub_u8(1, 1), 0);
+    assert_eq!(sub_i8(-1, 127), -128);
+    assert_eq!(mul_u8(0, 255), 0);
+    assert_eq!(mul_u8(15, 17), 255);
+    assert_eq!(mul_i32(-1, i32::MAX), -i32::MAX);
+    assert_eq!(mul_i32(i32::MIN, 1), i32::MIN);
+    assert_eq!(neg_i8(i8::MAX), -i8::MAX);
+    assert_eq!(shr_i64(-8, 63), -1);
+    assert_eq!(add_assign(usize::MAX - 1, 1), usize::MAX);
+    assert_eq!(unchecked_add(255, 1), 0);
+
+    assert!(panics_with(ARITH, || { add_u8(255, 1); }));
+    assert!(panics_with(ARITH, || { add_i8(127, 1); }));
+    assert!(panics_with(ARITH, || { add_i8(-128, -1); }));
+    assert!(panics_with(ARITH, || { sub_u8(0, 1); }));
+    assert!(panics_with(ARITH, || { sub_i8(-128, 1); }));
+    assert!(panics_with(ARITH, || { sub_i8(127, -1); }));
+    assert!(panics_with(ARITH, || { mul_u8(16, 16); }));
+    assert!(panics_with(ARITH, || { mul_i32(-1, i32::MIN); }));
+    assert!(panics_with(ARITH, || { mul_i32(i32::MIN, -1); }));
+    assert!(panics_with(ARITH, || { mul_i32(65536, 65536); }));
+    assert!(panics_with(NEG, || { neg_i8(i8::MIN); }));
+    assert!(panics_with(SHIFT, || { shr_i64(1, 64); }));
+    assert!(panics_with(SHIFT, || { shr_i64(1, -1); }));
+    assert!(panics_with(ARITH, || { add_assign(usize::MAX, 1); }));
+}
@@ -0,0 +1,93 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_attrs)]
+
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Point,
+    Circle(u32),
+    Rect(u32, u32),
+}
+
+#[derive(Debug, PartialEq)]
+struct Pair(u8, u64);
+
+trait Area {
+    fn area(&self) -> u32;
+    fn unit() -> Self;
+}
+
+impl Area for Shape {
+    fn area(&self) -> u32 {
+        match *self {
+            Shape::Point => 0,
+            Shape::Circle(r) => 3 * r * r,
+            Shape::Rect(w, h) => w * h,
+        }
+    }
+    fn unit() -> Shape { Shape::Rect(1, 1) }
+}
+
+#[rustc_mir]
+fn make_shapes(n: u32) -> (Shape, Shape, Shape) {
+    (Shape::Point, Shape::Circle(n), Shape::Rect(n, n + 1))
+}
+
+#[rustc_mir]
+fn areas(shapes: &[Shape]) -> u32 {
+    let mut total = 0;
+    for shape in shapes {
+        total += shape.area();
+    }
+    total
+}
+
+#[rustc_mir]
+fn unit_area<T: Area>() -> u32 {
+    T::unit().area()
+}
+
+#[rustc_mir]
+fn pair(a: u8, b: u64) -> Pair {
+    Pair(a, b)
+}
+
+#[rustc_mir]
+fn casts(x: i32, s: Shape) -> (u8, f64, i64, usize) {
+    (x as u8, x as f64, -(x as i64), s.area() as usize)
+}
+
+#[rustc_mir]
+fn call_closure(n: u32) -> u32 {
+    let k = 3;
+    let f = |x: u32| x * k + n;
+    f(2) + apply(&f, 1)
+}
+
+fn apply<F: Fn(u32) -> u32>(f: &F, x: u32) -> u32 {
+    f(x)
+}
+
+#[rustc_mir]
+fn transmute_and_size(x: u32) -> (f32, usize) {
+    (unsafe { std::mem::transmute(x) }, std::mem::size_of::<Pair>())
+}
+
+fn main() {
+    let (a, b, c) = make_shapes(2);
+    assert_eq!((&a, &b, &c), (&Shape::Point, &Shape::Circle(2), &Shape::Rect(2, 3)));
+    assert_eq!(areas(&[a, b, c]), 18);
+    assert_eq!(unit_area::<Shape>(), 1);
+    assert_eq!(pair(1, 2), Pair(1, 2));
+    assert_eq!(casts(300, Shape::Circle(1)), (44, 300.0, -300, 3));
+    assert_eq!(call_closure(1), 11);
+    assert_eq!(transmute_and_size(0x3f800000), (1.0, std::mem::size_of::<Pair>()));
+}
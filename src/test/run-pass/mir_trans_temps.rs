@@ -0,0 +1,51 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z orbit
+
+// Check that MIR trans handles temps which are assigned in several places,
+// such as the value of an `if` passed straight to a call, and associated
+// consts selected through the substs of the reference.
+
+#![feature(associated_consts)]
+
+trait Tag {
+    const TAG: u32;
+}
+
+struct A;
+struct B;
+
+impl Tag for A {
+    const TAG: u32 = 1;
+}
+
+impl Tag for B {
+    const TAG: u32 = 2;
+}
+
+fn id(x: u32) -> u32 {
+    x
+}
+
+fn pick(c: bool) -> u32 {
+    id(if c { 1 } else { 2 })
+}
+
+fn tag<T: Tag>() -> u32 {
+    T::TAG
+}
+
+fn main() {
+    assert_eq!(pick(true), 1);
+    assert_eq!(pick(false), 2);
+    assert_eq!(tag::<A>(), 1);
+    assert_eq!(tag::<B>(), 2);
+}
@@ -0,0 +1,53 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z orbit
+
+// Check that values are dropped when a MIR-translated fn unwinds, both
+// out of a call and out of a failed bounds check.
+
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+use std::thread;
+
+static DROPS: AtomicUsize = ATOMIC_USIZE_INIT;
+
+struct Noisy;
+
+impl Drop for Noisy {
+    fn drop(&mut self) {
+        DROPS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn fails() {
+    panic!("boom");
+}
+
+fn call_then_panic() {
+    let _a = Noisy;
+    fails();
+}
+
+fn index_out_of_bounds(i: usize) -> u8 {
+    let _a = Noisy;
+    let v = [1, 2, 3];
+    v[i]
+}
+
+fn main() {
+    assert!(thread::spawn(call_then_panic).join().is_err());
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+
+    assert!(thread::spawn(|| index_out_of_bounds(7)).join().is_err());
+    assert_eq!(DROPS.load(Ordering::SeqCst), 2);
+
+    assert_eq!(index_out_of_bounds(2), 3);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 3);
+}
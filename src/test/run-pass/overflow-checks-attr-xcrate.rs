@@ -0,0 +1,29 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:overflow_checks_attr.rs
+// compile-flags: -C debug-assertions
+
+// Check that fns inlined from another crate keep the `#[overflow_checks]`
+// setting of the impls and modules enclosing them there.
+
+extern crate overflow_checks_attr;
+
+use overflow_checks_attr::{Wrapping, hot, add_default};
+use std::thread;
+
+fn main() {
+    assert_eq!(Wrapping.add(255, 1), 0);
+    assert_eq!(hot::shl(1u32, 33), 2);
+    assert_eq!(hot::sub(3, 2), 1);
+
+    assert!(thread::spawn(|| hot::sub(2, 3)).join().is_err());
+    assert!(thread::spawn(|| add_default(255, 1)).join().is_err());
+}
@@ -0,0 +1,73 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -C debug-assertions
+
+// Check that `#[overflow_checks]` on fns, impls and modules overrides
+// `-C debug-assertions`, with the innermost attribute winning. Closures
+// take the setting of the fn they are defined in.
+
+#![feature(overflow_checks)]
+
+use std::thread;
+
+#[overflow_checks(off)]
+fn add_off(a: u8, b: u8) -> u8 {
+    a + b
+}
+
+fn add_default(a: u8, b: u8) -> u8 {
+    a + b
+}
+
+struct Wrapping;
+
+#[overflow_checks(off)]
+impl Wrapping {
+    fn mul(&self, a: i32, b: i32) -> i32 {
+        let f = |a: i32, b: i32| a * b;
+        f(a, b)
+    }
+}
+
+#[overflow_checks(off)]
+mod hot {
+    pub fn neg(a: i8) -> i8 {
+        -a
+    }
+
+    pub fn shl(a: u32, b: u32) -> u32 {
+        a << b
+    }
+
+    #[overflow_checks(on)]
+    pub fn sub(a: u64, b: u64) -> u64 {
+        let mut c = a;
+        c -= b;
+        c
+    }
+
+    #[overflow_checks(on)]
+    pub fn add_with(a: u8) -> Box<Fn(u8) -> u8> {
+        Box::new(move |b| a + b)
+    }
+}
+
+fn main() {
+    assert_eq!(add_off(255, 1), 0);
+    assert_eq!(Wrapping.mul(i32::max_value(), 2), -2);
+    assert_eq!(hot::neg(-128), -128);
+    assert_eq!(hot::shl(1, 33), 2);
+    assert_eq!(hot::sub(3, 2), 1);
+
+    assert!(thread::spawn(|| add_default(255, 1)).join().is_err());
+    assert!(thread::spawn(|| hot::sub(2, 3)).join().is_err());
+    assert!(thread::spawn(|| hot::add_with(255)(1)).join().is_err());
+}